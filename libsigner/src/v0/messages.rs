@@ -26,6 +26,7 @@
 use std::fmt::{Debug, Display};
 use std::io::{Read, Write};
 use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::ops::Range;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::Sender;
 use std::sync::Arc;
@@ -43,12 +44,15 @@ use clarity::util::secp256k1::MessageSignature;
 use clarity::vm::types::serialization::SerializationError;
 use clarity::vm::types::QualifiedContractIdentifier;
 use hashbrown::{HashMap, HashSet};
+use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use stacks_common::codec::{
     read_next, read_next_at_most, read_next_exact, write_next, Error as CodecError,
     StacksMessageCodec,
 };
 use stacks_common::consts::SIGNER_SLOTS_PER_USER;
+use stacks_common::types::chainstate::StacksPublicKey;
+use stacks_common::types::StacksPublicKeyBuffer;
 use stacks_common::util::hash::Sha512Trunc256Sum;
 use tiny_http::{
     Method as HttpMethod, Request as HttpRequest, Response as HttpResponse, Server as HttpServer,
@@ -64,7 +68,9 @@ MessageSlotID {
     /// Block Proposal message from miners
     BlockProposal = 0,
     /// Block Response message from signers
-    BlockResponse = 1
+    BlockResponse = 1,
+    /// Capabilities message from signers
+    Capabilities = 2
 });
 
 define_u8_enum!(
@@ -73,9 +79,36 @@ SignerMessageTypePrefix {
     /// Block Proposal message from miners
     BlockProposal = 0,
     /// Block Response message from signers
-    BlockResponse = 1
+    BlockResponse = 1,
+    /// Capabilities message from signers
+    Capabilities = 2
 });
 
+/// Maximum length of the software version string in a `Capabilities` message
+pub const MAX_CAPABILITIES_VERSION_LEN: u32 = 256;
+/// Maximum number of features in a `Capabilities` message
+pub const MAX_CAPABILITIES_FEATURES: u32 = 64;
+/// Maximum length of a single feature name in a `Capabilities` message
+pub const MAX_CAPABILITIES_FEATURE_LEN: u32 = 64;
+
+/// StackerDB contract IDs, by reward cycle parity and message slot
+type SlotContractIds = HashMap<(u64, MessageSlotID), QualifiedContractIdentifier>;
+
+lazy_static! {
+    /// The StackerDB contract for each reward cycle parity and message slot.  Signer contracts
+    /// alternate between cycle parities, so these are derived once per network: testnet at
+    /// index 0, and mainnet at index 1.
+    static ref SIGNERS_DB_CONTRACT_IDS: [SlotContractIds; 2] = [false, true].map(|mainnet| {
+        let mut contract_ids = HashMap::new();
+        for parity in 0..2 {
+            for slot_id in MessageSlotID::ALL {
+                contract_ids.insert((parity, *slot_id), slot_id.stacker_db_contract(mainnet, parity));
+            }
+        }
+        contract_ids
+    });
+}
+
 #[cfg_attr(test, mutants::skip)]
 impl MessageSlotID {
     /// Return the StackerDB contract corresponding to messages of this type
@@ -91,6 +124,24 @@ impl MessageSlotID {
     pub fn to_u32(self) -> u32 {
         self.to_u8().into()
     }
+
+    /// Return the StackerDB contract for each message slot in each of the given reward cycles,
+    /// ordered by cycle and then by slot.  The contract IDs are only derived once per network
+    /// (see `SIGNERS_DB_CONTRACT_IDS`), and reused across calls.
+    pub fn signers_db_contract_ids(
+        reward_cycles: Range<u64>,
+        mainnet: bool,
+    ) -> Vec<(u64, MessageSlotID, QualifiedContractIdentifier)> {
+        let contract_ids = &SIGNERS_DB_CONTRACT_IDS[usize::from(mainnet)];
+        let mut result = vec![];
+        for reward_cycle in reward_cycles {
+            for slot_id in MessageSlotID::ALL {
+                let contract_id = contract_ids[&(reward_cycle % 2, *slot_id)].clone();
+                result.push((reward_cycle, *slot_id, contract_id));
+            }
+        }
+        result
+    }
 }
 
 #[cfg_attr(test, mutants::skip)]
@@ -115,6 +166,7 @@ impl From<&SignerMessage> for SignerMessageTypePrefix {
         match message {
             SignerMessage::BlockProposal(_) => SignerMessageTypePrefix::BlockProposal,
             SignerMessage::BlockResponse(_) => SignerMessageTypePrefix::BlockResponse,
+            SignerMessage::Capabilities(_) => SignerMessageTypePrefix::Capabilities,
         }
     }
 }
@@ -126,6 +178,8 @@ pub enum SignerMessage {
     BlockProposal(BlockProposal),
     /// The block response from signers for miners to observe
     BlockResponse(BlockResponse),
+    /// The software version and features of a signer, for the rest of the set to observe
+    Capabilities(Capabilities),
 }
 
 impl Debug for SignerMessage {
@@ -134,6 +188,7 @@ impl Debug for SignerMessage {
         match self {
             Self::BlockProposal(b) => Debug::fmt(b, f),
             Self::BlockResponse(b) => Debug::fmt(b, f),
+            Self::Capabilities(c) => Debug::fmt(c, f),
         }
     }
 }
@@ -145,6 +200,34 @@ impl SignerMessage {
         match self {
             Self::BlockProposal(_) => MessageSlotID::BlockProposal,
             Self::BlockResponse(_) => MessageSlotID::BlockResponse,
+            Self::Capabilities(_) => MessageSlotID::Capabilities,
+        }
+    }
+
+    /// The block proposal in this message, if it is one
+    pub fn as_block_proposal(&self) -> Option<&BlockProposal> {
+        match self {
+            Self::BlockProposal(proposal) => Some(proposal),
+            _ => None,
+        }
+    }
+
+    /// The block response in this message, if it is one
+    pub fn as_block_response(&self) -> Option<&BlockResponse> {
+        match self {
+            Self::BlockResponse(response) => Some(response),
+            _ => None,
+        }
+    }
+
+    /// Every message signature carried by this message, for auditing: the miner's signature on
+    /// a proposed block, and the signer's signature on an accepted block.  Signatures inside
+    /// the proposed block's transactions are not included.
+    pub fn embedded_signatures(&self) -> Vec<MessageSignature> {
+        match self {
+            Self::BlockProposal(proposal) => vec![proposal.block.header.miner_signature.clone()],
+            Self::BlockResponse(BlockResponse::Accepted((_, sig))) => vec![sig.clone()],
+            Self::BlockResponse(BlockResponse::Rejected(_)) | Self::Capabilities(_) => vec![],
         }
     }
 }
@@ -159,6 +242,9 @@ impl StacksMessageCodec for SignerMessage {
             SignerMessage::BlockResponse(block_response) => {
                 write_next(fd, block_response)?;
             }
+            SignerMessage::Capabilities(capabilities) => {
+                write_next(fd, capabilities)?;
+            }
         };
         Ok(())
     }
@@ -169,18 +255,144 @@ impl StacksMessageCodec for SignerMessage {
         let type_prefix = SignerMessageTypePrefix::try_from(type_prefix_byte)?;
         let message = match type_prefix {
             SignerMessageTypePrefix::BlockProposal => {
-                let block_proposal = read_next::<BlockProposal, _>(fd)?;
+                let block_proposal = within_type(
+                    "SignerMessage::BlockProposal",
+                    read_next::<BlockProposal, _>(fd),
+                )?;
                 SignerMessage::BlockProposal(block_proposal)
             }
             SignerMessageTypePrefix::BlockResponse => {
-                let block_response = read_next::<BlockResponse, _>(fd)?;
+                let block_response = within_type(
+                    "SignerMessage::BlockResponse",
+                    read_next::<BlockResponse, _>(fd),
+                )?;
                 SignerMessage::BlockResponse(block_response)
             }
+            SignerMessageTypePrefix::Capabilities => {
+                let capabilities = within_type(
+                    "SignerMessage::Capabilities",
+                    read_next::<Capabilities, _>(fd),
+                )?;
+                SignerMessage::Capabilities(capabilities)
+            }
         };
         Ok(message)
     }
 }
 
+/// The software version and supported features of a signer, so that the signer set can tell
+/// when a feature is safe to turn on
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Capabilities {
+    /// The signer's software version
+    pub version: String,
+    /// The names of the features the signer supports
+    pub features: Vec<String>,
+    /// The public key of the signer
+    pub signer_pubkey: StacksPublicKey,
+}
+
+impl Capabilities {
+    /// Does this signer support the given feature?
+    pub fn supports(&self, feature: &str) -> bool {
+        self.features.iter().any(|f| f == feature)
+    }
+}
+
+/// Read a length-prefixed UTF-8 string of at most `max_len` bytes
+fn read_bounded_string<R: Read>(fd: &mut R, max_len: u32) -> Result<String, CodecError> {
+    let bytes = read_next_at_most::<_, u8>(fd, max_len)?;
+    String::from_utf8(bytes)
+        .map_err(|e| CodecError::DeserializeError(format!("Failed to decode string: {:?}", &e)))
+}
+
+/// Read a length-prefixed list of at most `MAX_CAPABILITIES_FEATURES` feature names
+fn read_capabilities_features<R: Read>(fd: &mut R) -> Result<Vec<String>, CodecError> {
+    let num_features = read_next::<u32, _>(fd)?;
+    if num_features > MAX_CAPABILITIES_FEATURES {
+        return Err(CodecError::DeserializeError(format!(
+            "Too many features in capabilities: {num_features}"
+        )));
+    }
+    (0..num_features)
+        .map(|_| read_bounded_string(fd, MAX_CAPABILITIES_FEATURE_LEN))
+        .collect()
+}
+
+/// Prefix a `DeserializeError` with the name of the field that was being read, so that the
+/// errors of nested types read as a path to the failing field, e.g.
+/// "SignerMessage::BlockResponse -> BlockRejection -> reason_code: <inner error>".
+/// Other errors (e.g. running out of bytes) are passed through as-is.
+pub(crate) fn within_field<T>(field: &str, result: Result<T, CodecError>) -> Result<T, CodecError> {
+    result.map_err(|e| match e {
+        CodecError::DeserializeError(msg) => {
+            CodecError::DeserializeError(format!("{field}: {msg}"))
+        }
+        e => e,
+    })
+}
+
+/// Prefix a `DeserializeError` with the name of the type that was being read.  See
+/// `within_field()`.
+pub(crate) fn within_type<T>(name: &str, result: Result<T, CodecError>) -> Result<T, CodecError> {
+    result.map_err(|e| match e {
+        CodecError::DeserializeError(msg) => {
+            CodecError::DeserializeError(format!("{name} -> {msg}"))
+        }
+        e => e,
+    })
+}
+
+impl StacksMessageCodec for Capabilities {
+    fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), CodecError> {
+        if self.version.len() > MAX_CAPABILITIES_VERSION_LEN as usize {
+            return Err(CodecError::SerializeError(
+                "Capabilities version string is too long".into(),
+            ));
+        }
+        if self.features.len() > MAX_CAPABILITIES_FEATURES as usize {
+            return Err(CodecError::SerializeError(
+                "Too many features in capabilities".into(),
+            ));
+        }
+        write_next(fd, &self.version.as_bytes().to_vec())?;
+        write_next(fd, &(self.features.len() as u32))?;
+        for feature in self.features.iter() {
+            if feature.len() > MAX_CAPABILITIES_FEATURE_LEN as usize {
+                return Err(CodecError::SerializeError(format!(
+                    "Capabilities feature name is too long: {feature}"
+                )));
+            }
+            write_next(fd, &feature.as_bytes().to_vec())?;
+        }
+        write_next(
+            fd,
+            &StacksPublicKeyBuffer::from_public_key(&self.signer_pubkey),
+        )?;
+        Ok(())
+    }
+
+    fn consensus_deserialize<R: Read>(fd: &mut R) -> Result<Self, CodecError> {
+        let version = within_field(
+            "version",
+            read_bounded_string(fd, MAX_CAPABILITIES_VERSION_LEN),
+        )?;
+        let features = within_field("features", read_capabilities_features(fd))?;
+        let signer_pubkey = within_field(
+            "signer_pubkey",
+            read_next::<StacksPublicKeyBuffer, _>(fd).and_then(|buf| {
+                buf.to_public_key()
+                    .map_err(|e| CodecError::DeserializeError(e.into()))
+            }),
+        )?;
+        Ok(Self {
+            version,
+            features,
+            signer_pubkey,
+        })
+    }
+}
+
 /// Work around for the fact that a lot of the structs being desierialized are not defined in messages.rs
 pub trait StacksMessageCodecExtensions: Sized {
     /// Serialize the struct to the provided writer
@@ -195,7 +407,9 @@ RejectCodeTypePrefix {
     /// The block was rejected due to validation issues
     ValidationFailed = 0,
     /// The block was rejected due to connectivity issues with the signer
-    ConnectivityIssues = 1
+    ConnectivityIssues = 1,
+    /// The block was rejected because the signer's chain tip is behind the miner's
+    StaleChainTip = 5
 });
 
 impl TryFrom<u8> for RejectCodeTypePrefix {
@@ -212,6 +426,7 @@ impl From<&RejectCode> for RejectCodeTypePrefix {
         match reject_code {
             RejectCode::ValidationFailed(_) => RejectCodeTypePrefix::ValidationFailed,
             RejectCode::ConnectivityIssues => RejectCodeTypePrefix::ConnectivityIssues,
+            RejectCode::StaleChainTip(_) => RejectCodeTypePrefix::StaleChainTip,
         }
     }
 }
@@ -223,6 +438,17 @@ pub enum RejectCode {
     ValidationFailed(ValidateRejectCode),
     /// The block was rejected due to connectivity issues with the signer
     ConnectivityIssues,
+    /// The block was rejected because the signer's node has not caught up to the miner's chain
+    /// tip.  Carries the Stacks tip height that the signer observed.
+    StaleChainTip(u64),
+}
+
+impl RejectCode {
+    /// The reject code types this version of the signer understands, so that consumers can tell
+    /// a code they have no label for apart from one that is unknown to this signer
+    pub fn all_known() -> Vec<RejectCodeTypePrefix> {
+        RejectCodeTypePrefix::ALL.to_vec()
+    }
 }
 
 define_u8_enum!(
@@ -294,6 +520,29 @@ impl BlockResponse {
     pub fn rejected(hash: Sha512Trunc256Sum, reject_code: RejectCode) -> Self {
         Self::Rejected(BlockRejection::new(hash, reject_code))
     }
+
+    /// Recover the public key of the signer that accepted the block, from its signature over
+    /// the block's signer signature hash.  Rejections carry no signature, so this fails for them.
+    pub fn recover_signer(&self) -> Result<StacksPublicKey, String> {
+        match self {
+            Self::Accepted((hash, sig)) => {
+                StacksPublicKey::recover_to_pubkey(hash.as_bytes(), sig).map_err(|e| e.to_string())
+            }
+            Self::Rejected(_) => Err("Cannot recover the signer of a rejected block".into()),
+        }
+    }
+
+    /// Get the node's validation reject code, if this is a rejection because the block failed
+    /// validation
+    pub fn validation_reject_code(&self) -> Option<ValidateRejectCode> {
+        match self {
+            Self::Rejected(BlockRejection {
+                reason_code: RejectCode::ValidationFailed(code),
+                ..
+            }) => Some(*code),
+            _ => None,
+        }
+    }
 }
 
 impl StacksMessageCodec for BlockResponse {
@@ -316,12 +565,15 @@ impl StacksMessageCodec for BlockResponse {
         let type_prefix = BlockResponseTypePrefix::try_from(type_prefix_byte)?;
         let response = match type_prefix {
             BlockResponseTypePrefix::Accepted => {
-                let hash = read_next::<Sha512Trunc256Sum, _>(fd)?;
-                let sig = read_next::<MessageSignature, _>(fd)?;
+                let hash = within_field(
+                    "signer_signature_hash",
+                    read_next::<Sha512Trunc256Sum, _>(fd),
+                )?;
+                let sig = within_field("signature", read_next::<MessageSignature, _>(fd))?;
                 BlockResponse::Accepted((hash, sig))
             }
             BlockResponseTypePrefix::Rejected => {
-                let rejection = read_next::<BlockRejection, _>(fd)?;
+                let rejection = within_type("BlockRejection", read_next::<BlockRejection, _>(fd))?;
                 BlockResponse::Rejected(rejection)
             }
         };
@@ -351,6 +603,50 @@ impl BlockRejection {
     }
 }
 
+/// Summarize a group of rejections (e.g. of the same block by many signers) as one reason code
+/// and reason.  The code is the one given most often, and the reason is the one given most often
+/// with that code.  Ties go to whichever came first.
+/// Returns None if there are no rejections, since an empty group has no reason to give.
+pub fn canonical_group_reason(rejections: &[BlockRejection]) -> Option<(RejectCode, String)> {
+    // RejectCode isn't hashable, and groups are only as big as the signer set
+    let mut code_counts: Vec<(&RejectCode, usize)> = vec![];
+    for rejection in rejections.iter() {
+        match code_counts
+            .iter_mut()
+            .find(|(code, _)| *code == &rejection.reason_code)
+        {
+            Some((_, count)) => *count += 1,
+            None => code_counts.push((&rejection.reason_code, 1)),
+        }
+    }
+    // `max_by_key()` picks the last of equals, so search from the back to get the first
+    let (modal_code, _) = code_counts
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)?;
+
+    let mut reason_counts: Vec<(&str, usize)> = vec![];
+    for rejection in rejections
+        .iter()
+        .filter(|rejection| &rejection.reason_code == modal_code)
+    {
+        match reason_counts
+            .iter_mut()
+            .find(|(reason, _)| *reason == rejection.reason.as_str())
+        {
+            Some((_, count)) => *count += 1,
+            None => reason_counts.push((rejection.reason.as_str(), 1)),
+        }
+    }
+    let (modal_reason, _) = reason_counts
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .expect("infallible: the modal reason code was given with some reason");
+
+    Some((modal_code.clone(), modal_reason.to_string()))
+}
+
 impl StacksMessageCodec for BlockRejection {
     fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), CodecError> {
         write_next(fd, &self.reason.as_bytes().to_vec())?;
@@ -360,12 +656,18 @@ impl StacksMessageCodec for BlockRejection {
     }
 
     fn consensus_deserialize<R: Read>(fd: &mut R) -> Result<Self, CodecError> {
-        let reason_bytes = read_next::<Vec<u8>, _>(fd)?;
-        let reason = String::from_utf8(reason_bytes).map_err(|e| {
-            CodecError::DeserializeError(format!("Failed to decode reason string: {:?}", &e))
-        })?;
-        let reason_code = read_next::<RejectCode, _>(fd)?;
-        let signer_signature_hash = read_next::<Sha512Trunc256Sum, _>(fd)?;
+        let reason_bytes = within_field("reason", read_next::<Vec<u8>, _>(fd))?;
+        let reason = within_field(
+            "reason",
+            String::from_utf8(reason_bytes).map_err(|e| {
+                CodecError::DeserializeError(format!("Failed to decode reason string: {:?}", &e))
+            }),
+        )?;
+        let reason_code = within_field("reason_code", read_next::<RejectCode, _>(fd))?;
+        let signer_signature_hash = within_field(
+            "signer_signature_hash",
+            read_next::<Sha512Trunc256Sum, _>(fd),
+        )?;
         Ok(Self {
             reason,
             reason_code,
@@ -393,6 +695,7 @@ impl StacksMessageCodec for RejectCode {
             RejectCode::ConnectivityIssues => {
                 // No additional data to serialize / deserialize
             }
+            RejectCode::StaleChainTip(stacks_tip_height) => write_next(fd, stacks_tip_height)?,
         };
         Ok(())
     }
@@ -410,6 +713,9 @@ impl StacksMessageCodec for RejectCode {
                 })?,
             ),
             RejectCodeTypePrefix::ConnectivityIssues => RejectCode::ConnectivityIssues,
+            RejectCodeTypePrefix::StaleChainTip => {
+                RejectCode::StaleChainTip(read_next::<u64, _>(fd)?)
+            }
         };
         Ok(code)
     }
@@ -424,6 +730,11 @@ impl std::fmt::Display for RejectCode {
                 f,
                 "The block was rejected due to connectivity issues with the signer."
             ),
+            RejectCode::StaleChainTip(stacks_tip_height) => write!(
+                f,
+                "The block was rejected because the signer's chain tip (height {}) is stale.",
+                stacks_tip_height
+            ),
         }
     }
 }
@@ -462,6 +773,7 @@ mod test {
     use stacks_common::bitvec::BitVec;
     use stacks_common::consts::CHAIN_ID_TESTNET;
     use stacks_common::types::chainstate::StacksPrivateKey;
+    use stacks_common::types::PrivateKey;
 
     use super::{StacksMessageCodecExtensions, *};
 
@@ -476,6 +788,33 @@ mod test {
         );
     }
 
+    #[test]
+    fn signers_db_contract_ids_match_per_cycle() {
+        for mainnet in [true, false] {
+            let contract_ids = MessageSlotID::signers_db_contract_ids(10..15, mainnet);
+            assert_eq!(contract_ids.len(), 5 * MessageSlotID::ALL.len());
+
+            let mut expected = vec![];
+            for reward_cycle in 10..15 {
+                for slot_id in MessageSlotID::ALL {
+                    expected.push((
+                        reward_cycle,
+                        *slot_id,
+                        slot_id.stacker_db_contract(mainnet, reward_cycle),
+                    ));
+                }
+            }
+            assert_eq!(contract_ids, expected);
+        }
+        assert!(MessageSlotID::signers_db_contract_ids(3..3, false).is_empty());
+
+        // the cached contracts are kept apart by network
+        assert_ne!(
+            MessageSlotID::signers_db_contract_ids(10..11, true),
+            MessageSlotID::signers_db_contract_ids(10..11, false)
+        );
+    }
+
     #[test]
     fn serde_reject_code() {
         let code = RejectCode::ValidationFailed(ValidateRejectCode::InvalidBlock);
@@ -491,6 +830,28 @@ mod test {
         assert_eq!(code, deserialized_code);
     }
 
+    #[test]
+    fn reject_code_all_known_is_exhaustive() {
+        let known = RejectCode::all_known();
+        assert_eq!(known.len(), RejectCodeTypePrefix::ALL.len());
+        for prefix in RejectCodeTypePrefix::ALL {
+            // adding a prefix variant will fail to compile here until it is accounted for
+            match prefix {
+                RejectCodeTypePrefix::ValidationFailed
+                | RejectCodeTypePrefix::ConnectivityIssues
+                | RejectCodeTypePrefix::StaleChainTip => {}
+            }
+            assert!(known.contains(prefix));
+        }
+        for code in [
+            RejectCode::ValidationFailed(ValidateRejectCode::InvalidBlock),
+            RejectCode::ConnectivityIssues,
+            RejectCode::StaleChainTip(0),
+        ] {
+            assert!(known.contains(&RejectCodeTypePrefix::from(&code)));
+        }
+    }
+
     #[test]
     fn serde_block_rejection() {
         let rejection = BlockRejection::new(
@@ -508,12 +869,92 @@ mod test {
         let deserialized_rejection = read_next::<BlockRejection, _>(&mut &serialized_rejection[..])
             .expect("Failed to deserialize BlockRejection");
         assert_eq!(rejection, deserialized_rejection);
+
+        let rejection = BlockRejection::new(
+            Sha512Trunc256Sum([2u8; 32]),
+            RejectCode::StaleChainTip(123456),
+        );
+        let serialized_rejection = rejection.serialize_to_vec();
+        let deserialized_rejection = read_next::<BlockRejection, _>(&mut &serialized_rejection[..])
+            .expect("Failed to deserialize BlockRejection");
+        assert_eq!(rejection, deserialized_rejection);
+    }
+
+    #[test]
+    fn serde_reject_code_encoding() {
+        // the tip height follows the prefix byte
+        let code = RejectCode::StaleChainTip(0x0102030405060708);
+        assert_eq!(
+            code.serialize_to_vec(),
+            vec![5, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08]
+        );
+        assert!(read_next::<RejectCode, _>(&mut &[5u8, 0, 0, 0][..]).is_err());
+
+        // older codes are encoded as before, and don't consume the bytes after them
+        let cases = [
+            (
+                vec![0u8, 2u8],
+                RejectCode::ValidationFailed(ValidateRejectCode::InvalidBlock),
+            ),
+            (vec![1u8], RejectCode::ConnectivityIssues),
+        ];
+        for (bytes, code) in cases {
+            assert_eq!(code.serialize_to_vec(), bytes);
+            let mut with_trailer = bytes.clone();
+            with_trailer.extend_from_slice(&[0xff; 8]);
+            let mut reader = &with_trailer[..];
+            assert_eq!(read_next::<RejectCode, _>(&mut reader).unwrap(), code);
+            assert_eq!(reader, &[0xff; 8][..]);
+        }
+    }
+
+    #[test]
+    fn canonical_group_reason_picks_modal_code_and_reason() {
+        let hash = Sha512Trunc256Sum([0u8; 32]);
+        let rejection = |reason_code: RejectCode, reason: &str| BlockRejection {
+            reason: reason.to_string(),
+            reason_code,
+            signer_signature_hash: hash,
+        };
+        let invalid_block = RejectCode::ValidationFailed(ValidateRejectCode::InvalidBlock);
+        let bad_transaction = RejectCode::ValidationFailed(ValidateRejectCode::BadTransaction);
+
+        let rejections = vec![
+            rejection(RejectCode::ConnectivityIssues, "timed out"),
+            rejection(invalid_block.clone(), "invalid block: bad state root"),
+            rejection(invalid_block.clone(), "invalid block"),
+            rejection(RejectCode::ConnectivityIssues, "timed out"),
+            rejection(invalid_block.clone(), "invalid block"),
+            rejection(bad_transaction.clone(), "bad transaction"),
+            rejection(invalid_block.clone(), "Invalid block: state root mismatch"),
+        ];
+        assert_eq!(
+            canonical_group_reason(&rejections),
+            Some((invalid_block.clone(), "invalid block".to_string()))
+        );
+
+        // ties go to whichever came first
+        let rejections = vec![
+            rejection(bad_transaction.clone(), "bad nonce"),
+            rejection(invalid_block.clone(), "invalid block"),
+            rejection(bad_transaction.clone(), "bad fee"),
+            rejection(invalid_block.clone(), "invalid block"),
+        ];
+        assert_eq!(
+            canonical_group_reason(&rejections),
+            Some((bad_transaction, "bad nonce".to_string()))
+        );
+    }
+
+    #[test]
+    fn canonical_group_reason_should_be_none_for_empty_group() {
+        assert_eq!(canonical_group_reason(&[]), None);
     }
 
     #[test]
     fn serde_block_response() {
         let response =
-            BlockResponse::Accepted((Sha512Trunc256Sum([0u8; 32]), MessageSignature::empty()));
+            BlockResponse::accepted(Sha512Trunc256Sum([0u8; 32]), MessageSignature::empty());
         let serialized_response = response.serialize_to_vec();
         let deserialized_response = read_next::<BlockResponse, _>(&mut &serialized_response[..])
             .expect("Failed to deserialize BlockResponse");
@@ -529,12 +970,36 @@ mod test {
         assert_eq!(response, deserialized_response);
     }
 
+    #[test]
+    fn deserialize_error_should_name_failing_field() {
+        let rejection =
+            BlockRejection::new(Sha512Trunc256Sum([5u8; 32]), RejectCode::ConnectivityIssues);
+        let signer_message =
+            SignerMessage::BlockResponse(BlockResponse::Rejected(rejection.clone()));
+        let mut serialized_signer_message = signer_message.serialize_to_vec();
+        // message type prefix, response type prefix, then the length-prefixed reason
+        let reason_code_offset = 1 + 1 + 4 + rejection.reason.len();
+        assert_eq!(
+            serialized_signer_message[reason_code_offset],
+            RejectCodeTypePrefix::ConnectivityIssues as u8
+        );
+        serialized_signer_message[reason_code_offset] = 0xff;
+
+        match read_next::<SignerMessage, _>(&mut &serialized_signer_message[..]) {
+            Err(CodecError::DeserializeError(msg)) => assert_eq!(
+                msg,
+                "SignerMessage::BlockResponse -> BlockRejection -> reason_code: Unknown reject code type prefix: 255"
+            ),
+            res => panic!("Expected a deserialize error, got {res:?}"),
+        }
+    }
+
     #[test]
     fn serde_signer_message() {
-        let signer_message = SignerMessage::BlockResponse(BlockResponse::Accepted((
+        let signer_message = SignerMessage::BlockResponse(BlockResponse::accepted(
             Sha512Trunc256Sum([2u8; 32]),
             MessageSignature::empty(),
-        )));
+        ));
         let serialized_signer_message = signer_message.serialize_to_vec();
         let deserialized_signer_message =
             read_next::<SignerMessage, _>(&mut &serialized_signer_message[..])
@@ -569,4 +1034,168 @@ mod test {
                 .expect("Failed to deserialize SignerMessage");
         assert_eq!(signer_message, deserialized_signer_message);
     }
+
+    #[test]
+    fn signer_message_accessors() {
+        let block_response =
+            BlockResponse::accepted(Sha512Trunc256Sum([2u8; 32]), MessageSignature::empty());
+        let block_proposal = BlockProposal {
+            block: NakamotoBlock {
+                header: NakamotoBlockHeader::empty(),
+                txs: vec![],
+            },
+            burn_height: thread_rng().next_u64(),
+            reward_cycle: thread_rng().next_u64(),
+        };
+
+        let signer_message = SignerMessage::BlockResponse(block_response.clone());
+        assert_eq!(signer_message.as_block_response(), Some(&block_response));
+        assert!(signer_message.as_block_proposal().is_none());
+
+        let signer_message = SignerMessage::BlockProposal(block_proposal.clone());
+        assert_eq!(signer_message.as_block_proposal(), Some(&block_proposal));
+        assert!(signer_message.as_block_response().is_none());
+    }
+
+    #[test]
+    fn embedded_signatures_should_cover_every_variant() {
+        let private_key = StacksPrivateKey::new();
+        let hash = Sha512Trunc256Sum([4u8; 32]);
+        let sig = private_key.sign(hash.as_bytes()).unwrap();
+
+        let signer_message =
+            SignerMessage::BlockResponse(BlockResponse::accepted(hash, sig.clone()));
+        assert_eq!(signer_message.embedded_signatures(), vec![sig.clone()]);
+
+        let signer_message = SignerMessage::BlockResponse(BlockResponse::rejected(
+            hash,
+            RejectCode::ConnectivityIssues,
+        ));
+        assert!(signer_message.embedded_signatures().is_empty());
+
+        let mut header = NakamotoBlockHeader::empty();
+        header.miner_signature = sig.clone();
+        let signer_message = SignerMessage::BlockProposal(BlockProposal {
+            block: NakamotoBlock {
+                header,
+                txs: vec![],
+            },
+            burn_height: thread_rng().next_u64(),
+            reward_cycle: thread_rng().next_u64(),
+        });
+        assert_eq!(signer_message.embedded_signatures(), vec![sig]);
+
+        let signer_message = SignerMessage::Capabilities(Capabilities {
+            version: "1.0.0".to_string(),
+            features: vec![],
+            signer_pubkey: StacksPublicKey::from_private(&private_key),
+        });
+        assert!(signer_message.embedded_signatures().is_empty());
+    }
+
+    #[test]
+    fn block_response_recover_signer() {
+        let private_key = StacksPrivateKey::new();
+        let hash = Sha512Trunc256Sum([3u8; 32]);
+        let sig = private_key.sign(hash.as_bytes()).unwrap();
+        let response = BlockResponse::accepted(hash, sig);
+        assert_eq!(
+            response.recover_signer().unwrap(),
+            StacksPublicKey::from_private(&private_key)
+        );
+
+        let other_key = StacksPrivateKey::new();
+        assert_ne!(
+            response.recover_signer().unwrap(),
+            StacksPublicKey::from_private(&other_key)
+        );
+
+        let response = BlockResponse::rejected(hash, RejectCode::ConnectivityIssues);
+        assert!(response.recover_signer().is_err());
+    }
+
+    #[test]
+    fn block_response_validation_reject_code() {
+        let hash = Sha512Trunc256Sum([3u8; 32]);
+        let response = BlockResponse::rejected(
+            hash,
+            RejectCode::ValidationFailed(ValidateRejectCode::UnknownParent),
+        );
+        assert_eq!(
+            response.validation_reject_code(),
+            Some(ValidateRejectCode::UnknownParent)
+        );
+
+        let response = BlockResponse::rejected(hash, RejectCode::ConnectivityIssues);
+        assert_eq!(response.validation_reject_code(), None);
+
+        let response = BlockResponse::accepted(hash, MessageSignature::empty());
+        assert_eq!(response.validation_reject_code(), None);
+    }
+
+    #[test]
+    fn serde_capabilities() {
+        let capabilities = Capabilities {
+            version: "signer-3.0.0.0.0".to_string(),
+            features: vec![
+                "conditional-block-responses".to_string(),
+                "mock-signing".to_string(),
+            ],
+            signer_pubkey: StacksPublicKey::from_private(&StacksPrivateKey::new()),
+        };
+        let signer_message = SignerMessage::Capabilities(capabilities.clone());
+        assert_eq!(signer_message.msg_id(), MessageSlotID::Capabilities);
+
+        let serialized_signer_message = signer_message.serialize_to_vec();
+        let deserialized_signer_message =
+            read_next::<SignerMessage, _>(&mut &serialized_signer_message[..])
+                .expect("Failed to deserialize SignerMessage");
+        assert_eq!(signer_message, deserialized_signer_message);
+
+        assert!(capabilities.supports("mock-signing"));
+        assert!(!capabilities.supports("mock"));
+
+        // no features is fine
+        let no_features = Capabilities {
+            features: vec![],
+            ..capabilities.clone()
+        };
+        let serialized = no_features.serialize_to_vec();
+        assert_eq!(
+            read_next::<Capabilities, _>(&mut &serialized[..]).unwrap(),
+            no_features
+        );
+        assert!(!no_features.supports("mock-signing"));
+
+        // oversized fields are refused in both directions
+        let too_many_features = Capabilities {
+            features: (0..=MAX_CAPABILITIES_FEATURES)
+                .map(|i| format!("feature-{i}"))
+                .collect(),
+            ..capabilities.clone()
+        };
+        let mut bytes = vec![];
+        assert!(too_many_features.consensus_serialize(&mut bytes).is_err());
+
+        let mut bytes = vec![];
+        write_next(&mut bytes, &capabilities.version.as_bytes().to_vec()).unwrap();
+        write_next(&mut bytes, &(MAX_CAPABILITIES_FEATURES + 1)).unwrap();
+        match read_next::<Capabilities, _>(&mut &bytes[..]) {
+            Err(CodecError::DeserializeError(msg)) => assert_eq!(
+                msg,
+                format!(
+                    "features: Too many features in capabilities: {}",
+                    MAX_CAPABILITIES_FEATURES + 1
+                )
+            ),
+            res => panic!("Expected a deserialize error, got {res:?}"),
+        }
+
+        let long_feature = Capabilities {
+            features: vec!["x".repeat(MAX_CAPABILITIES_FEATURE_LEN as usize + 1)],
+            ..capabilities
+        };
+        let mut bytes = vec![];
+        assert!(long_feature.consensus_serialize(&mut bytes).is_err());
+    }
 }
@@ -43,12 +43,14 @@ use clarity::vm::types::serialization::SerializationError;
 use clarity::vm::types::QualifiedContractIdentifier;
 use hashbrown::{HashMap, HashSet};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use stacks_common::codec::{
     read_next, read_next_at_most, read_next_exact, write_next, Error as CodecError,
-    StacksMessageCodec,
+    StacksMessageCodec, MAX_MESSAGE_LEN,
 };
 use stacks_common::consts::SIGNER_SLOTS_PER_USER;
-use stacks_common::util::hash::Sha512Trunc256Sum;
+use stacks_common::util::get_epoch_time_secs;
+use stacks_common::util::hash::{to_hex, Sha512Trunc256Sum};
 use tiny_http::{
     Method as HttpMethod, Request as HttpRequest, Response as HttpResponse, Server as HttpServer,
 };
@@ -64,6 +66,7 @@ use wsts::schnorr::ID;
 use wsts::state_machine::{signer, SignError};
 
 use crate::http::{decode_http_body, decode_http_request};
+use crate::v0::messages::{within_field, within_type};
 use crate::EventError;
 
 define_u8_enum!(
@@ -112,7 +115,9 @@ SignerMessageTypePrefix {
     /// The results of a successful DKG
     DkgResults = 3,
     /// The encrypted state of the signer to be persisted
-    EncryptedSignerState = 4
+    EncryptedSignerState = 4,
+    /// A checkpoint of the latest state of the signer's other slots
+    StateCheckpoint = 5
 });
 
 #[cfg_attr(test, mutants::skip)]
@@ -156,6 +161,7 @@ impl From<&SignerMessage> for SignerMessageTypePrefix {
             SignerMessage::Transactions(_) => SignerMessageTypePrefix::Transactions,
             SignerMessage::DkgResults { .. } => SignerMessageTypePrefix::DkgResults,
             SignerMessage::EncryptedSignerState(_) => SignerMessageTypePrefix::EncryptedSignerState,
+            SignerMessage::StateCheckpoint(_) => SignerMessageTypePrefix::StateCheckpoint,
         }
     }
 }
@@ -271,6 +277,8 @@ pub enum SignerMessage {
     },
     /// The encrypted state of the signer to be persisted
     EncryptedSignerState(Vec<u8>),
+    /// The latest state of the signer's other slots, so readers need not replay every version
+    StateCheckpoint(StateCheckpoint),
 }
 
 impl Debug for SignerMessage {
@@ -296,6 +304,7 @@ impl Debug for SignerMessage {
             Self::EncryptedSignerState(s) => {
                 f.debug_tuple("EncryptedSignerState").field(s).finish()
             }
+            Self::StateCheckpoint(c) => Debug::fmt(c, f),
         }
     }
 }
@@ -321,6 +330,75 @@ impl SignerMessage {
             Self::Transactions(_) => MessageSlotID::Transactions,
             Self::DkgResults { .. } => MessageSlotID::DkgResults,
             Self::EncryptedSignerState(_) => MessageSlotID::EncryptedSignerState,
+            // the signer's own persisted state, like its encrypted state
+            Self::StateCheckpoint(_) => MessageSlotID::EncryptedSignerState,
+        }
+    }
+
+    /// The chain ID embedded in this message, if it carries one.
+    /// Only transaction lists are tagged; the first transaction's chain ID is used.
+    pub fn chain_id(&self) -> Option<u32> {
+        match self {
+            Self::Transactions(txs) => txs.first().map(|tx| tx.chain_id),
+            _ => None,
+        }
+    }
+
+    /// A short identifier for this message, for correlating log lines across processes.
+    /// This is the message type, followed by the first few hex digits of the hash of the
+    /// message's consensus serialization (e.g. `BlockResponse:3f9a1c`), so equal messages always
+    /// get the same ID.
+    pub fn short_id(&self) -> String {
+        let hash = Sha512Trunc256Sum::from_data(&self.serialize_to_vec());
+        format!("{:?}:{}", self.msg_id(), &hash.to_hex()[..6])
+    }
+
+    /// A self-describing JSON rendering of this message, for logs and debugging.
+    /// Hashes, signatures and keys are hex-encoded, and enum codes are given by name.
+    /// This is not a wire format; use the consensus serialization for that.
+    pub fn to_debug_json(&self) -> serde_json::Value {
+        match self {
+            Self::BlockResponse(response) => json!({
+                "type": "BlockResponse",
+                "response": response.to_debug_json(),
+            }),
+            Self::Packet(packet) => json!({
+                "type": "Packet",
+                "msg_type": format!("{:?}", MessageTypePrefix::from(&packet.msg)),
+                "sig": to_hex(&packet.sig),
+            }),
+            Self::Transactions(txs) => json!({
+                "type": "Transactions",
+                "txids": txs.iter().map(|tx| tx.txid().to_hex()).collect::<Vec<_>>(),
+            }),
+            Self::DkgResults {
+                aggregate_key,
+                party_polynomials,
+            } => json!({
+                "type": "DkgResults",
+                "aggregate_key": to_hex(aggregate_key.compress().as_bytes()),
+                "party_ids": party_polynomials.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+            }),
+            Self::EncryptedSignerState(state) => json!({
+                "type": "EncryptedSignerState",
+                "state": to_hex(state),
+            }),
+            Self::StateCheckpoint(checkpoint) => json!({
+                "type": "StateCheckpoint",
+                "reward_cycle": checkpoint.reward_cycle,
+                "last_block_response": checkpoint
+                    .last_block_response
+                    .as_ref()
+                    .map(BlockResponse::to_debug_json),
+                "txids": checkpoint.transactions.as_ref().map(|txs| {
+                    txs.iter().map(|tx| tx.txid().to_hex()).collect::<Vec<_>>()
+                }),
+                "slot_versions": checkpoint
+                    .slot_versions
+                    .iter()
+                    .map(|(id, version)| json!({ "slot": format!("{id:?}"), "version": version }))
+                    .collect::<Vec<_>>(),
+            }),
         }
     }
 }
@@ -363,6 +441,87 @@ impl SignerMessage {
         }
         Ok(())
     }
+
+    /// Deserialize the internal components of DkgResults
+    fn deserialize_dkg_result_components<R: Read>(
+        fd: &mut R,
+    ) -> Result<(Point, Vec<(u32, PolyCommitment)>), CodecError> {
+        let aggregate_key = within_field("aggregate_key", Point::inner_consensus_deserialize(fd))?;
+        let party_polynomial_len = u32::consensus_deserialize(fd)?;
+        let mut party_polynomials = Vec::with_capacity(
+            party_polynomial_len
+                .try_into()
+                .expect("FATAL: u32 could not fit in usize"),
+        );
+        for _ in 0..party_polynomial_len {
+            let party_id = u32::consensus_deserialize(fd)?;
+            let polynomial = within_field(
+                "party_polynomials",
+                PolyCommitment::inner_consensus_deserialize(fd),
+            )?;
+            party_polynomials.push((party_id, polynomial));
+        }
+        Ok((aggregate_key, party_polynomials))
+    }
+
+    /// Write this message prefixed with its length as a u32, so that messages can be concatenated
+    /// on a stream and a reader can skip the ones it can't parse (see `read_framed`).
+    pub fn write_framed<W: Write>(&self, fd: &mut W) -> Result<(), CodecError> {
+        let bytes = self.serialize_to_vec();
+        let len = u32::try_from(bytes.len()).map_err(|_| CodecError::ArrayTooLong)?;
+        if len > MAX_MESSAGE_LEN {
+            return Err(CodecError::ArrayTooLong);
+        }
+        write_next(fd, &len)?;
+        fd.write_all(&bytes).map_err(CodecError::WriteError)
+    }
+
+    /// Read one message written by `write_framed`.  The whole frame is consumed even if the
+    /// message in it can't be parsed, so on a `DeserializeError` the reader is still positioned
+    /// at the next frame and can carry on.  Any other error means the stream itself is unusable.
+    pub fn read_framed<R: Read>(fd: &mut R) -> Result<SignerMessage, CodecError> {
+        let len: u32 = read_next(fd)?;
+        if len > MAX_MESSAGE_LEN {
+            return Err(CodecError::OverflowError(format!(
+                "Signer message frame of {len} bytes is too long"
+            )));
+        }
+        let mut frame = vec![0u8; len as usize];
+        fd.read_exact(&mut frame).map_err(CodecError::ReadError)?;
+
+        let mut frame_ptr = &frame[..];
+        let message = read_next::<SignerMessage, _>(&mut frame_ptr)?;
+        if !frame_ptr.is_empty() {
+            return Err(CodecError::DeserializeError(format!(
+                "Signer message frame has {} trailing bytes",
+                frame_ptr.len()
+            )));
+        }
+        Ok(message)
+    }
+
+    /// Write several messages as one batch, e.g. so they can share a single StackerDB chunk: the
+    /// number of messages as a u32, followed by each message.  Read it back with `read_batch`.
+    pub fn write_batch<W: Write>(fd: &mut W, messages: &[SignerMessage]) -> Result<(), CodecError> {
+        let len = u32::try_from(messages.len()).map_err(|_| CodecError::ArrayTooLong)?;
+        write_next(fd, &len)?;
+        for message in messages.iter() {
+            write_next(fd, message)?;
+        }
+        Ok(())
+    }
+
+    /// Read a batch of messages written by `write_batch`.  A batch is not tagged as such, so
+    /// only read a chunk with this if its writer is known to batch.
+    pub fn read_batch<R: Read>(fd: &mut R) -> Result<Vec<SignerMessage>, CodecError> {
+        let len: u32 = read_next(fd)?;
+        // don't trust the count for an allocation; a short batch just fails to read
+        let mut messages = vec![];
+        for _ in 0..len {
+            messages.push(read_next::<SignerMessage, _>(fd)?);
+        }
+        Ok(messages)
+    }
 }
 
 impl StacksMessageCodec for SignerMessage {
@@ -391,6 +550,9 @@ impl StacksMessageCodec for SignerMessage {
             SignerMessage::EncryptedSignerState(encrypted_state) => {
                 write_next(fd, encrypted_state)?;
             }
+            SignerMessage::StateCheckpoint(checkpoint) => {
+                write_next(fd, checkpoint)?;
+            }
         };
         Ok(())
     }
@@ -401,30 +563,31 @@ impl StacksMessageCodec for SignerMessage {
         let type_prefix = SignerMessageTypePrefix::try_from(type_prefix_byte)?;
         let message = match type_prefix {
             SignerMessageTypePrefix::Packet => {
-                let packet = Packet::inner_consensus_deserialize(fd)?;
+                let packet = within_type(
+                    "SignerMessage::Packet",
+                    Packet::inner_consensus_deserialize(fd),
+                )?;
                 SignerMessage::Packet(packet)
             }
             SignerMessageTypePrefix::BlockResponse => {
-                let block_response = read_next::<BlockResponse, _>(fd)?;
+                let block_response = within_type(
+                    "SignerMessage::BlockResponse",
+                    read_next::<BlockResponse, _>(fd),
+                )?;
                 SignerMessage::BlockResponse(block_response)
             }
             SignerMessageTypePrefix::Transactions => {
-                let transactions = read_next::<Vec<StacksTransaction>, _>(fd)?;
+                let transactions = within_type(
+                    "SignerMessage::Transactions",
+                    read_next::<Vec<StacksTransaction>, _>(fd),
+                )?;
                 SignerMessage::Transactions(transactions)
             }
             SignerMessageTypePrefix::DkgResults => {
-                let aggregate_key = Point::inner_consensus_deserialize(fd)?;
-                let party_polynomial_len = u32::consensus_deserialize(fd)?;
-                let mut party_polynomials = Vec::with_capacity(
-                    party_polynomial_len
-                        .try_into()
-                        .expect("FATAL: u32 could not fit in usize"),
-                );
-                for _ in 0..party_polynomial_len {
-                    let party_id = u32::consensus_deserialize(fd)?;
-                    let polynomial = PolyCommitment::inner_consensus_deserialize(fd)?;
-                    party_polynomials.push((party_id, polynomial));
-                }
+                let (aggregate_key, party_polynomials) = within_type(
+                    "SignerMessage::DkgResults",
+                    Self::deserialize_dkg_result_components(fd),
+                )?;
                 Self::DkgResults {
                     aggregate_key,
                     party_polynomials,
@@ -436,14 +599,173 @@ impl StacksMessageCodec for SignerMessage {
                 // To have some margin, we're expanding the left term with an additional factor 4
                 let max_encrypted_state_size = 2800 * 32 * 4 * 4;
                 let mut bound_reader = BoundReader::from_reader(fd, max_encrypted_state_size);
-                let encrypted_state = read_next::<_, _>(&mut bound_reader)?;
+                let encrypted_state = within_type(
+                    "SignerMessage::EncryptedSignerState",
+                    read_next::<_, _>(&mut bound_reader),
+                )?;
                 SignerMessage::EncryptedSignerState(encrypted_state)
             }
+            SignerMessageTypePrefix::StateCheckpoint => {
+                let checkpoint = within_type(
+                    "SignerMessage::StateCheckpoint",
+                    read_next::<StateCheckpoint, _>(fd),
+                )?;
+                SignerMessage::StateCheckpoint(checkpoint)
+            }
         };
         Ok(message)
     }
 }
 
+/// The latest state of a signer's slots.  Readers can bootstrap from a checkpoint, and then only
+/// read slot versions newer than the ones it records, instead of replaying every version of every
+/// slot.  Like the signer's encrypted state, it belongs in the signer's persisted state slot, which
+/// has no `.signers` contract yet, so it is not written to StackerDB.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StateCheckpoint {
+    /// The reward cycle of the checkpointed slots
+    pub reward_cycle: u64,
+    /// The signer's latest block response, if it has sent one
+    pub last_block_response: Option<BlockResponse>,
+    /// The signer's latest list of transactions, if it has sent one
+    pub transactions: Option<Vec<StacksTransaction>>,
+    /// The chunk version of each checkpointed slot, as of the message recorded from it
+    pub slot_versions: Vec<(MessageSlotID, u32)>,
+}
+
+impl StateCheckpoint {
+    /// Create an empty checkpoint for the given reward cycle
+    pub fn new(reward_cycle: u64) -> Self {
+        Self {
+            reward_cycle,
+            last_block_response: None,
+            transactions: None,
+            slot_versions: vec![],
+        }
+    }
+
+    /// The chunk version recorded for the given slot, if any
+    pub fn slot_version(&self, msg_id: &MessageSlotID) -> Option<u32> {
+        self.slot_versions
+            .iter()
+            .find(|(id, _)| id == msg_id)
+            .map(|(_, version)| *version)
+    }
+
+    /// Record a message that was written to the signer's slot at the given chunk version.
+    /// Only block responses and transaction lists are checkpointed; other messages, and messages
+    /// older than the ones already recorded, are ignored.
+    /// Returns true if the checkpoint changed.
+    pub fn record(&mut self, message: &SignerMessage, version: u32) -> bool {
+        let msg_id = message.msg_id();
+        if self
+            .slot_version(&msg_id)
+            .map(|recorded| recorded >= version)
+            .unwrap_or(false)
+        {
+            return false;
+        }
+        match message {
+            SignerMessage::BlockResponse(response) => {
+                self.last_block_response = Some(response.clone());
+            }
+            SignerMessage::Transactions(transactions) => {
+                self.transactions = Some(transactions.clone());
+            }
+            SignerMessage::Packet(_)
+            | SignerMessage::DkgResults { .. }
+            | SignerMessage::EncryptedSignerState(_)
+            | SignerMessage::StateCheckpoint(_) => return false,
+        }
+        self.slot_versions.retain(|(id, _)| *id != msg_id);
+        self.slot_versions.push((msg_id, version));
+        self.slot_versions.sort();
+        true
+    }
+}
+
+/// Read a length-prefixed list of at most one (slot ID, chunk version) pair per message slot
+fn read_slot_versions<R: Read>(fd: &mut R) -> Result<Vec<(MessageSlotID, u32)>, CodecError> {
+    let slot_versions_len = read_next::<u32, _>(fd)?;
+    if slot_versions_len as usize > MessageSlotID::ALL.len() {
+        return Err(CodecError::DeserializeError(format!(
+            "Too many slot versions in state checkpoint: {slot_versions_len}"
+        )));
+    }
+    let mut slot_versions = Vec::with_capacity(slot_versions_len as usize);
+    for _ in 0..slot_versions_len {
+        let msg_id_byte = read_next::<u8, _>(fd)?;
+        let msg_id = MessageSlotID::from_u8(msg_id_byte).ok_or_else(|| {
+            CodecError::DeserializeError(format!("Unknown message slot ID: {msg_id_byte}"))
+        })?;
+        let version = read_next::<u32, _>(fd)?;
+        slot_versions.push((msg_id, version));
+    }
+    Ok(slot_versions)
+}
+
+impl StacksMessageCodec for StateCheckpoint {
+    fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), CodecError> {
+        write_next(fd, &self.reward_cycle)?;
+        match &self.last_block_response {
+            Some(response) => {
+                write_next(fd, &1u8)?;
+                write_next(fd, response)?;
+            }
+            None => write_next(fd, &0u8)?,
+        }
+        match &self.transactions {
+            Some(transactions) => {
+                write_next(fd, &1u8)?;
+                write_next(fd, transactions)?;
+            }
+            None => write_next(fd, &0u8)?,
+        }
+        let slot_versions_len: u32 = self
+            .slot_versions
+            .len()
+            .try_into()
+            .map_err(|_| CodecError::ArrayTooLong)?;
+        write_next(fd, &slot_versions_len)?;
+        for (msg_id, version) in self.slot_versions.iter() {
+            write_next(fd, &msg_id.to_u8())?;
+            write_next(fd, version)?;
+        }
+        Ok(())
+    }
+
+    fn consensus_deserialize<R: Read>(fd: &mut R) -> Result<Self, CodecError> {
+        let reward_cycle = read_next::<u64, _>(fd)?;
+        let last_block_response = within_field(
+            "last_block_response",
+            match read_next::<u8, _>(fd)? {
+                0 => Ok(None),
+                1 => within_type("BlockResponse", read_next::<BlockResponse, _>(fd)).map(Some),
+                flag => Err(CodecError::DeserializeError(format!(
+                    "Invalid block response flag in state checkpoint: {flag}"
+                ))),
+            },
+        )?;
+        let transactions = within_field(
+            "transactions",
+            match read_next::<u8, _>(fd)? {
+                0 => Ok(None),
+                1 => read_next::<Vec<StacksTransaction>, _>(fd).map(Some),
+                flag => Err(CodecError::DeserializeError(format!(
+                    "Invalid transactions flag in state checkpoint: {flag}"
+                ))),
+            },
+        )?;
+        let slot_versions = within_field("slot_versions", read_slot_versions(fd))?;
+        Ok(Self {
+            reward_cycle,
+            last_block_response,
+            transactions,
+            slot_versions,
+        })
+    }
+}
+
 /// Work around for the fact that a lot of the structs being desierialized are not defined in messages.rs
 pub trait StacksMessageCodecExtensions: Sized {
     /// Serialize the struct to the provided writer
@@ -1076,34 +1398,50 @@ impl StacksMessageCodecExtensions for Message {
         let type_prefix_byte = read_next::<u8, _>(fd)?;
         let type_prefix = MessageTypePrefix::try_from(type_prefix_byte)?;
         let message = match type_prefix {
-            MessageTypePrefix::DkgBegin => {
-                Message::DkgBegin(DkgBegin::inner_consensus_deserialize(fd)?)
-            }
-            MessageTypePrefix::DkgPrivateBegin => {
-                Message::DkgPrivateBegin(DkgPrivateBegin::inner_consensus_deserialize(fd)?)
-            }
-            MessageTypePrefix::DkgEndBegin => {
-                Message::DkgEndBegin(DkgEndBegin::inner_consensus_deserialize(fd)?)
-            }
-            MessageTypePrefix::DkgEnd => Message::DkgEnd(DkgEnd::inner_consensus_deserialize(fd)?),
-            MessageTypePrefix::DkgPublicShares => {
-                Message::DkgPublicShares(DkgPublicShares::inner_consensus_deserialize(fd)?)
+            MessageTypePrefix::DkgBegin => Message::DkgBegin(within_type(
+                "Message::DkgBegin",
+                DkgBegin::inner_consensus_deserialize(fd),
+            )?),
+            MessageTypePrefix::DkgPrivateBegin => Message::DkgPrivateBegin(within_type(
+                "Message::DkgPrivateBegin",
+                DkgPrivateBegin::inner_consensus_deserialize(fd),
+            )?),
+            MessageTypePrefix::DkgEndBegin => Message::DkgEndBegin(within_type(
+                "Message::DkgEndBegin",
+                DkgEndBegin::inner_consensus_deserialize(fd),
+            )?),
+            MessageTypePrefix::DkgEnd => Message::DkgEnd(within_type(
+                "Message::DkgEnd",
+                DkgEnd::inner_consensus_deserialize(fd),
+            )?),
+            MessageTypePrefix::DkgPublicShares => Message::DkgPublicShares(within_type(
+                "Message::DkgPublicShares",
+                DkgPublicShares::inner_consensus_deserialize(fd),
+            )?),
+            MessageTypePrefix::DkgPrivateShares => Message::DkgPrivateShares(within_type(
+                "Message::DkgPrivateShares",
+                DkgPrivateShares::inner_consensus_deserialize(fd),
+            )?),
+            MessageTypePrefix::NonceRequest => Message::NonceRequest(within_type(
+                "Message::NonceRequest",
+                NonceRequest::inner_consensus_deserialize(fd),
+            )?),
+            MessageTypePrefix::NonceResponse => Message::NonceResponse(within_type(
+                "Message::NonceResponse",
+                NonceResponse::inner_consensus_deserialize(fd),
+            )?),
+            MessageTypePrefix::SignatureShareRequest => {
+                Message::SignatureShareRequest(within_type(
+                    "Message::SignatureShareRequest",
+                    SignatureShareRequest::inner_consensus_deserialize(fd),
+                )?)
             }
-            MessageTypePrefix::DkgPrivateShares => {
-                Message::DkgPrivateShares(DkgPrivateShares::inner_consensus_deserialize(fd)?)
+            MessageTypePrefix::SignatureShareResponse => {
+                Message::SignatureShareResponse(within_type(
+                    "Message::SignatureShareResponse",
+                    SignatureShareResponse::inner_consensus_deserialize(fd),
+                )?)
             }
-            MessageTypePrefix::NonceRequest => {
-                Message::NonceRequest(NonceRequest::inner_consensus_deserialize(fd)?)
-            }
-            MessageTypePrefix::NonceResponse => {
-                Message::NonceResponse(NonceResponse::inner_consensus_deserialize(fd)?)
-            }
-            MessageTypePrefix::SignatureShareRequest => Message::SignatureShareRequest(
-                SignatureShareRequest::inner_consensus_deserialize(fd)?,
-            ),
-            MessageTypePrefix::SignatureShareResponse => Message::SignatureShareResponse(
-                SignatureShareResponse::inner_consensus_deserialize(fd)?,
-            ),
         };
         Ok(message)
     }
@@ -1118,7 +1456,7 @@ impl StacksMessageCodecExtensions for Packet {
 
     fn inner_consensus_deserialize<R: Read>(fd: &mut R) -> Result<Self, CodecError> {
         let msg = Message::inner_consensus_deserialize(fd)?;
-        let sig: Vec<u8> = read_next(fd)?;
+        let sig: Vec<u8> = within_field("sig", read_next(fd))?;
         Ok(Packet { msg, sig })
     }
 }
@@ -1129,7 +1467,11 @@ BlockResponseTypePrefix {
     /// Accepted
     Accepted = 0,
     /// Rejected
-    Rejected = 1
+    Rejected = 1,
+    /// Accepted, followed by the response's metadata
+    AcceptedWithMetadata = 2,
+    /// Rejected, followed by the response's metadata
+    RejectedWithMetadata = 3
 });
 
 impl TryFrom<u8> for BlockResponseTypePrefix {
@@ -1144,8 +1486,14 @@ impl TryFrom<u8> for BlockResponseTypePrefix {
 impl From<&BlockResponse> for BlockResponseTypePrefix {
     fn from(block_response: &BlockResponse) -> Self {
         match block_response {
-            BlockResponse::Accepted(_) => BlockResponseTypePrefix::Accepted,
-            BlockResponse::Rejected(_) => BlockResponseTypePrefix::Rejected,
+            BlockResponse::Accepted(accepted) if accepted.metadata.is_empty() => {
+                BlockResponseTypePrefix::Accepted
+            }
+            BlockResponse::Accepted(_) => BlockResponseTypePrefix::AcceptedWithMetadata,
+            BlockResponse::Rejected(rejection) if rejection.metadata.is_empty() => {
+                BlockResponseTypePrefix::Rejected
+            }
+            BlockResponse::Rejected(_) => BlockResponseTypePrefix::RejectedWithMetadata,
         }
     }
 }
@@ -1155,7 +1503,7 @@ impl From<&BlockResponse> for BlockResponseTypePrefix {
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum BlockResponse {
     /// The Nakamoto block was accepted and therefore signed
-    Accepted((Sha512Trunc256Sum, ThresholdSignature)),
+    Accepted(BlockAccepted),
     /// The Nakamoto block was rejected and therefore not signed
     Rejected(BlockRejection),
 }
@@ -1167,7 +1515,7 @@ impl std::fmt::Display for BlockResponse {
                 write!(
                     f,
                     "BlockAccepted: signer_sighash = {}, signature = {}",
-                    a.0, a.1
+                    a.signer_signature_hash, a.signature
                 )
             }
             BlockResponse::Rejected(r) => {
@@ -1184,7 +1532,7 @@ impl std::fmt::Display for BlockResponse {
 impl BlockResponse {
     /// Create a new accepted BlockResponse for the provided block signer signature hash and signature
     pub fn accepted(hash: Sha512Trunc256Sum, sig: Signature) -> Self {
-        Self::Accepted((hash, ThresholdSignature(sig)))
+        Self::Accepted(BlockAccepted::new(hash, ThresholdSignature(sig)))
     }
 
     /// Create a new rejected BlockResponse for the provided block signer signature hash and signature
@@ -1194,15 +1542,95 @@ impl BlockResponse {
             RejectCode::SignedRejection(ThresholdSignature(sig)),
         ))
     }
+
+    /// What the signer says about this response beyond its verdict
+    pub fn metadata(&self) -> &BlockResponseMetadata {
+        match self {
+            BlockResponse::Accepted(accepted) => &accepted.metadata,
+            BlockResponse::Rejected(rejection) => &rejection.metadata,
+        }
+    }
+
+    fn metadata_mut(&mut self) -> &mut BlockResponseMetadata {
+        match self {
+            BlockResponse::Accepted(accepted) => &mut accepted.metadata,
+            BlockResponse::Rejected(rejection) => &mut rejection.metadata,
+        }
+    }
+
+    /// When (in unix seconds) the signer made this response, if it says so.  Responses from
+    /// signers that predate timestamps carry none.
+    pub fn timestamp(&self) -> Option<u64> {
+        self.metadata().timestamp
+    }
+
+    /// Attach `metadata` to this response.  Non-empty metadata changes the encoding, which
+    /// miners and signers that predate it cannot read.
+    pub fn with_metadata(mut self, metadata: BlockResponseMetadata) -> Self {
+        *self.metadata_mut() = metadata;
+        self
+    }
+
+    /// Name the signer slot of the signer sending this response, so that it can be attributed
+    /// without the StackerDB slot it was read from
+    pub fn with_signer_slot_id(mut self, signer_slot_id: u32) -> Self {
+        self.metadata_mut().signer_slot_id = Some(signer_slot_id);
+        self
+    }
+
+    /// The signer slot of the signer that sent this response, if it says so
+    pub fn signer_slot_id(&self) -> Option<u32> {
+        self.metadata().signer_slot_id
+    }
+
+    /// Is this response newer than `other`?  Only acceptances that both carry a timestamp can
+    /// be compared; for anything else (e.g. old-format messages), this returns None.
+    pub fn newer_than(&self, other: &BlockResponse) -> Option<bool> {
+        match (self, other) {
+            (BlockResponse::Accepted(_), BlockResponse::Accepted(_)) => {
+                Some(self.timestamp()? > other.timestamp()?)
+            }
+            _ => None,
+        }
+    }
+
+    /// A self-describing JSON rendering of this response, for logs and debugging
+    pub fn to_debug_json(&self) -> serde_json::Value {
+        match self {
+            BlockResponse::Accepted(accepted) => json!({
+                "result": "Accepted",
+                "signer_signature_hash": accepted.signer_signature_hash.to_hex(),
+                "signature": to_hex(&accepted.signature.serialize_to_vec()),
+                "timestamp": accepted.metadata.timestamp,
+                "signer_slot_id": accepted.metadata.signer_slot_id,
+            }),
+            BlockResponse::Rejected(rejection) => json!({
+                "result": "Rejected",
+                "signer_signature_hash": rejection.signer_signature_hash.to_hex(),
+                "reason": rejection.reason,
+                "reason_code": format!("{:?}", RejectCodeTypePrefix::from(&rejection.reason_code)),
+                "reason_details": rejection.reason_code.debug_details(),
+                "timestamp": rejection.metadata.timestamp,
+                "signer_slot_id": rejection.metadata.signer_slot_id,
+            }),
+        }
+    }
 }
 
 impl StacksMessageCodec for BlockResponse {
     fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), CodecError> {
         write_next(fd, &(BlockResponseTypePrefix::from(self) as u8))?;
+        // Responses without metadata keep the original encoding, so that signers and miners that
+        // predate it can still read them.  Otherwise, the metadata follows the original fields.
         match self {
-            BlockResponse::Accepted((hash, sig)) => {
-                write_next(fd, hash)?;
-                write_next(fd, sig)?;
+            BlockResponse::Accepted(accepted) if accepted.metadata.is_empty() => {
+                accepted.serialize_fields(fd)?;
+            }
+            BlockResponse::Accepted(accepted) => {
+                write_next(fd, accepted)?;
+            }
+            BlockResponse::Rejected(rejection) if rejection.metadata.is_empty() => {
+                rejection.serialize_fields(fd)?;
             }
             BlockResponse::Rejected(rejection) => {
                 write_next(fd, rejection)?;
@@ -1216,12 +1644,20 @@ impl StacksMessageCodec for BlockResponse {
         let type_prefix = BlockResponseTypePrefix::try_from(type_prefix_byte)?;
         let response = match type_prefix {
             BlockResponseTypePrefix::Accepted => {
-                let hash = read_next::<Sha512Trunc256Sum, _>(fd)?;
-                let sig = read_next::<ThresholdSignature, _>(fd)?;
-                BlockResponse::Accepted((hash, sig))
+                let accepted = within_type("BlockAccepted", BlockAccepted::deserialize_fields(fd))?;
+                BlockResponse::Accepted(accepted)
+            }
+            BlockResponseTypePrefix::AcceptedWithMetadata => {
+                let accepted = within_type("BlockAccepted", read_next::<BlockAccepted, _>(fd))?;
+                BlockResponse::Accepted(accepted)
             }
             BlockResponseTypePrefix::Rejected => {
-                let rejection = read_next::<BlockRejection, _>(fd)?;
+                let rejection =
+                    within_type("BlockRejection", BlockRejection::deserialize_fields(fd))?;
+                BlockResponse::Rejected(rejection)
+            }
+            BlockResponseTypePrefix::RejectedWithMetadata => {
+                let rejection = within_type("BlockRejection", read_next::<BlockRejection, _>(fd))?;
                 BlockResponse::Rejected(rejection)
             }
         };
@@ -1229,6 +1665,132 @@ impl StacksMessageCodec for BlockResponse {
     }
 }
 
+/// What a signer can say about its block response beyond the verdict.  Responses from signers
+/// that predate it carry none of it.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BlockResponseMetadata {
+    /// When (in unix seconds) the signer made the response, if it says so
+    #[serde(default)]
+    pub timestamp: Option<u64>,
+    /// The signer slot of the signer that made the response, if it says so
+    #[serde(default)]
+    pub signer_slot_id: Option<u32>,
+}
+
+impl BlockResponseMetadata {
+    /// Metadata for a response that the signer in `signer_slot_id` is making now
+    pub fn now(signer_slot_id: u32) -> Self {
+        Self {
+            timestamp: Some(get_epoch_time_secs()),
+            signer_slot_id: Some(signer_slot_id),
+        }
+    }
+
+    /// Is there nothing to say?  Responses without metadata are written in the original
+    /// encoding.
+    pub fn is_empty(&self) -> bool {
+        self.timestamp.is_none() && self.signer_slot_id.is_none()
+    }
+}
+
+impl StacksMessageCodec for BlockResponseMetadata {
+    fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), CodecError> {
+        match &self.timestamp {
+            Some(timestamp) => {
+                write_next(fd, &1u8)?;
+                write_next(fd, timestamp)?;
+            }
+            None => write_next(fd, &0u8)?,
+        }
+        match &self.signer_slot_id {
+            Some(signer_slot_id) => {
+                write_next(fd, &1u8)?;
+                write_next(fd, signer_slot_id)?;
+            }
+            None => write_next(fd, &0u8)?,
+        }
+        Ok(())
+    }
+
+    fn consensus_deserialize<R: Read>(fd: &mut R) -> Result<Self, CodecError> {
+        let timestamp = within_field("timestamp", read_optional::<u64, _>(fd))?;
+        let signer_slot_id = within_field("signer_slot_id", read_optional::<u32, _>(fd))?;
+        Ok(Self {
+            timestamp,
+            signer_slot_id,
+        })
+    }
+}
+
+/// Read a value written after a 0/1 presence flag, as `BlockResponseMetadata` writes them
+fn read_optional<T: StacksMessageCodec, R: Read>(fd: &mut R) -> Result<Option<T>, CodecError> {
+    match read_next::<u8, _>(fd)? {
+        0 => Ok(None),
+        1 => read_next::<T, _>(fd).map(Some),
+        flag => Err(CodecError::DeserializeError(format!(
+            "Invalid presence flag: {flag}"
+        ))),
+    }
+}
+
+/// An acceptance response from a signer for a proposed block
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BlockAccepted {
+    /// The signer signature hash of the block that was accepted
+    pub signer_signature_hash: Sha512Trunc256Sum,
+    /// The signature over the block
+    pub signature: ThresholdSignature,
+    /// What the signer says about the acceptance beyond the signature
+    #[serde(default)]
+    pub metadata: BlockResponseMetadata,
+}
+
+impl BlockAccepted {
+    /// Create a new BlockAccepted for the provided block signer signature hash and signature
+    pub fn new(signer_signature_hash: Sha512Trunc256Sum, signature: ThresholdSignature) -> Self {
+        Self {
+            signer_signature_hash,
+            signature,
+            metadata: BlockResponseMetadata::default(),
+        }
+    }
+
+    /// Write the fields of the original encoding, without the metadata
+    fn serialize_fields<W: Write>(&self, fd: &mut W) -> Result<(), CodecError> {
+        write_next(fd, &self.signer_signature_hash)?;
+        write_next(fd, &self.signature)?;
+        Ok(())
+    }
+
+    /// Read the fields of the original encoding.  The metadata is left empty.
+    fn deserialize_fields<R: Read>(fd: &mut R) -> Result<Self, CodecError> {
+        let signer_signature_hash = within_field(
+            "signer_signature_hash",
+            read_next::<Sha512Trunc256Sum, _>(fd),
+        )?;
+        let signature = within_field("signature", read_next::<ThresholdSignature, _>(fd))?;
+        Ok(Self {
+            signer_signature_hash,
+            signature,
+            metadata: BlockResponseMetadata::default(),
+        })
+    }
+}
+
+impl StacksMessageCodec for BlockAccepted {
+    fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), CodecError> {
+        self.serialize_fields(fd)?;
+        write_next(fd, &self.metadata)?;
+        Ok(())
+    }
+
+    fn consensus_deserialize<R: Read>(fd: &mut R) -> Result<Self, CodecError> {
+        let mut accepted = Self::deserialize_fields(fd)?;
+        accepted.metadata = within_field("metadata", read_next::<BlockResponseMetadata, _>(fd))?;
+        Ok(accepted)
+    }
+}
+
 /// A rejection response from a signer for a proposed block
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct BlockRejection {
@@ -1238,6 +1800,9 @@ pub struct BlockRejection {
     pub reason_code: RejectCode,
     /// The signer signature hash of the block that was rejected
     pub signer_signature_hash: Sha512Trunc256Sum,
+    /// What the signer says about the rejection beyond its reason
+    #[serde(default)]
+    pub metadata: BlockResponseMetadata,
 }
 
 impl BlockRejection {
@@ -1247,39 +1812,76 @@ impl BlockRejection {
             reason: reason_code.to_string(),
             reason_code,
             signer_signature_hash,
+            metadata: BlockResponseMetadata::default(),
         }
     }
-}
 
-impl StacksMessageCodec for BlockRejection {
-    fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), CodecError> {
+    /// Attach `metadata` to this rejection.  Non-empty metadata changes the encoding, which
+    /// miners and signers that predate it cannot read.
+    pub fn with_metadata(mut self, metadata: BlockResponseMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Name the signer slot of the signer sending this rejection, so that it can be attributed
+    /// without the StackerDB slot it was read from
+    pub fn with_signer_slot_id(mut self, signer_slot_id: u32) -> Self {
+        self.metadata.signer_slot_id = Some(signer_slot_id);
+        self
+    }
+
+    /// Write the fields of the original encoding, without the metadata
+    fn serialize_fields<W: Write>(&self, fd: &mut W) -> Result<(), CodecError> {
         write_next(fd, &self.reason.as_bytes().to_vec())?;
         write_next(fd, &self.reason_code)?;
         write_next(fd, &self.signer_signature_hash)?;
         Ok(())
     }
 
-    fn consensus_deserialize<R: Read>(fd: &mut R) -> Result<Self, CodecError> {
-        let reason_bytes = read_next::<Vec<u8>, _>(fd)?;
-        let reason = String::from_utf8(reason_bytes).map_err(|e| {
-            CodecError::DeserializeError(format!("Failed to decode reason string: {:?}", &e))
-        })?;
-        let reason_code = read_next::<RejectCode, _>(fd)?;
-        let signer_signature_hash = read_next::<Sha512Trunc256Sum, _>(fd)?;
+    /// Read the fields of the original encoding.  The metadata is left empty.
+    fn deserialize_fields<R: Read>(fd: &mut R) -> Result<Self, CodecError> {
+        let reason_bytes = within_field("reason", read_next::<Vec<u8>, _>(fd))?;
+        let reason = within_field(
+            "reason",
+            String::from_utf8(reason_bytes).map_err(|e| {
+                CodecError::DeserializeError(format!("Failed to decode reason string: {:?}", &e))
+            }),
+        )?;
+        let reason_code = within_field("reason_code", read_next::<RejectCode, _>(fd))?;
+        let signer_signature_hash = within_field(
+            "signer_signature_hash",
+            read_next::<Sha512Trunc256Sum, _>(fd),
+        )?;
         Ok(Self {
             reason,
             reason_code,
             signer_signature_hash,
+            metadata: BlockResponseMetadata::default(),
         })
     }
 }
 
+impl StacksMessageCodec for BlockRejection {
+    fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), CodecError> {
+        self.serialize_fields(fd)?;
+        write_next(fd, &self.metadata)?;
+        Ok(())
+    }
+
+    fn consensus_deserialize<R: Read>(fd: &mut R) -> Result<Self, CodecError> {
+        let mut rejection = Self::deserialize_fields(fd)?;
+        rejection.metadata = within_field("metadata", read_next::<BlockResponseMetadata, _>(fd))?;
+        Ok(rejection)
+    }
+}
+
 impl From<BlockValidateReject> for BlockRejection {
     fn from(reject: BlockValidateReject) -> Self {
         Self {
             reason: reject.reason,
             reason_code: RejectCode::ValidationFailed(reject.reason_code),
             signer_signature_hash: reject.signer_signature_hash,
+            metadata: BlockResponseMetadata::default(),
         }
     }
 }
@@ -1374,6 +1976,32 @@ impl StacksMessageCodec for RejectCode {
     }
 }
 
+impl RejectCode {
+    /// The data carried by this code, as JSON, for `BlockResponse::to_debug_json`
+    fn debug_details(&self) -> serde_json::Value {
+        match self {
+            RejectCode::ValidationFailed(code) => json!({
+                "validation_code": format!("{:?}", code),
+            }),
+            RejectCode::SignedRejection(sig) => json!({
+                "signature": to_hex(&sig.serialize_to_vec()),
+            }),
+            RejectCode::InsufficientSigners(malicious_signers)
+            | RejectCode::NonceTimeout(malicious_signers) => json!({
+                "malicious_signers": malicious_signers,
+            }),
+            RejectCode::AggregatorError(reason) => json!({ "error": reason }),
+            RejectCode::MissingTransactions(missing_transactions) => json!({
+                "txids": missing_transactions
+                    .iter()
+                    .map(|tx| tx.txid().to_hex())
+                    .collect::<Vec<_>>(),
+            }),
+            RejectCode::ConnectivityIssues => json!({}),
+        }
+    }
+}
+
 impl std::fmt::Display for RejectCode {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -1570,10 +2198,34 @@ mod test {
         assert_eq!(rejection, deserialized_rejection);
     }
 
+    /// An acceptance of the block with the given hash, made at the given time
+    fn block_accepted(hash: Sha512Trunc256Sum, timestamp: Option<u64>) -> BlockResponse {
+        BlockResponse::Accepted(BlockAccepted {
+            signer_signature_hash: hash,
+            signature: ThresholdSignature::empty(),
+            metadata: BlockResponseMetadata {
+                timestamp,
+                signer_slot_id: None,
+            },
+        })
+    }
+
+    /// A rejection of the block with the given hash, made at the given time
+    fn block_rejected(hash: Sha512Trunc256Sum, timestamp: Option<u64>) -> BlockResponse {
+        let mut rejection = BlockRejection::new(hash, RejectCode::ConnectivityIssues);
+        rejection.metadata.timestamp = timestamp;
+        BlockResponse::Rejected(rejection)
+    }
+
     #[test]
     fn serde_block_response() {
-        let response =
-            BlockResponse::Accepted((Sha512Trunc256Sum([0u8; 32]), ThresholdSignature::empty()));
+        let response = block_accepted(Sha512Trunc256Sum([0u8; 32]), None);
+        let serialized_response = response.serialize_to_vec();
+        let deserialized_response = read_next::<BlockResponse, _>(&mut &serialized_response[..])
+            .expect("Failed to deserialize BlockResponse");
+        assert_eq!(response, deserialized_response);
+
+        let response = block_accepted(Sha512Trunc256Sum([0u8; 32]), Some(1_700_000_000));
         let serialized_response = response.serialize_to_vec();
         let deserialized_response = read_next::<BlockResponse, _>(&mut &serialized_response[..])
             .expect("Failed to deserialize BlockResponse");
@@ -1589,6 +2241,251 @@ mod test {
         assert_eq!(response, deserialized_response);
     }
 
+    #[test]
+    fn serde_block_response_with_signer_slot_id() {
+        let hash = Sha512Trunc256Sum([3u8; 32]);
+        let responses = vec![
+            block_accepted(hash, None).with_signer_slot_id(4),
+            block_accepted(hash, Some(1_700_000_000)).with_signer_slot_id(5),
+            block_rejected(hash, Some(1_700_000_000)).with_signer_slot_id(6),
+            block_rejected(hash, None).with_signer_slot_id(7),
+        ];
+        for (response, signer_slot_id) in responses.into_iter().zip(4..) {
+            assert_eq!(response.signer_slot_id(), Some(signer_slot_id));
+            let serialized_response = response.serialize_to_vec();
+            let deserialized_response =
+                read_next::<BlockResponse, _>(&mut &serialized_response[..])
+                    .expect("Failed to deserialize BlockResponse");
+            assert_eq!(response, deserialized_response);
+        }
+
+        // both verdicts encode their metadata the same way, after their original fields
+        let accepted = block_accepted(hash, Some(1)).with_signer_slot_id(2);
+        let rejected = block_rejected(hash, Some(1)).with_signer_slot_id(2);
+        let metadata = accepted.metadata().serialize_to_vec();
+        assert_eq!(metadata, rejected.metadata().serialize_to_vec());
+        assert!(accepted.serialize_to_vec().ends_with(&metadata));
+        assert!(rejected.serialize_to_vec().ends_with(&metadata));
+
+        // old-format messages have no metadata, and are written in the original encoding
+        let old_formats = vec![
+            (
+                block_accepted(hash, None),
+                BlockResponseTypePrefix::Accepted,
+            ),
+            (
+                block_rejected(hash, None),
+                BlockResponseTypePrefix::Rejected,
+            ),
+        ];
+        for (response, type_prefix) in old_formats {
+            let serialized_response = response.serialize_to_vec();
+            assert_eq!(serialized_response[0], type_prefix as u8);
+            let deserialized_response =
+                read_next::<BlockResponse, _>(&mut &serialized_response[..])
+                    .expect("Failed to deserialize BlockResponse");
+            assert_eq!(deserialized_response.signer_slot_id(), None);
+            assert_eq!(response, deserialized_response);
+        }
+    }
+
+    #[test]
+    fn deserialize_error_should_name_failing_field() {
+        let rejection = BlockRejection {
+            metadata: BlockResponseMetadata::default(),
+            ..BlockRejection::new(Sha512Trunc256Sum([5u8; 32]), RejectCode::ConnectivityIssues)
+        };
+        let signer_message =
+            SignerMessage::BlockResponse(BlockResponse::Rejected(rejection.clone()));
+        let mut serialized_signer_message = signer_message.serialize_to_vec();
+        // message type prefix, response type prefix, then the length-prefixed reason
+        let reason_code_offset = 1 + 1 + 4 + rejection.reason.len();
+        assert_eq!(
+            serialized_signer_message[reason_code_offset],
+            RejectCodeTypePrefix::ConnectivityIssues as u8
+        );
+        serialized_signer_message[reason_code_offset] = 0xff;
+
+        match read_next::<SignerMessage, _>(&mut &serialized_signer_message[..]) {
+            Err(CodecError::DeserializeError(msg)) => assert_eq!(
+                msg,
+                "SignerMessage::BlockResponse -> BlockRejection -> reason_code: Unknown reject code type prefix: 255"
+            ),
+            res => panic!("Expected a deserialize error, got {res:?}"),
+        }
+    }
+
+    #[test]
+    fn deserialize_error_should_name_failing_metadata_field() {
+        let hash = Sha512Trunc256Sum([6u8; 32]);
+        let responses = vec![
+            (block_accepted(hash, Some(1)), "BlockAccepted"),
+            (block_rejected(hash, Some(1)), "BlockRejection"),
+        ];
+        for (response, type_name) in responses {
+            let signer_message = SignerMessage::BlockResponse(response.with_signer_slot_id(2));
+            let mut serialized_signer_message = signer_message.serialize_to_vec();
+            // the metadata ends with the signer_slot_id flag and its u32
+            let flag_offset = serialized_signer_message.len() - 1 - 4;
+            assert_eq!(serialized_signer_message[flag_offset], 1);
+            serialized_signer_message[flag_offset] = 2;
+
+            match read_next::<SignerMessage, _>(&mut &serialized_signer_message[..]) {
+                Err(CodecError::DeserializeError(msg)) => assert_eq!(
+                    msg,
+                    format!("SignerMessage::BlockResponse -> {type_name} -> metadata: signer_slot_id: Invalid presence flag: 2")
+                ),
+                res => panic!("Expected a deserialize error, got {res:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn serde_block_response_timestamp() {
+        let hash = Sha512Trunc256Sum([4u8; 32]);
+        let before = get_epoch_time_secs();
+        let rejection = BlockRejection::new(hash, RejectCode::ConnectivityIssues);
+        let responses = vec![
+            BlockResponse::accepted(hash, ThresholdSignature::empty().0),
+            BlockResponse::Rejected(rejection.clone()),
+        ];
+        for response in responses {
+            // unless asked for, a response has no timestamp and keeps the original encoding
+            assert_eq!(response.timestamp(), None);
+            let type_prefix = response.serialize_to_vec()[0];
+            let response = response.with_metadata(BlockResponseMetadata::now(3));
+            assert!(response.timestamp().unwrap() >= before);
+            assert_eq!(response.signer_slot_id(), Some(3));
+            let serialized_response = response.serialize_to_vec();
+            assert_eq!(serialized_response[0], type_prefix + 2);
+            let deserialized_response =
+                read_next::<BlockResponse, _>(&mut &serialized_response[..])
+                    .expect("Failed to deserialize BlockResponse");
+            assert_eq!(response, deserialized_response);
+        }
+        let serialized_response = BlockResponse::Rejected(rejection.clone()).serialize_to_vec();
+        assert_eq!(
+            serialized_response[0],
+            BlockResponseTypePrefix::Rejected as u8
+        );
+        let serialized_response =
+            BlockResponse::Rejected(rejection.with_metadata(BlockResponseMetadata::now(3)))
+                .serialize_to_vec();
+        assert_eq!(
+            serialized_response[0],
+            BlockResponseTypePrefix::RejectedWithMetadata as u8
+        );
+
+        // a rejection from a signer that predates timestamps has none
+        let old_format = block_rejected(hash, None);
+        let serialized_response = old_format.serialize_to_vec();
+        assert_eq!(
+            serialized_response[0],
+            BlockResponseTypePrefix::Rejected as u8
+        );
+        let deserialized_response = read_next::<BlockResponse, _>(&mut &serialized_response[..])
+            .expect("Failed to deserialize BlockResponse");
+        assert_eq!(deserialized_response.timestamp(), None);
+
+        // ...which is not the same as a timestamp of 0
+        let zero = block_rejected(hash, Some(0));
+        let serialized_response = zero.serialize_to_vec();
+        let deserialized_response = read_next::<BlockResponse, _>(&mut &serialized_response[..])
+            .expect("Failed to deserialize BlockResponse");
+        assert_eq!(deserialized_response.timestamp(), Some(0));
+    }
+
+    #[test]
+    fn newer_than_should_compare_acceptance_times() {
+        let hash = Sha512Trunc256Sum([0u8; 32]);
+        let earlier = block_accepted(hash, Some(100));
+        let later = block_accepted(hash, Some(200));
+        assert_eq!(later.newer_than(&earlier), Some(true));
+        assert_eq!(earlier.newer_than(&later), Some(false));
+        assert_eq!(earlier.newer_than(&earlier), Some(false));
+
+        // no time to compare against
+        let old_format = block_accepted(hash, None);
+        let rejected = BlockResponse::Rejected(BlockRejection::new(
+            hash,
+            RejectCode::ValidationFailed(ValidateRejectCode::InvalidBlock),
+        ));
+        assert_eq!(later.newer_than(&old_format), None);
+        assert_eq!(old_format.newer_than(&earlier), None);
+        assert_eq!(later.newer_than(&rejected), None);
+    }
+
+    #[test]
+    fn to_debug_json_should_name_and_hex_encode_fields() {
+        let sig = ThresholdSignature::empty();
+        let response = BlockResponse::Rejected(BlockRejection::new(
+            Sha512Trunc256Sum([0xab; 32]),
+            RejectCode::SignedRejection(sig.clone()),
+        ));
+        let json = SignerMessage::BlockResponse(response).to_debug_json();
+        assert_eq!(json["type"], "BlockResponse");
+
+        let response = &json["response"];
+        assert_eq!(response["result"], "Rejected");
+        assert_eq!(response["signer_signature_hash"], "ab".repeat(32));
+        assert_eq!(response["reason_code"], "SignedRejection");
+        assert_eq!(
+            response["reason_details"]["signature"],
+            to_hex(&sig.serialize_to_vec())
+        );
+
+        let response = BlockResponse::Rejected(BlockRejection::new(
+            Sha512Trunc256Sum([0u8; 32]),
+            RejectCode::ValidationFailed(ValidateRejectCode::InvalidBlock),
+        ));
+        let json = response.to_debug_json();
+        assert_eq!(json["reason_code"], "ValidationFailed");
+        assert_eq!(json["reason_details"]["validation_code"], "InvalidBlock");
+    }
+
+    #[test]
+    fn state_checkpoint_should_keep_latest_state() {
+        let accepted = block_accepted(Sha512Trunc256Sum([0u8; 32]), None);
+        let rejected = BlockResponse::Rejected(BlockRejection::new(
+            Sha512Trunc256Sum([1u8; 32]),
+            RejectCode::ValidationFailed(ValidateRejectCode::InvalidBlock),
+        ));
+
+        let mut checkpoint = StateCheckpoint::new(7);
+        assert!(checkpoint.record(&SignerMessage::BlockResponse(accepted.clone()), 1));
+        assert!(checkpoint.record(&SignerMessage::BlockResponse(rejected.clone()), 2));
+        assert!(checkpoint.record(&SignerMessage::Transactions(vec![]), 5));
+
+        // stale versions and un-checkpointed messages are ignored
+        assert!(!checkpoint.record(&SignerMessage::BlockResponse(accepted.clone()), 2));
+        assert!(!checkpoint.record(&SignerMessage::EncryptedSignerState(vec![1, 2, 3]), 9));
+
+        assert_eq!(checkpoint.last_block_response, Some(rejected));
+        assert_eq!(checkpoint.transactions, Some(vec![]));
+        assert_eq!(
+            checkpoint.slot_versions,
+            vec![
+                (MessageSlotID::BlockResponse, 2),
+                (MessageSlotID::Transactions, 5)
+            ]
+        );
+
+        let signer_message = SignerMessage::StateCheckpoint(checkpoint);
+        assert_eq!(signer_message.msg_id(), MessageSlotID::EncryptedSignerState);
+        let serialized_signer_message = signer_message.serialize_to_vec();
+        let deserialized_signer_message =
+            read_next::<SignerMessage, _>(&mut &serialized_signer_message[..])
+                .expect("Failed to deserialize SignerMessage");
+        assert_eq!(signer_message, deserialized_signer_message);
+
+        let empty = SignerMessage::StateCheckpoint(StateCheckpoint::new(7));
+        let serialized_empty = empty.serialize_to_vec();
+        assert_eq!(
+            read_next::<SignerMessage, _>(&mut &serialized_empty[..]).unwrap(),
+            empty
+        );
+    }
+
     #[test]
     fn serde_point_scalar() {
         let mut rng = OsRng;
@@ -1815,13 +2712,13 @@ mod test {
                 .expect("Failed to deserialize SignerMessage");
         assert_eq!(signer_message, deserialized_signer_message);
 
-        let signer_message = SignerMessage::BlockResponse(BlockResponse::Accepted((
+        let signer_message = SignerMessage::BlockResponse(BlockResponse::accepted(
             Sha512Trunc256Sum([2u8; 32]),
-            ThresholdSignature(Signature {
+            Signature {
                 R: Point::from(Scalar::random(rng)),
                 z: Scalar::random(rng),
-            }),
-        )));
+            },
+        ));
         let serialized_signer_message = signer_message.serialize_to_vec();
         let deserialized_signer_message =
             read_next::<SignerMessage, _>(&mut &serialized_signer_message[..])
@@ -1851,4 +2748,67 @@ mod test {
                 .expect("Failed to deserialize SignerMessage");
         assert_eq!(signer_message, deserialized_signer_message);
     }
+
+    #[test]
+    fn short_id_should_identify_message() {
+        let accepted =
+            SignerMessage::BlockResponse(block_accepted(Sha512Trunc256Sum([0u8; 32]), None));
+        let rejected = SignerMessage::BlockResponse(BlockResponse::Rejected(BlockRejection::new(
+            Sha512Trunc256Sum([0u8; 32]),
+            RejectCode::ValidationFailed(ValidateRejectCode::InvalidBlock),
+        )));
+
+        let short_id = accepted.short_id();
+        assert!(short_id.starts_with("BlockResponse:"));
+        assert_eq!(short_id.len(), "BlockResponse:".len() + 6);
+
+        // equal messages share an ID, even once they've been through the wire
+        let deserialized = read_next::<SignerMessage, _>(&mut &accepted.serialize_to_vec()[..])
+            .expect("Failed to deserialize SignerMessage");
+        assert_eq!(deserialized.short_id(), short_id);
+        assert_eq!(accepted.clone().short_id(), short_id);
+
+        // different messages don't
+        assert_ne!(rejected.short_id(), short_id);
+        assert_ne!(
+            SignerMessage::Transactions(vec![]).short_id(),
+            SignerMessage::EncryptedSignerState(vec![]).short_id()
+        );
+    }
+
+    #[test]
+    fn framed_messages_should_be_read_independently() {
+        let first = SignerMessage::Packet(Packet {
+            msg: Message::DkgBegin(DkgBegin { dkg_id: 0 }),
+            sig: vec![1u8; 20],
+        });
+        let second = SignerMessage::BlockResponse(BlockResponse::Rejected(BlockRejection::new(
+            Sha512Trunc256Sum([2u8; 32]),
+            RejectCode::ValidationFailed(ValidateRejectCode::InvalidBlock),
+        )));
+
+        let mut stream = vec![];
+        first.write_framed(&mut stream).unwrap();
+        // a frame holding something that isn't a signer message
+        let garbage = vec![0xffu8; 10];
+        write_next(&mut stream, &(garbage.len() as u32)).unwrap();
+        stream.extend_from_slice(&garbage);
+        second.write_framed(&mut stream).unwrap();
+        let first_len = first.serialize_to_vec().len();
+        assert_eq!(&stream[4..4 + first_len], &first.serialize_to_vec()[..]);
+
+        let mut stream_ptr = &stream[..];
+        assert_eq!(SignerMessage::read_framed(&mut stream_ptr).unwrap(), first);
+        assert!(matches!(
+            SignerMessage::read_framed(&mut stream_ptr),
+            Err(CodecError::DeserializeError(_))
+        ));
+        // the bad frame was skipped
+        assert_eq!(SignerMessage::read_framed(&mut stream_ptr).unwrap(), second);
+        assert!(stream_ptr.is_empty());
+        assert!(matches!(
+            SignerMessage::read_framed(&mut stream_ptr),
+            Err(CodecError::ReadError(_))
+        ));
+    }
 }
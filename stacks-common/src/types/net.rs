@@ -378,6 +378,28 @@ impl PeerHost {
             PeerHost::IP(ref i, ref p) => (format!("{}", i.to_socketaddr(0).ip()), *p),
         }
     }
+
+    /// Normalized `host:port` key for deduplication and logging, so that the same logical host
+    /// yields the same key whether it came from a URL, a socket address, or a `Host:` header.
+    /// DNS names are lowercased and lose any trailing '.', DNS names which are really IP
+    /// addresses are treated as IP addresses, and IPv4-mapped IPv6 addresses are treated as IPv4.
+    /// No name resolution is performed.
+    pub fn canonical_key(&self) -> String {
+        match *self {
+            PeerHost::DNS(ref name, ref port) => {
+                let name = name
+                    .trim_end_matches('.')
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .to_lowercase();
+                match name.parse::<IpAddr>() {
+                    Ok(addr) => format!("{}", PeerAddress::from_ip(&addr).to_socketaddr(*port)),
+                    Err(_) => format!("{}:{}", name, port),
+                }
+            }
+            PeerHost::IP(ref addr, ref port) => format!("{}", addr.to_socketaddr(*port)),
+        }
+    }
 }
 
 impl From<SocketAddr> for PeerHost {
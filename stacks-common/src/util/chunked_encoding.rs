@@ -399,6 +399,14 @@ impl<'a, 'state, W: Write> HttpChunkedTransferWriter<'a, 'state, W> {
     pub fn corked(&self) -> bool {
         self.state.corked
     }
+
+    /// Send out any buffered chunk data as its own chunk, without ending the stream.
+    pub fn flush_buffered(&mut self) -> io::Result<()> {
+        if !self.state.corked && !self.state.chunk_buf.is_empty() {
+            self.flush_chunk()?;
+        }
+        Ok(())
+    }
 }
 
 impl<'a, 'state, W: Write> Write for HttpChunkedTransferWriter<'a, 'state, W> {
@@ -19,7 +19,7 @@ mod stackerdb;
 /// The stacks node client module for communicating with the stacks node
 pub(crate) mod stacks_client;
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use clarity::vm::errors::Error as ClarityError;
 use clarity::vm::types::serialization::SerializationError;
@@ -47,9 +47,17 @@ pub enum ClientError {
     /// Failed to sign stacker-db chunk
     #[error("Failed to sign stacker-db chunk: {0}")]
     FailToSign(#[from] StackerDBError),
-    /// Stacker-db instance rejected the chunk
-    #[error("Stacker-db rejected the chunk. Reason: {0}")]
-    PutChunkRejected(String),
+    /// Stacker-db instance rejected the chunk, after the given number of put attempts spanning
+    /// the given amount of time
+    #[error("Stacker-db rejected the chunk after {attempts} attempt(s) in {elapsed:?}. Reason: {reason}")]
+    PutChunkRejected {
+        /// The reason given by the node
+        reason: String,
+        /// The number of times the chunk was put to the node
+        attempts: u32,
+        /// How long we spent trying
+        elapsed: Duration,
+    },
     /// Failed to call a read only function
     #[error("Failed to call read only function. {0}")]
     ReadOnlyFailure(String),
@@ -86,6 +94,26 @@ pub enum ClientError {
     /// Invalid response from the stacks node
     #[error("Invalid response from the stacks node: {0}")]
     InvalidResponse(String),
+    /// The stacker-db self-test found our slot unreadable, or not written by our key
+    #[error("Stacker-db self-test failed: {0}")]
+    SelfTestFailed(String),
+    /// A message was tagged for a different network than the one this client is configured for
+    #[error("Message is for chain ID {0}, but this client is on chain ID {1}")]
+    NetworkMismatch(u32, u32),
+    /// Too few signers saw a message before the timeout
+    #[error("Only {acknowledged} of the {quorum} signers needed for a quorum saw the message before the timeout")]
+    QuorumTimeout {
+        /// how many signers saw the message
+        acknowledged: usize,
+        /// how many signers needed to see it
+        quorum: usize,
+    },
+    /// A message is too big to be split into parts that each fit into a chunk
+    #[error("Message of {0} bytes needs more parts than a multi-part message can have")]
+    MessageTooLarge(usize),
+    /// Messages can't be sent together in one chunk
+    #[error("Cannot batch messages: {0}")]
+    InvalidBatch(String),
 }
 
 /// Retry a function F with an exponential backoff and notification on transient failure
@@ -108,6 +136,35 @@ where
     backoff::retry_notify(backoff_timer, request_fn, notify).map_err(|_| ClientError::RetryTimeout)
 }
 
+/// Retry a function F with an exponential backoff and notification on transient failure, but
+/// give up with `ClientError::RetryTimeout` once `deadline` (if given) has passed.  F is always
+/// called at least once.
+pub fn retry_with_exponential_backoff_until<F, E, T>(
+    request_fn: F,
+    deadline: Option<Instant>,
+) -> Result<T, ClientError>
+where
+    F: FnMut() -> Result<T, backoff::Error<E>>,
+    E: std::fmt::Debug,
+{
+    let Some(deadline) = deadline else {
+        return retry_with_exponential_backoff(request_fn);
+    };
+    let notify = |err, dur| {
+        debug!(
+            "Failed to connect to stacks node and/or deserialize its response: {err:?}. Next attempt in {dur:?}"
+        );
+    };
+
+    let backoff_timer = backoff::ExponentialBackoffBuilder::new()
+        .with_initial_interval(Duration::from_millis(BACKOFF_INITIAL_INTERVAL))
+        .with_max_interval(Duration::from_millis(BACKOFF_MAX_INTERVAL))
+        .with_max_elapsed_time(Some(deadline.saturating_duration_since(Instant::now())))
+        .build();
+
+    backoff::retry_notify(backoff_timer, request_fn, notify).map_err(|_| ClientError::RetryTimeout)
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use std::io::{Read, Write};
@@ -558,6 +615,7 @@ pub(crate) mod tests {
             tx_fee_ustx: config.tx_fee_ustx,
             max_tx_fee_ustx: config.max_tx_fee_ustx,
             db_path: config.db_path.clone(),
+            block_response_metadata: config.block_response_metadata,
         }
     }
 
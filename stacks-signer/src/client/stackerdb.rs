@@ -14,22 +14,37 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 //
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use blockstack_lib::chainstate::nakamoto::signer_set::NakamotoSigners;
+use blockstack_lib::chainstate::stacks::boot::SIGNERS_NAME;
 use blockstack_lib::chainstate::stacks::StacksTransaction;
 use blockstack_lib::net::api::poststackerdbchunk::StackerDBErrorCodes;
+use blockstack_lib::util_lib::boot::boot_code_id;
 use hashbrown::HashMap;
-use libsigner::v1::messages::{MessageSlotID, SignerMessage};
+use libsigner::v1::messages::{BlockResponse, MessageSlotID, SignerMessage, StateCheckpoint};
 use libsigner::{SignerSession, StackerDBSession};
-use libstackerdb::{StackerDBChunkAckData, StackerDBChunkData};
+use libstackerdb::{StackerDBChunkAckData, StackerDBChunkData, STACKERDB_MAX_CHUNK_SIZE};
 use slog::{slog_debug, slog_error, slog_warn};
-use stacks_common::codec::{read_next, StacksMessageCodec};
-use stacks_common::types::chainstate::StacksPrivateKey;
+use stacks_common::codec::{read_next, write_next, Error as CodecError, StacksMessageCodec};
+use stacks_common::consts::{CHAIN_ID_MAINNET, CHAIN_ID_TESTNET};
+use stacks_common::types::chainstate::{StacksAddress, StacksPrivateKey, StacksPublicKey};
+use stacks_common::util::get_epoch_time_secs;
+use stacks_common::util::hash::Sha512Trunc256Sum;
 use stacks_common::{debug, error, warn};
 use wsts::net::Packet;
 
 use super::ClientError;
-use crate::client::retry_with_exponential_backoff;
+use crate::client::{
+    retry_with_exponential_backoff, retry_with_exponential_backoff_until, StacksClient,
+};
 use crate::config::SignerConfig;
 
+/// How often `put_and_await_quorum()` re-reads the signer slots
+const QUORUM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 /// The signer StackerDB slot ID, purposefully wrapped to prevent conflation with SignerID
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, PartialOrd, Ord)]
 pub struct SignerSlotID(pub u32);
@@ -40,43 +55,280 @@ impl std::fmt::Display for SignerSlotID {
     }
 }
 
+/// A signer message, along with the signer slot it was read from.  Only the signer that owns a
+/// slot can write to it, so the slot identifies the message's author.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthoredSignerMessage {
+    /// The signer slot the message was read from
+    pub author_slot: SignerSlotID,
+    /// The message itself
+    pub message: SignerMessage,
+}
+
+impl From<(SignerSlotID, SignerMessage)> for AuthoredSignerMessage {
+    fn from((author_slot, message): (SignerSlotID, SignerMessage)) -> Self {
+        Self {
+            author_slot,
+            message,
+        }
+    }
+}
+
+/// How many bytes a `MessagePart` adds on top of the message bytes it carries
+pub const MESSAGE_PART_HEADER_LEN: usize = 16;
+
+/// One part of a signer message that is too big for a single StackerDB chunk.  The parts of a
+/// message are put into the same slot at successive versions, in order, and put back together
+/// by a `MessageReassembler`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessagePart {
+    /// Identifies the message this is a part of
+    pub message_id: u64,
+    /// Which part this is, starting at 0
+    pub part_index: u16,
+    /// How many parts the message was split into
+    pub total_parts: u16,
+    /// This part's share of the serialized message
+    pub data: Vec<u8>,
+}
+
+impl StacksMessageCodec for MessagePart {
+    fn consensus_serialize<W: std::io::Write>(&self, fd: &mut W) -> Result<(), CodecError> {
+        write_next(fd, &self.message_id)?;
+        write_next(fd, &self.part_index)?;
+        write_next(fd, &self.total_parts)?;
+        write_next(fd, &self.data)?;
+        Ok(())
+    }
+
+    fn consensus_deserialize<R: std::io::Read>(fd: &mut R) -> Result<Self, CodecError> {
+        let message_id = read_next::<u64, _>(fd)?;
+        let part_index = read_next::<u16, _>(fd)?;
+        let total_parts = read_next::<u16, _>(fd)?;
+        if part_index >= total_parts {
+            return Err(CodecError::DeserializeError(format!(
+                "Message part index {part_index} is out of range for {total_parts} parts"
+            )));
+        }
+        let data = read_next::<Vec<u8>, _>(fd)?;
+        Ok(Self {
+            message_id,
+            part_index,
+            total_parts,
+            data,
+        })
+    }
+}
+
+impl MessagePart {
+    /// Split serialized message bytes into parts that carry at most `max_part_len` bytes each.
+    /// The message ID is taken from the hash of the bytes, so readers can tell apart the parts
+    /// of successive messages written to the same slot.
+    pub fn split(message_bytes: &[u8], max_part_len: usize) -> Result<Vec<Self>, ClientError> {
+        let max_part_len = max_part_len.max(1);
+        let total_parts = u16::try_from(message_bytes.len().div_ceil(max_part_len).max(1))
+            .map_err(|_| ClientError::MessageTooLarge(message_bytes.len()))?;
+        let mut message_id_bytes = [0u8; 8];
+        message_id_bytes
+            .copy_from_slice(&Sha512Trunc256Sum::from_data(message_bytes).as_bytes()[0..8]);
+        let message_id = u64::from_be_bytes(message_id_bytes);
+
+        let mut parts: Vec<Self> = message_bytes
+            .chunks(max_part_len)
+            .zip(0..total_parts)
+            .map(|(data, part_index)| Self {
+                message_id,
+                part_index,
+                total_parts,
+                data: data.to_vec(),
+            })
+            .collect();
+        if parts.is_empty() {
+            parts.push(Self {
+                message_id,
+                part_index: 0,
+                total_parts,
+                data: vec![],
+            });
+        }
+        Ok(parts)
+    }
+}
+
+/// The parts of a multi-part message read from one slot so far
+#[derive(Debug)]
+struct PendingMessage {
+    message_id: u64,
+    parts: Vec<Option<Vec<u8>>>,
+}
+
+/// Puts multi-part messages back together as their parts are read from the signer slots.
+/// Only the latest version of a slot can be read, so the reader must see every part before the
+/// next one overwrites it; a part of a new message discards the parts of any unfinished message
+/// from the same slot.
+#[derive(Debug, Default)]
+pub struct MessageReassembler {
+    pending: HashMap<SignerSlotID, PendingMessage>,
+}
+
+impl MessageReassembler {
+    /// Add a chunk read from `slot_id`.  Returns the message once all of its parts are in.
+    pub fn add_chunk(
+        &mut self,
+        slot_id: SignerSlotID,
+        chunk: &[u8],
+    ) -> Result<Option<SignerMessage>, ClientError> {
+        let part = read_next::<MessagePart, _>(&mut &chunk[..])?;
+        self.add_part(slot_id, part)
+    }
+
+    /// Add a part read from `slot_id`.  Returns the message once all of its parts are in.
+    pub fn add_part(
+        &mut self,
+        slot_id: SignerSlotID,
+        part: MessagePart,
+    ) -> Result<Option<SignerMessage>, ClientError> {
+        let pending = self
+            .pending
+            .entry(slot_id)
+            .or_insert_with(|| PendingMessage {
+                message_id: part.message_id,
+                parts: vec![None; part.total_parts.into()],
+            });
+        if pending.message_id != part.message_id
+            || pending.parts.len() != usize::from(part.total_parts)
+        {
+            *pending = PendingMessage {
+                message_id: part.message_id,
+                parts: vec![None; part.total_parts.into()],
+            };
+        }
+        pending.parts[usize::from(part.part_index)] = Some(part.data);
+        if pending.parts.iter().any(Option::is_none) {
+            return Ok(None);
+        }
+
+        let Some(pending) = self.pending.remove(&slot_id) else {
+            return Ok(None);
+        };
+        let message_bytes: Vec<u8> = pending.parts.into_iter().flatten().flatten().collect();
+        let message = read_next::<SignerMessage, _>(&mut &message_bytes[..])?;
+        Ok(Some(message))
+    }
+
+    /// Number of slots with an unfinished message
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+/// Signs StackerDB chunks on behalf of this signer.  The signer's key is used in-process by
+/// default, but it can be kept elsewhere (e.g. in an HSM, or by a remote signing service).
+pub trait ChunkSigner: Send + std::fmt::Debug {
+    /// Sign the chunk in place
+    fn sign(&self, chunk: &mut StackerDBChunkData) -> Result<(), ClientError>;
+}
+
+impl ChunkSigner for StacksPrivateKey {
+    fn sign(&self, chunk: &mut StackerDBChunkData) -> Result<(), ClientError> {
+        chunk.sign(self)?;
+        Ok(())
+    }
+}
+
 /// The StackerDB client for communicating with the .signers contract
 #[derive(Debug)]
 pub struct StackerDB {
     /// The stacker-db sessions for each signer set and message type.
     /// Maps message ID to the DB session.
     signers_message_stackerdb_sessions: HashMap<MessageSlotID, StackerDBSession>,
-    /// The private key used in all stacks node communications
-    stacks_private_key: StacksPrivateKey,
+    /// Signs the chunks we put to the stacks node
+    chunk_signer: Box<dyn ChunkSigner>,
     /// A map of a message ID to last chunk version for each session
     slot_versions: HashMap<MessageSlotID, HashMap<SignerSlotID, u32>>,
+    /// A map of a message ID to the last chunk version we put into our slot.  Only kept in
+    /// debug builds, to check `slot_versions` against.
+    #[cfg(debug_assertions)]
+    sent_versions: HashMap<MessageSlotID, u32>,
+    /// A map of a message ID to the highest chunk version read from each signer slot
+    read_versions: HashMap<MessageSlotID, HashMap<SignerSlotID, u32>>,
     /// The signer slot ID -- the index into the signer list for this signer daemon's signing key.
     signer_slot_id: SignerSlotID,
     /// The reward cycle of the connecting signer
     reward_cycle: u64,
     /// The stacker-db transaction msg session for the NEXT reward cycle
     next_transaction_session: StackerDBSession,
+    /// The chain ID of the network this client is configured for
+    chain_id: u32,
+    /// The latest state of our slots, as of the messages we have put into them
+    checkpoint: StateCheckpoint,
+    /// The stacks node we talk to
+    host: String,
+    /// How many message slot indices past `MessageSlotID::ALL` to read raw chunks from, in case
+    /// newer signers write message types we don't know about.  0 means none.
+    unknown_slots_to_read: u32,
+    /// The stacker-db sessions for the unknown message slot indices that have been read
+    unknown_slot_sessions: HashMap<u32, StackerDBSession>,
+    /// The most message bytes `send_large_message_with_retry` puts into a single chunk
+    max_message_part_len: usize,
+    /// A map of a message ID to when (UNIX time in seconds) a chunk was last accepted into our slot
+    last_write_times: HashMap<MessageSlotID, u64>,
+    /// Where `slot_versions` is saved after each accepted chunk, so a restarted signer can pick
+    /// up where it left off instead of colliding with its own last-written versions
+    slot_versions_path: Option<PathBuf>,
 }
 
 impl From<&SignerConfig> for StackerDB {
     fn from(config: &SignerConfig) -> Self {
+        // keep the slot versions next to the signer DB, if it's on disk.  The signers of
+        // consecutive reward cycles run side by side, so each reward cycle parity gets its own.
+        let slot_versions_path = (config.db_path != Path::new(":memory:")).then(|| {
+            config
+                .db_path
+                .with_extension(format!("slot-versions.{}", config.reward_cycle % 2))
+        });
         Self::new(
             &config.node_host,
             config.stacks_private_key,
             config.mainnet,
             config.reward_cycle,
             config.signer_slot_id,
+            slot_versions_path,
         )
     }
 }
 impl StackerDB {
-    /// Create a new StackerDB client
+    /// Create a new StackerDB client, which signs its chunks with the given private key.
+    /// If `slot_versions_path` is given, our slot versions are loaded from it (see
+    /// `set_slot_versions_path()`).
     pub fn new(
         host: &str,
         stacks_private_key: StacksPrivateKey,
         is_mainnet: bool,
         reward_cycle: u64,
         signer_slot_id: SignerSlotID,
+        slot_versions_path: Option<PathBuf>,
+    ) -> Self {
+        let mut stackerdb = Self::new_with_chunk_signer(
+            host,
+            Box::new(stacks_private_key),
+            is_mainnet,
+            reward_cycle,
+            signer_slot_id,
+        );
+        if let Some(path) = slot_versions_path {
+            stackerdb.set_slot_versions_path(path);
+        }
+        stackerdb
+    }
+
+    /// Create a new StackerDB client, which has its chunks signed by `chunk_signer`
+    pub fn new_with_chunk_signer(
+        host: &str,
+        chunk_signer: Box<dyn ChunkSigner>,
+        is_mainnet: bool,
+        reward_cycle: u64,
+        signer_slot_id: SignerSlotID,
     ) -> Self {
         let mut signers_message_stackerdb_sessions = HashMap::new();
         for msg_id in MessageSlotID::ALL {
@@ -93,22 +345,285 @@ impl StackerDB {
 
         Self {
             signers_message_stackerdb_sessions,
-            stacks_private_key,
+            chunk_signer,
             slot_versions: HashMap::new(),
+            #[cfg(debug_assertions)]
+            sent_versions: HashMap::new(),
+            read_versions: HashMap::new(),
             signer_slot_id,
             reward_cycle,
             next_transaction_session,
+            chain_id: if is_mainnet {
+                CHAIN_ID_MAINNET
+            } else {
+                CHAIN_ID_TESTNET
+            },
+            checkpoint: StateCheckpoint::new(reward_cycle),
+            host: host.to_string(),
+            unknown_slots_to_read: 0,
+            unknown_slot_sessions: HashMap::new(),
+            max_message_part_len: STACKERDB_MAX_CHUNK_SIZE as usize - MESSAGE_PART_HEADER_LEN,
+            last_write_times: HashMap::new(),
+            slot_versions_path: None,
+        }
+    }
+
+    /// Save our slot versions to `path` whenever a chunk is accepted, and load the ones saved
+    /// there by an earlier client (for the same reward cycle and slot), so that our first sends
+    /// after a restart use the right versions rather than being rejected with
+    /// `DataAlreadyExists`.  A missing or unreadable file is ignored.
+    pub fn set_slot_versions_path(&mut self, path: PathBuf) {
+        match Self::load_slot_versions(&path, self.reward_cycle, self.signer_slot_id) {
+            Ok(slot_versions) => {
+                debug!(
+                    "Loaded versions of {} slots from {}",
+                    slot_versions.len(),
+                    path.display()
+                );
+                for (msg_id, version) in slot_versions {
+                    let next_version = self
+                        .slot_versions
+                        .entry(msg_id)
+                        .or_default()
+                        .entry(self.signer_slot_id)
+                        .or_insert(0);
+                    *next_version = (*next_version).max(version);
+                }
+            }
+            Err(e) => {
+                if path.exists() {
+                    warn!(
+                        "Failed to load slot versions from {}, so starting without them: {e}",
+                        path.display()
+                    );
+                }
+            }
+        }
+        self.slot_versions_path = Some(path);
+    }
+
+    /// Read the (message ID, next version) pairs for our slot saved by `save_slot_versions()`.
+    /// Returns none if they were saved for another reward cycle or slot.
+    fn load_slot_versions(
+        path: &Path,
+        reward_cycle: u64,
+        signer_slot_id: SignerSlotID,
+    ) -> Result<Vec<(MessageSlotID, u32)>, CodecError> {
+        let bytes = fs::read(path).map_err(CodecError::ReadError)?;
+        let fd = &mut &bytes[..];
+        let saved_reward_cycle = read_next::<u64, _>(fd)?;
+        let saved_slot_id = read_next::<u32, _>(fd)?;
+        let num_versions = read_next::<u32, _>(fd)?;
+        if saved_reward_cycle != reward_cycle || saved_slot_id != signer_slot_id.0 {
+            return Ok(vec![]);
+        }
+        let mut slot_versions = vec![];
+        for _ in 0..num_versions {
+            let msg_id_byte = read_next::<u8, _>(fd)?;
+            let msg_id = MessageSlotID::from_u8(msg_id_byte).ok_or_else(|| {
+                CodecError::DeserializeError(format!("Unknown message slot ID: {msg_id_byte}"))
+            })?;
+            slot_versions.push((msg_id, read_next::<u32, _>(fd)?));
+        }
+        Ok(slot_versions)
+    }
+
+    /// Write our slot versions to `slot_versions_path`, if set.  Failures are only logged, since
+    /// at worst they cost a `DataAlreadyExists` round-trip after a restart.
+    fn save_slot_versions(&self) {
+        let Some(path) = self.slot_versions_path.as_ref() else {
+            return;
+        };
+        let slot_versions: Vec<_> = self
+            .slot_versions
+            .iter()
+            .filter_map(|(msg_id, versions)| {
+                versions
+                    .get(&self.signer_slot_id)
+                    .map(|version| (msg_id.to_u8(), *version))
+            })
+            .collect();
+        let mut bytes = vec![];
+        let res = write_next(&mut bytes, &self.reward_cycle)
+            .and_then(|_| write_next(&mut bytes, &self.signer_slot_id.0))
+            .and_then(|_| write_next(&mut bytes, &(slot_versions.len() as u32)))
+            .and_then(|_| {
+                slot_versions.iter().try_for_each(|(msg_id, version)| {
+                    write_next(&mut bytes, msg_id)?;
+                    write_next(&mut bytes, version)
+                })
+            });
+        if let Err(e) = res {
+            warn!("Failed to encode slot versions: {e}");
+            return;
+        }
+        // write-then-rename, so a crash can't leave a half-written file behind.  The temporary
+        // file keeps the reward cycle parity, so the two cycles' clients don't share it.
+        let tmp_path = format!("{}.tmp", path.display());
+        if let Err(e) = fs::write(&tmp_path, &bytes).and_then(|_| fs::rename(&tmp_path, path)) {
+            warn!("Failed to save slot versions to {}: {e}", path.display());
         }
     }
 
-    /// Sends messages to the .signers stacker-db with an exponential backoff retry
+    /// Set how many message slot indices past the ones we know about `read_unknown_slots` should
+    /// read from.  0 (the default) turns it off.
+    pub fn set_unknown_slots_to_read(&mut self, count: u32) {
+        self.unknown_slots_to_read = count;
+    }
+
+    /// Sends messages to the .signers stacker-db with an exponential backoff retry.
+    /// Refuses to send a message tagged with another network's chain ID.
     pub fn send_message_with_retry(
         &mut self,
         message: SignerMessage,
     ) -> Result<StackerDBChunkAckData, ClientError> {
+        let message_bytes = message.serialize_to_vec();
+        self.send_chunk_messages_with_retry(&[message], message_bytes, None)
+    }
+
+    /// Sends several messages to the .signers stacker-db in a single chunk (written with
+    /// `SignerMessage::write_batch`), with an exponential backoff retry.  This costs one chunk
+    /// version and one round-trip for the lot, but readers of the slot have to know to read it
+    /// with `SignerMessage::read_batch`.
+    /// The messages must all go into the same message slot, i.e. form a single batch per
+    /// `batch_by_slot()`.  Refuses to send a message tagged with another network's chain ID.
+    pub fn send_messages_with_retry(
+        &mut self,
+        messages: Vec<SignerMessage>,
+    ) -> Result<StackerDBChunkAckData, ClientError> {
+        match Self::batch_by_slot(&messages).as_slice() {
+            [] => return Err(ClientError::InvalidBatch("no messages to send".into())),
+            [_] => {}
+            [batch, next, ..] => {
+                return Err(ClientError::InvalidBatch(format!(
+                    "message {} does not go into slot {}",
+                    next[0].short_id(),
+                    batch[0].msg_id()
+                )))
+            }
+        }
+        let mut message_bytes = vec![];
+        SignerMessage::write_batch(&mut message_bytes, &messages)?;
+        self.send_chunk_messages_with_retry(&messages, message_bytes, None)
+    }
+
+    /// Put the serialized form of `messages`, which all go into the same slot, into a single
+    /// chunk.  Once it's accepted, the last of them is recorded in our checkpoint as the slot's
+    /// latest message.  Retries stop once `deadline` (if given) has passed.
+    fn send_chunk_messages_with_retry(
+        &mut self,
+        messages: &[SignerMessage],
+        message_bytes: Vec<u8>,
+        deadline: Option<Instant>,
+    ) -> Result<StackerDBChunkAckData, ClientError> {
+        for message in messages.iter() {
+            if let Some(chain_id) = message.chain_id() {
+                if chain_id != self.chain_id {
+                    warn!(
+                        "Refusing to send a message for chain ID {chain_id} on chain ID {}",
+                        self.chain_id
+                    );
+                    return Err(ClientError::NetworkMismatch(chain_id, self.chain_id));
+                }
+            }
+        }
+        let Some(msg_id) = messages.first().map(SignerMessage::msg_id) else {
+            return Err(ClientError::InvalidBatch("no messages to send".into()));
+        };
+        for message in messages.iter() {
+            debug!("Sending signer message {}", message.short_id());
+        }
+        let chunk_ack = self.send_message_bytes_before(&msg_id, message_bytes, deadline)?;
+        if chunk_ack.accepted {
+            // the version we just put is one behind the next one we'd use
+            if let Some(next_version) = self
+                .slot_versions
+                .get(&msg_id)
+                .and_then(|versions| versions.get(&self.signer_slot_id))
+                .copied()
+            {
+                if let Some(message) = messages.last() {
+                    self.checkpoint
+                        .record(message, next_version.saturating_sub(1));
+                }
+            }
+        }
+        Ok(chunk_ack)
+    }
+
+    /// Set the most message bytes `send_large_message_with_retry` puts into a single chunk.
+    /// Defaults to whatever fits into the largest chunk StackerDB allows.
+    pub fn set_max_message_part_len(&mut self, max_message_part_len: usize) {
+        self.max_message_part_len = max_message_part_len.max(1);
+    }
+
+    /// Sends a message that may be too big for a single chunk to the .signers stacker-db, with
+    /// an exponential backoff retry for each part.  The message is split into `MessagePart`s,
+    /// which are put into our slot at successive versions; readers put them back together with a
+    /// `MessageReassembler`.  Stops at the first part that is not accepted.
+    /// Returns the acks of the parts that were sent.
+    pub fn send_large_message_with_retry(
+        &mut self,
+        message: SignerMessage,
+    ) -> Result<Vec<StackerDBChunkAckData>, ClientError> {
+        if let Some(chain_id) = message.chain_id() {
+            if chain_id != self.chain_id {
+                warn!(
+                    "Refusing to send a message for chain ID {chain_id} on chain ID {}",
+                    self.chain_id
+                );
+                return Err(ClientError::NetworkMismatch(chain_id, self.chain_id));
+            }
+        }
         let msg_id = message.msg_id();
         let message_bytes = message.serialize_to_vec();
-        self.send_message_bytes_with_retry(&msg_id, message_bytes)
+        let parts = MessagePart::split(&message_bytes, self.max_message_part_len)?;
+        debug!(
+            "Sending signer message {} in {} part(s)",
+            message.short_id(),
+            parts.len()
+        );
+        let mut chunk_acks = Vec::with_capacity(parts.len());
+        for part in parts {
+            let chunk_ack = self.send_message_bytes_with_retry(&msg_id, part.serialize_to_vec())?;
+            let accepted = chunk_ack.accepted;
+            chunk_acks.push(chunk_ack);
+            if !accepted {
+                break;
+            }
+        }
+        Ok(chunk_acks)
+    }
+
+    /// The latest state of our slots, as of the messages we have put into them
+    /// TODO: this can only be handed to local tooling for now.  Like the encrypted signer state,
+    /// a checkpoint needs a `.signers` slot that doesn't exist until the number of signer slots
+    /// can be expanded.
+    pub fn state_checkpoint(&self) -> &StateCheckpoint {
+        &self.checkpoint
+    }
+
+    /// Split `messages` into the batches that `send_messages_with_retry` puts into one chunk
+    /// each: runs of consecutive messages that go into the same message slot.
+    pub fn batch_by_slot(messages: &[SignerMessage]) -> Vec<&[SignerMessage]> {
+        let mut batches = vec![];
+        let mut start = 0;
+        for i in 1..=messages.len() {
+            if i == messages.len() || messages[i].msg_id() != messages[start].msg_id() {
+                batches.push(&messages[start..i]);
+                start = i;
+            }
+        }
+        batches
+    }
+
+    /// How many chunks sending `messages` will sign, if each batch from `batch_by_slot()` is
+    /// sent with `send_messages_with_retry` and accepted on the first try.  Each version
+    /// conflict reported by the node costs one more signature on top of this.
+    /// NOTE: chunks are signed on the calling thread, one per put, so there is no signing to
+    /// offload; a caller worried about signing costs should batch its messages instead.
+    pub fn estimate_signing_ops(messages: &[SignerMessage]) -> usize {
+        Self::batch_by_slot(messages).len()
     }
 
     /// Sends message (as a raw msg ID and bytes) to the .signers stacker-db with an
@@ -117,8 +632,114 @@ impl StackerDB {
         &mut self,
         msg_id: &MessageSlotID,
         message_bytes: Vec<u8>,
+    ) -> Result<StackerDBChunkAckData, ClientError> {
+        self.send_message_bytes_before(msg_id, message_bytes, None)
+    }
+
+    /// Like `send_message_bytes_with_retry()`, but retries stop once `deadline` (if given) has
+    /// passed
+    fn send_message_bytes_before(
+        &mut self,
+        msg_id: &MessageSlotID,
+        message_bytes: Vec<u8>,
+        deadline: Option<Instant>,
+    ) -> Result<StackerDBChunkAckData, ClientError> {
+        let result = self.put_message_bytes_with_retry(msg_id, message_bytes, deadline);
+        #[cfg(debug_assertions)]
+        self.assert_version_invariants();
+        result
+    }
+
+    /// How many seconds ago a chunk was last accepted into our slot for the given message ID, or
+    /// None if none has been since this client was created.  A slot that stops being written to
+    /// during an active tenure suggests this signer is stuck.
+    pub fn seconds_since_last_write(&self, msg_id: &MessageSlotID) -> Option<u64> {
+        self.last_write_times
+            .get(msg_id)
+            .map(|last_write| get_epoch_time_secs().saturating_sub(*last_write))
+    }
+
+    /// Check that `signer_slot_id` is the slot the .signers contract assigns to the key our
+    /// chunks are signed with.  If it isn't, every chunk we put is rejected by the node, so this
+    /// is worth calling at startup.  Returns Ok(false) on a mismatch, or if the slot does not
+    /// exist in the current signer set.  The signer set is read through `stacks_client`.
+    pub fn verify_slot_ownership(
+        &mut self,
+        stacks_client: &StacksClient,
+    ) -> Result<bool, ClientError> {
+        let mainnet = self.chain_id == CHAIN_ID_MAINNET;
+        // The chunk signer may not hand out its key, so recover it from a signed probe chunk
+        let mut probe = StackerDBChunkData::new(self.signer_slot_id.0, 0, vec![]);
+        self.chunk_signer.sign(&mut probe)?;
+        let public_key: StacksPublicKey = probe.recover_pk()?;
+        let address = StacksAddress::p2pkh(mainnet, &public_key);
+
+        let signer_set =
+            u32::try_from(self.reward_cycle % 2).expect("FATAL: reward_cycle % 2 exceeds u32::MAX");
+        let signer_slots = stacks_client
+            .get_stackerdb_signer_slots(&boot_code_id(SIGNERS_NAME, mainnet), signer_set)?;
+        let slot_index = usize::try_from(self.signer_slot_id.0)
+            .expect("infallible: signer slot id exceeds usize::MAX");
+        let Some((owner, _)) = signer_slots.get(slot_index) else {
+            warn!(
+                "Signer slot {} is not in the signer set of reward cycle {}, which has {} slots",
+                self.signer_slot_id,
+                self.reward_cycle,
+                signer_slots.len()
+            );
+            return Ok(false);
+        };
+        if owner != &address {
+            warn!(
+                "Signer slot {} is owned by {owner}, but our chunks are signed by {address}. Check the signer's stacks_private_key.",
+                self.signer_slot_id
+            );
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Check that the cached slot versions are sane:
+    /// * only our own slot has a cached version, and
+    /// * the version we'll send next is always ahead of the last one we sent, so versions only
+    /// ever go up.
+    /// Panics if not.  A violation means the version bookkeeping in
+    /// `put_message_bytes_with_retry()` is broken, which can leave us looping forever on
+    /// version conflicts.
+    #[cfg(debug_assertions)]
+    pub fn assert_version_invariants(&self) {
+        for (msg_id, versions) in self.slot_versions.iter() {
+            for slot_id in versions.keys() {
+                assert_eq!(
+                    *slot_id, self.signer_slot_id,
+                    "FATAL: cached a version for another signer's slot {slot_id} (message ID {msg_id})"
+                );
+            }
+            let (Some(next_version), Some(sent_version)) = (
+                versions.get(&self.signer_slot_id),
+                self.sent_versions.get(msg_id),
+            ) else {
+                continue;
+            };
+            assert!(
+                next_version > sent_version,
+                "FATAL: next version {next_version} of slot {} (message ID {msg_id}) is not ahead of last-sent version {sent_version}",
+                self.signer_slot_id
+            );
+        }
+    }
+
+    /// Put a message into our slot, retrying on transient failures and version conflicts.  If
+    /// `deadline` is given, then retries stop with `ClientError::RetryTimeout` once it has passed.
+    fn put_message_bytes_with_retry(
+        &mut self,
+        msg_id: &MessageSlotID,
+        message_bytes: Vec<u8>,
+        deadline: Option<Instant>,
     ) -> Result<StackerDBChunkAckData, ClientError> {
         let slot_id = self.signer_slot_id;
+        let start = Instant::now();
+        let mut attempts: u32 = 0;
         loop {
             let mut slot_version = if let Some(versions) = self.slot_versions.get_mut(msg_id) {
                 if let Some(version) = versions.get(&slot_id) {
@@ -134,8 +755,12 @@ impl StackerDB {
                 1
             };
 
-            let mut chunk = StackerDBChunkData::new(slot_id.0, slot_version, message_bytes.clone());
-            chunk.sign(&self.stacks_private_key)?;
+            let chunk = sign_chunk_bytes(
+                message_bytes.clone(),
+                slot_id.0,
+                slot_version,
+                self.chunk_signer.as_ref(),
+            )?;
 
             let Some(session) = self.signers_message_stackerdb_sessions.get_mut(msg_id) else {
                 panic!("FATAL: would loop forever trying to send a message with ID {}, for which we don't have a session", msg_id);
@@ -146,8 +771,12 @@ impl StackerDB {
                 &session.stackerdb_contract_id
             );
 
-            let send_request = || session.put_chunk(&chunk).map_err(backoff::Error::transient);
-            let chunk_ack: StackerDBChunkAckData = retry_with_exponential_backoff(send_request)?;
+            let send_request = || {
+                attempts = attempts.saturating_add(1);
+                session.put_chunk(&chunk).map_err(backoff::Error::transient)
+            };
+            let chunk_ack: StackerDBChunkAckData =
+                retry_with_exponential_backoff_until(send_request, deadline)?;
 
             if let Some(versions) = self.slot_versions.get_mut(msg_id) {
                 // NOTE: per the above, this is always executed
@@ -155,17 +784,32 @@ impl StackerDB {
             } else {
                 return Err(ClientError::NotConnected);
             }
+            #[cfg(debug_assertions)]
+            self.sent_versions.insert(*msg_id, slot_version);
 
             if chunk_ack.accepted {
                 debug!("Chunk accepted by stackerdb: {chunk_ack:?}");
+                let now = get_epoch_time_secs();
+                self.last_write_times.insert(*msg_id, now);
+                crate::monitoring::update_signer_slot_last_write(&msg_id.to_string(), now);
+                self.save_slot_versions();
                 return Ok(chunk_ack);
             } else {
                 warn!("Chunk rejected by stackerdb: {chunk_ack:?}");
             }
             if let Some(code) = chunk_ack.code {
+                let rejected = |attempts| ClientError::PutChunkRejected {
+                    reason: chunk_ack
+                        .reason
+                        .clone()
+                        .unwrap_or_else(|| "No reason given".to_string()),
+                    attempts,
+                    elapsed: start.elapsed(),
+                };
                 match StackerDBErrorCodes::from_code(code) {
+                    // Our version is stale, so retry at the version the node has (or the next one).
                     Some(StackerDBErrorCodes::DataAlreadyExists) => {
-                        if let Some(slot_metadata) = chunk_ack.metadata {
+                        if let Some(slot_metadata) = chunk_ack.metadata.as_ref() {
                             warn!("Failed to send message to stackerdb due to wrong version number. Attempted {}. Expected {}. Retrying...", slot_version, slot_metadata.slot_version);
                             slot_version = slot_metadata.slot_version;
                         } else {
@@ -178,24 +822,131 @@ impl StackerDB {
                             return Err(ClientError::NotConnected);
                         }
                     }
-                    _ => {
-                        warn!("Failed to send message to stackerdb: {:?}", chunk_ack);
-                        return Err(ClientError::PutChunkRejected(
-                            chunk_ack
-                                .reason
-                                .unwrap_or_else(|| "No reason given".to_string()),
-                        ));
+                    // Our slot isn't in the contract (e.g. the signer set changed under us), so
+                    // retrying won't help.  Forget its cached version, so that if it comes back,
+                    // we re-discover its version from the node.
+                    Some(StackerDBErrorCodes::NoSuchSlot) => {
+                        warn!("Failed to send message to stackerdb: no slot {slot_id} for message ID {msg_id}");
+                        self.invalidate_slot(msg_id);
+                        return Err(rejected(attempts));
+                    }
+                    // Our key doesn't own the slot, so every retry would be rejected too.
+                    Some(StackerDBErrorCodes::BadSigner) => {
+                        error!("Failed to send message to stackerdb: our key does not own slot {slot_id}. Check the signer's stacks_private_key (see StackerDB::verify_slot_ownership).");
+                        return Err(rejected(attempts));
+                    }
+                    // A code from a newer node that we don't know how to recover from.
+                    None => {
+                        warn!("Failed to send message to stackerdb with unknown error code {code}: {chunk_ack:?}");
+                        return Err(rejected(attempts));
                     }
                 }
             }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                warn!("Giving up on sending message to stackerdb slot ID {slot_id} and message ID {msg_id}: out of time");
+                return Err(ClientError::RetryTimeout);
+            }
+        }
+    }
+
+    /// Send a block response, and then wait until at least `quorum` signer slots (including our
+    /// own) hold a response to the same block.  Only block responses can reach a quorum, since
+    /// they are the only messages every signer writes for the same thing.
+    /// `timeout` covers sending the response as well as waiting for the quorum.  Fails with
+    /// `ClientError::QuorumTimeout` if there is no quorum within it.
+    pub fn put_and_await_quorum(
+        &mut self,
+        response: BlockResponse,
+        quorum: usize,
+        timeout: Duration,
+    ) -> Result<(), ClientError> {
+        let deadline = Instant::now() + timeout;
+        let message = SignerMessage::BlockResponse(response.clone());
+        let msg_id = message.msg_id();
+        let message_bytes = message.serialize_to_vec();
+        match self.send_chunk_messages_with_retry(
+            std::slice::from_ref(&message),
+            message_bytes,
+            Some(deadline),
+        ) {
+            Ok(_) => {}
+            Err(ClientError::RetryTimeout) if Instant::now() >= deadline => {
+                warn!(
+                    "Signer message {} could not be sent before the timeout",
+                    message.short_id()
+                );
+                return Err(ClientError::QuorumTimeout {
+                    acknowledged: 0,
+                    quorum,
+                });
+            }
+            Err(e) => return Err(e),
+        }
+        loop {
+            let acknowledged = self.count_acknowledgements(&msg_id, &response)?;
+            if acknowledged >= quorum {
+                debug!(
+                    "Signer message {} is visible to {acknowledged} signers",
+                    message.short_id()
+                );
+                return Ok(());
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                warn!(
+                    "Signer message {} is only visible to {acknowledged} of {quorum} signers",
+                    message.short_id()
+                );
+                return Err(ClientError::QuorumTimeout {
+                    acknowledged,
+                    quorum,
+                });
+            }
+            std::thread::sleep(QUORUM_POLL_INTERVAL.min(deadline - now));
+        }
+    }
+
+    /// Count the signer slots of the given message type that acknowledge `response`
+    fn count_acknowledgements(
+        &mut self,
+        msg_id: &MessageSlotID,
+        response: &BlockResponse,
+    ) -> Result<usize, ClientError> {
+        let session = self
+            .signers_message_stackerdb_sessions
+            .get_mut(msg_id)
+            .ok_or(ClientError::NotConnected)?;
+        let send_request = || session.list_chunks().map_err(backoff::Error::transient);
+        let mut slot_ids: Vec<_> = retry_with_exponential_backoff(send_request)?
+            .into_iter()
+            .filter(|slot| slot.slot_version > 0)
+            .map(|slot| slot.slot_id)
+            .collect();
+        slot_ids.sort();
+        slot_ids.dedup();
+        if slot_ids.is_empty() {
+            return Ok(0);
         }
+        Ok(Self::get_messages(session, &slot_ids)?
+            .iter()
+            .filter(|other| acknowledges(response, &other.message))
+            .count())
     }
 
-    /// Get all signer messages from stackerdb for the given slot IDs
+    /// Forget the cached version of our slot for the given message ID, so that the next send to
+    /// it starts over from version 1 and re-discovers the real version from the node.
+    pub fn invalidate_slot(&mut self, msg_id: &MessageSlotID) {
+        self.slot_versions.remove(msg_id);
+        #[cfg(debug_assertions)]
+        self.sent_versions.remove(msg_id);
+    }
+
+    /// Get all signer messages from stackerdb for the given slot IDs, along with the slot each
+    /// was read from
     fn get_messages(
         session: &mut StackerDBSession,
         slot_ids: &[u32],
-    ) -> Result<Vec<SignerMessage>, ClientError> {
+    ) -> Result<Vec<AuthoredSignerMessage>, ClientError> {
         let mut messages = vec![];
         let send_request = || {
             session
@@ -203,99 +954,356 @@ impl StackerDB {
                 .map_err(backoff::Error::transient)
         };
         let chunk_ack = retry_with_exponential_backoff(send_request)?;
-        for (i, chunk) in chunk_ack.iter().enumerate() {
+        for (slot_id, chunk) in slot_ids.iter().zip(chunk_ack.iter()) {
             let Some(data) = chunk else {
                 continue;
             };
             let Ok(message) = read_next::<SignerMessage, _>(&mut &data[..]) else {
                 if !data.is_empty() {
                     warn!("Failed to deserialize chunk data into a SignerMessage");
-                    debug!("slot #{i}: Failed chunk ({}): {data:?}", &data.len(),);
+                    debug!("slot #{slot_id}: Failed chunk ({}): {data:?}", &data.len(),);
                 }
                 continue;
             };
-            messages.push(message);
+            messages.push(AuthoredSignerMessage {
+                author_slot: SignerSlotID(*slot_id),
+                message,
+            });
         }
         Ok(messages)
     }
 
-    /// Get the ordered DKG packets from stackerdb for the signer slot IDs.
-    pub fn get_dkg_packets(
+    /// Get the latest messages of the given type from the given signer slots, each paired with
+    /// the slot (and so the signer) it came from.  Empty and unparseable slots are skipped.
+    pub fn get_authored_messages(
         &mut self,
+        msg_id: &MessageSlotID,
         signer_ids: &[SignerSlotID],
-    ) -> Result<Vec<Packet>, ClientError> {
-        let packet_slots = &[
-            MessageSlotID::DkgBegin,
-            MessageSlotID::DkgPublicShares,
-            MessageSlotID::DkgPrivateBegin,
-            MessageSlotID::DkgPrivateShares,
-            MessageSlotID::DkgEndBegin,
-            MessageSlotID::DkgEnd,
-        ];
-        let slot_ids = signer_ids.iter().map(|id| id.0).collect::<Vec<_>>();
-        let mut packets = vec![];
-        for packet_slot in packet_slots {
-            let session = self
-                .signers_message_stackerdb_sessions
-                .get_mut(packet_slot)
-                .ok_or(ClientError::NotConnected)?;
-            let messages = Self::get_messages(session, &slot_ids)?;
-            for message in messages {
-                let SignerMessage::Packet(packet) = message else {
-                    warn!("Found an unexpected type in a packet slot {packet_slot}");
-                    continue;
-                };
-                packets.push(packet);
-            }
-        }
-        Ok(packets)
+    ) -> Result<Vec<AuthoredSignerMessage>, ClientError> {
+        let session = self
+            .signers_message_stackerdb_sessions
+            .get_mut(msg_id)
+            .ok_or(ClientError::NotConnected)?;
+        let slot_ids: Vec<_> = signer_ids.iter().map(|id| id.0).collect();
+        Self::get_messages(session, &slot_ids)
     }
 
-    /// Get the transactions from stackerdb for the signers
-    fn get_transactions(
-        transactions_session: &mut StackerDBSession,
-        signer_ids: &[SignerSlotID],
-    ) -> Result<Vec<StacksTransaction>, ClientError> {
-        let slot_ids = signer_ids.iter().map(|id| id.0).collect::<Vec<_>>();
-        let messages = Self::get_messages(transactions_session, &slot_ids)?;
-        let mut transactions = vec![];
-        for message in messages {
-            let SignerMessage::Transactions(chunk_transactions) = message else {
-                warn!("Signer wrote an unexpected type to the transactions slot");
-                continue;
-            };
-            transactions.extend(chunk_transactions);
+    /// Get the latest message of the given type in the given signer slot, e.g. to recover what
+    /// we last wrote after a restart.  Returns None if the slot is empty.
+    /// If the slot is ours, this also catches up our cached version of it to the one the node
+    /// has, so that the next send doesn't have to be rejected with `DataAlreadyExists` first.
+    pub fn get_latest_message(
+        &mut self,
+        msg_id: &MessageSlotID,
+        slot_id: SignerSlotID,
+    ) -> Result<Option<SignerMessage>, ClientError> {
+        let session = self
+            .signers_message_stackerdb_sessions
+            .get_mut(msg_id)
+            .ok_or(ClientError::NotConnected)?;
+
+        let send_request = || session.list_chunks().map_err(backoff::Error::transient);
+        let slot_version = retry_with_exponential_backoff(send_request)?
+            .into_iter()
+            .find(|slot| slot.slot_id == slot_id.0)
+            .map(|slot| slot.slot_version)
+            .unwrap_or(0);
+        if slot_version == 0 {
+            debug!("Slot {slot_id} for message ID {msg_id} has never been written");
+            return Ok(None);
         }
-        Ok(transactions)
-    }
 
-    /// Get this signer's latest transactions from stackerdb
-    pub fn get_current_transactions(&mut self) -> Result<Vec<StacksTransaction>, ClientError> {
-        let Some(transactions_session) = self
-            .signers_message_stackerdb_sessions
-            .get_mut(&MessageSlotID::Transactions)
-        else {
-            return Err(ClientError::NotConnected);
+        let send_request = || {
+            session
+                .get_latest_chunks(&[slot_id.0])
+                .map_err(backoff::Error::transient)
         };
-        Self::get_transactions(transactions_session, &[self.signer_slot_id])
+        let chunk = retry_with_exponential_backoff(send_request)?.pop().ok_or(
+            ClientError::UnexpectedResponseFormat(format!(
+                "Missing response for latest chunk request for slot {slot_id}"
+            )),
+        )?;
+
+        if slot_id == self.signer_slot_id {
+            // only ever move forward, in case we've written since listing
+            let next_version = self
+                .slot_versions
+                .entry(*msg_id)
+                .or_default()
+                .entry(slot_id)
+                .or_insert(0);
+            *next_version = (*next_version).max(slot_version.saturating_add(1));
+        }
+
+        let Some(data) = chunk.filter(|data| !data.is_empty()) else {
+            debug!("Slot {slot_id} for message ID {msg_id} is empty");
+            return Ok(None);
+        };
+        Ok(Some(read_next::<SignerMessage, _>(&mut &data[..])?))
     }
 
-    /// Get the latest signer transactions from signer ids for the next reward cycle
-    pub fn get_next_transactions(
-        &mut self,
-        signer_ids: &[SignerSlotID],
-    ) -> Result<Vec<StacksTransaction>, ClientError> {
-        debug!("Getting latest chunks from stackerdb for the following signers: {signer_ids:?}",);
-        Self::get_transactions(&mut self.next_transaction_session, signer_ids)
+    /// Get the highest version written to any signer slot for the given message type, or 0 if
+    /// no slot has been written.  Only reads slot metadata, not chunk data.
+    pub fn max_version_for(&mut self, msg_id: &MessageSlotID) -> Result<u32, ClientError> {
+        let session = self
+            .signers_message_stackerdb_sessions
+            .get_mut(msg_id)
+            .ok_or(ClientError::NotConnected)?;
+        let send_request = || session.list_chunks().map_err(backoff::Error::transient);
+        Ok(retry_with_exponential_backoff(send_request)?
+            .iter()
+            .map(|slot| slot.slot_version)
+            .max()
+            .unwrap_or(0))
     }
 
-    /// Get the encrypted state for the given signer
-    pub fn get_encrypted_signer_state(
+    /// Get the messages of the given type that were written after `last_seen_version`, across
+    /// all signer slots.  Returns (slot ID, message) pairs, ordered by slot ID.
+    pub fn poll_new_chunks(
         &mut self,
-        signer_id: SignerSlotID,
-    ) -> Result<Option<Vec<u8>>, ClientError> {
-        debug!("Getting the persisted encrypted state for signer {signer_id}");
-        let Some(state_session) = self
+        msg_id: &MessageSlotID,
+        last_seen_version: u32,
+    ) -> Result<Vec<(SignerSlotID, SignerMessage)>, ClientError> {
+        let session = self
+            .signers_message_stackerdb_sessions
+            .get_mut(msg_id)
+            .ok_or(ClientError::NotConnected)?;
+
+        let send_request = || session.list_chunks().map_err(backoff::Error::transient);
+        let mut slots_and_versions: Vec<_> = retry_with_exponential_backoff(send_request)?
+            .into_iter()
+            .filter(|slot| slot.slot_version > last_seen_version)
+            .map(|slot| (slot.slot_id, slot.slot_version))
+            .collect();
+        if slots_and_versions.is_empty() {
+            return Ok(vec![]);
+        }
+        slots_and_versions.sort();
+
+        let send_request = || {
+            session
+                .get_chunks(&slots_and_versions)
+                .map_err(backoff::Error::transient)
+        };
+        let chunks = retry_with_exponential_backoff(send_request)?;
+
+        let mut messages = vec![];
+        for ((slot_id, slot_version), chunk) in slots_and_versions.iter().zip(chunks) {
+            let Some(data) = chunk else {
+                // overwritten since we listed it; we'll see the newer version on the next poll
+                continue;
+            };
+            let Ok(message) = read_next::<SignerMessage, _>(&mut &data[..]) else {
+                warn!("Failed to deserialize chunk data into a SignerMessage");
+                debug!(
+                    "slot #{slot_id} version {slot_version}: Failed chunk ({}): {data:?}",
+                    &data.len()
+                );
+                continue;
+            };
+            messages.push((SignerSlotID(*slot_id), message));
+        }
+        Ok(messages)
+    }
+
+    /// Get the messages of the given type from the given signer slots that have not been read
+    /// before.  Only the highest version listed for each slot is fetched, and a (slot, version)
+    /// pair returned by an earlier call is never returned again, so consumers won't process the
+    /// same message twice across polls.
+    /// Returns (slot ID, version, message) triples, ordered by slot ID.
+    pub fn read_new_signer_messages(
+        &mut self,
+        msg_id: &MessageSlotID,
+        signer_ids: &[SignerSlotID],
+    ) -> Result<Vec<(SignerSlotID, u32, SignerMessage)>, ClientError> {
+        let session = self
+            .signers_message_stackerdb_sessions
+            .get_mut(msg_id)
+            .ok_or(ClientError::NotConnected)?;
+        let read_versions = self.read_versions.entry(*msg_id).or_default();
+
+        let send_request = || session.list_chunks().map_err(backoff::Error::transient);
+        let mut latest_versions: HashMap<SignerSlotID, u32> = HashMap::new();
+        for slot in retry_with_exponential_backoff(send_request)? {
+            let slot_id = SignerSlotID(slot.slot_id);
+            if !signer_ids.contains(&slot_id) {
+                continue;
+            }
+            let latest_version = latest_versions.entry(slot_id).or_insert(slot.slot_version);
+            *latest_version = (*latest_version).max(slot.slot_version);
+        }
+
+        let mut slots_and_versions: Vec<_> = latest_versions
+            .into_iter()
+            .filter(|(slot_id, slot_version)| {
+                read_versions
+                    .get(slot_id)
+                    .map(|read_version| slot_version > read_version)
+                    .unwrap_or(true)
+            })
+            .map(|(slot_id, slot_version)| (slot_id.0, slot_version))
+            .collect();
+        if slots_and_versions.is_empty() {
+            return Ok(vec![]);
+        }
+        slots_and_versions.sort();
+
+        let send_request = || {
+            session
+                .get_chunks(&slots_and_versions)
+                .map_err(backoff::Error::transient)
+        };
+        let chunks = retry_with_exponential_backoff(send_request)?;
+
+        let mut messages = vec![];
+        for ((slot_id, slot_version), chunk) in slots_and_versions.iter().zip(chunks) {
+            let Some(data) = chunk else {
+                // overwritten since we listed it; we'll see the newer version on the next poll
+                continue;
+            };
+            // don't re-read this version, even if it's garbage
+            read_versions.insert(SignerSlotID(*slot_id), *slot_version);
+            let Ok(message) = read_next::<SignerMessage, _>(&mut &data[..]) else {
+                warn!("Failed to deserialize chunk data into a SignerMessage");
+                debug!(
+                    "slot #{slot_id} version {slot_version}: Failed chunk ({}): {data:?}",
+                    &data.len()
+                );
+                continue;
+            };
+            debug!(
+                "Read signer message {} from slot #{slot_id} version {slot_version}",
+                message.short_id()
+            );
+            messages.push((SignerSlotID(*slot_id), *slot_version, message));
+        }
+        Ok(messages)
+    }
+
+    /// Get the raw chunks that the given signer wrote to message slots we don't know about (i.e.
+    /// past `MessageSlotID::ALL`), up to `unknown_slots_to_read` of them.  The chunks are not
+    /// parsed, so tooling can inspect messages written by newer signers.
+    /// Returns (message slot index, chunk) pairs, ordered by message slot index.
+    pub fn read_unknown_slots(
+        &mut self,
+        signer_id: SignerSlotID,
+    ) -> Result<Vec<(u32, Vec<u8>)>, ClientError> {
+        let first_unknown = u32::try_from(MessageSlotID::ALL.len())
+            .expect("infallible: message slot count exceeds u32::MAX");
+        let mainnet = self.chain_id == CHAIN_ID_MAINNET;
+        let mut chunks = vec![];
+        for slot_index in first_unknown..first_unknown.saturating_add(self.unknown_slots_to_read) {
+            let session = self
+                .unknown_slot_sessions
+                .entry(slot_index)
+                .or_insert_with(|| {
+                    StackerDBSession::new(
+                        &self.host,
+                        NakamotoSigners::make_signers_db_contract_id(
+                            self.reward_cycle,
+                            slot_index,
+                            mainnet,
+                        ),
+                    )
+                });
+            let send_request = || {
+                session
+                    .get_latest_chunks(&[signer_id.0])
+                    .map_err(backoff::Error::transient)
+            };
+            // a missing contract or slot reads as no chunk
+            let Some(Some(data)) = retry_with_exponential_backoff(send_request)?.pop() else {
+                continue;
+            };
+            if data.is_empty() {
+                continue;
+            }
+            debug!(
+                "Read {} bytes from unknown message slot {slot_index} of signer slot {signer_id}",
+                data.len()
+            );
+            chunks.push((slot_index, data));
+        }
+        Ok(chunks)
+    }
+
+    /// Get the ordered DKG packets from stackerdb for the signer slot IDs.
+    pub fn get_dkg_packets(
+        &mut self,
+        signer_ids: &[SignerSlotID],
+    ) -> Result<Vec<Packet>, ClientError> {
+        let packet_slots = &[
+            MessageSlotID::DkgBegin,
+            MessageSlotID::DkgPublicShares,
+            MessageSlotID::DkgPrivateBegin,
+            MessageSlotID::DkgPrivateShares,
+            MessageSlotID::DkgEndBegin,
+            MessageSlotID::DkgEnd,
+        ];
+        let slot_ids = signer_ids.iter().map(|id| id.0).collect::<Vec<_>>();
+        let mut packets = vec![];
+        for packet_slot in packet_slots {
+            let session = self
+                .signers_message_stackerdb_sessions
+                .get_mut(packet_slot)
+                .ok_or(ClientError::NotConnected)?;
+            let messages = Self::get_messages(session, &slot_ids)?;
+            for AuthoredSignerMessage { message, .. } in messages {
+                let SignerMessage::Packet(packet) = message else {
+                    warn!("Found an unexpected type in a packet slot {packet_slot}");
+                    continue;
+                };
+                packets.push(packet);
+            }
+        }
+        Ok(packets)
+    }
+
+    /// Get the transactions from stackerdb for the signers
+    fn get_transactions(
+        transactions_session: &mut StackerDBSession,
+        signer_ids: &[SignerSlotID],
+    ) -> Result<Vec<StacksTransaction>, ClientError> {
+        let slot_ids = signer_ids.iter().map(|id| id.0).collect::<Vec<_>>();
+        let messages = Self::get_messages(transactions_session, &slot_ids)?;
+        let mut transactions = vec![];
+        for AuthoredSignerMessage { message, .. } in messages {
+            let SignerMessage::Transactions(chunk_transactions) = message else {
+                warn!("Signer wrote an unexpected type to the transactions slot");
+                continue;
+            };
+            transactions.extend(chunk_transactions);
+        }
+        Ok(transactions)
+    }
+
+    /// Get this signer's latest transactions from stackerdb
+    pub fn get_current_transactions(&mut self) -> Result<Vec<StacksTransaction>, ClientError> {
+        let Some(transactions_session) = self
+            .signers_message_stackerdb_sessions
+            .get_mut(&MessageSlotID::Transactions)
+        else {
+            return Err(ClientError::NotConnected);
+        };
+        Self::get_transactions(transactions_session, &[self.signer_slot_id])
+    }
+
+    /// Get the latest signer transactions from signer ids for the next reward cycle
+    pub fn get_next_transactions(
+        &mut self,
+        signer_ids: &[SignerSlotID],
+    ) -> Result<Vec<StacksTransaction>, ClientError> {
+        debug!("Getting latest chunks from stackerdb for the following signers: {signer_ids:?}",);
+        Self::get_transactions(&mut self.next_transaction_session, signer_ids)
+    }
+
+    /// Get the encrypted state for the given signer
+    pub fn get_encrypted_signer_state(
+        &mut self,
+        signer_id: SignerSlotID,
+    ) -> Result<Option<Vec<u8>>, ClientError> {
+        debug!("Getting the persisted encrypted state for signer {signer_id}");
+        let Some(state_session) = self
             .signers_message_stackerdb_sessions
             .get_mut(&MessageSlotID::EncryptedSignerState)
         else {
@@ -334,6 +1342,73 @@ impl StackerDB {
         Ok(Some(state))
     }
 
+    /// Read back this signer's slot to confirm that the node, the .signers contract, and the
+    /// configured key and network all work together, without writing anything: every slot is
+    /// read by someone, so there is no scratch slot to write a probe into.  Checks that our slot
+    /// in the transactions contract can be listed, and, if it has been written, that its latest
+    /// chunk matches its metadata and was signed by our key.  An empty slot only confirms the
+    /// node and the contract.
+    pub fn self_test(&mut self) -> Result<(), ClientError> {
+        let msg_id = MessageSlotID::Transactions;
+        let slot_id = self.signer_slot_id.0;
+        let mainnet = self.chain_id == CHAIN_ID_MAINNET;
+        // The chunk signer may not hand out its key, so recover it from a signed probe chunk
+        let mut probe = StackerDBChunkData::new(slot_id, 0, vec![]);
+        self.chunk_signer.sign(&mut probe)?;
+        let address = StacksAddress::p2pkh(mainnet, &probe.recover_pk()?);
+
+        let session = self
+            .signers_message_stackerdb_sessions
+            .get_mut(&msg_id)
+            .ok_or(ClientError::NotConnected)?;
+        let send_request = || session.list_chunks().map_err(backoff::Error::transient);
+        let Some(metadata) = retry_with_exponential_backoff(send_request)?
+            .into_iter()
+            .find(|metadata| metadata.slot_id == slot_id)
+        else {
+            return Err(ClientError::SelfTestFailed(format!(
+                "slot {} is not in {msg_id}",
+                self.signer_slot_id
+            )));
+        };
+        if metadata.slot_version == 0 {
+            debug!(
+                "Stackerdb self-test passed for slot {}, which is empty, so our key was not checked",
+                self.signer_slot_id
+            );
+            return Ok(());
+        }
+        if !metadata.verify(&address)? {
+            return Err(ClientError::SelfTestFailed(format!(
+                "slot {} in {msg_id} was not signed by our key ({address})",
+                self.signer_slot_id
+            )));
+        }
+
+        let send_request = || {
+            session
+                .get_chunk(slot_id, metadata.slot_version)
+                .map_err(backoff::Error::transient)
+        };
+        let Some(data) = retry_with_exponential_backoff(send_request)? else {
+            return Err(ClientError::SelfTestFailed(format!(
+                "could not read back version {} of slot {} in {msg_id}",
+                metadata.slot_version, self.signer_slot_id
+            )));
+        };
+        if Sha512Trunc256Sum::from_data(&data) != metadata.data_hash {
+            return Err(ClientError::SelfTestFailed(format!(
+                "version {} of slot {} in {msg_id} does not match its metadata",
+                metadata.slot_version, self.signer_slot_id
+            )));
+        }
+        debug!(
+            "Stackerdb self-test passed for slot {}",
+            self.signer_slot_id
+        );
+        Ok(())
+    }
+
     /// Retrieve the signer set this stackerdb client is attached to
     pub fn get_signer_set(&self) -> u32 {
         u32::try_from(self.reward_cycle % 2).expect("FATAL: reward cycle % 2 exceeds u32::MAX")
@@ -345,8 +1420,52 @@ impl StackerDB {
     }
 }
 
+/// Does `other` show that its signer saw `response`?  True if it is a response to the same
+/// block.
+fn acknowledges(response: &BlockResponse, other: &SignerMessage) -> bool {
+    let block_hash = |response: &BlockResponse| match response {
+        BlockResponse::Accepted(accepted) => accepted.signer_signature_hash,
+        BlockResponse::Rejected(rejection) => rejection.signer_signature_hash,
+    };
+    let SignerMessage::BlockResponse(theirs) = other else {
+        return false;
+    };
+    block_hash(response) == block_hash(theirs)
+}
+
+/// Serialize a signer message into a chunk for the given slot and version, signed with `key`.
+/// This is the chunk that `StackerDB` would put to the node for this message.
+pub fn to_signed_chunk(
+    message: &SignerMessage,
+    slot_id: u32,
+    version: u32,
+    key: &StacksPrivateKey,
+) -> Result<StackerDBChunkData, CodecError> {
+    sign_chunk_bytes(message.serialize_to_vec(), slot_id, version, key).map_err(|e| match e {
+        ClientError::StackerDBSerializationError(e) => e,
+        e => CodecError::SigningError(e.to_string()),
+    })
+}
+
+/// Wrap already-serialized message bytes into a chunk for the given slot and version, signed
+/// by `signer`
+fn sign_chunk_bytes(
+    message_bytes: Vec<u8>,
+    slot_id: u32,
+    version: u32,
+    signer: &dyn ChunkSigner,
+) -> Result<StackerDBChunkData, ClientError> {
+    let mut chunk = StackerDBChunkData::new(slot_id, version, message_bytes);
+    signer.sign(&mut chunk)?;
+    Ok(chunk)
+}
+
 #[cfg(test)]
 mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
     use std::thread::spawn;
     use std::time::Duration;
 
@@ -355,9 +1474,18 @@ mod tests {
         TransactionSmartContract, TransactionVersion,
     };
     use blockstack_lib::util_lib::strings::StacksString;
+    use clarity::util::hash::Sha512Trunc256Sum;
+    use clarity::util::secp256k1::MessageSignature;
+    use clarity::vm::types::{PrincipalData, TupleData};
+    use clarity::vm::Value as ClarityValue;
+    use libsigner::v1::messages::{BlockRejection, RejectCode};
+    use libstackerdb::{stackerdb_get_metadata_path, SlotMetadata};
+    use stacks_common::types::chainstate::StacksPublicKey;
 
     use super::*;
-    use crate::client::tests::{generate_signer_config, mock_server_from_config, write_response};
+    use crate::client::tests::{
+        build_read_only_response, generate_signer_config, mock_server_from_config, write_response,
+    };
     use crate::config::GlobalConfig;
 
     #[test]
@@ -403,6 +1531,56 @@ mod tests {
         assert_eq!(transactions, vec![tx]);
     }
 
+    /// An ack for a chunk the node accepted
+    fn accepted_ack() -> StackerDBChunkAckData {
+        StackerDBChunkAckData {
+            accepted: true,
+            reason: None,
+            metadata: None,
+            code: None,
+        }
+    }
+
+    /// Read the chunk the client puts over `stream`, and reply with `ack`.  The chunk is written
+    /// separately from the request headers, so keep reading until all of it has arrived.
+    fn reply_to_put(stream: &mut TcpStream, ack: &StackerDBChunkAckData) -> StackerDBChunkData {
+        let mut request = vec![];
+        let mut buf = [0u8; 1024];
+        let chunk = loop {
+            let nread = stream.read(&mut buf).unwrap();
+            assert!(nread > 0, "Client hung up before sending a whole chunk");
+            request.extend_from_slice(&buf[..nread]);
+            let body_start = request
+                .windows(4)
+                .position(|window| window == b"\r\n\r\n")
+                .map(|pos| pos + 4);
+            if let Some(Ok(chunk)) =
+                body_start.map(|pos| serde_json::from_slice::<StackerDBChunkData>(&request[pos..]))
+            {
+                break chunk;
+            }
+        };
+        let mut response_bytes = b"HTTP/1.1 200 OK\n\n".to_vec();
+        response_bytes.extend(serde_json::to_vec(ack).unwrap());
+        stream.write_all(&response_bytes).unwrap();
+        chunk
+    }
+
+    /// Reply to each of the client's next puts with the matching ack in `acks`, and return the
+    /// chunks it put
+    fn mock_ack_server(
+        config: &GlobalConfig,
+        acks: &[StackerDBChunkAckData],
+    ) -> Vec<StackerDBChunkData> {
+        acks.iter()
+            .map(|ack| {
+                let mock_server = mock_server_from_config(config);
+                let mut stream = mock_server.accept().unwrap().0;
+                reply_to_put(&mut stream, ack)
+            })
+            .collect()
+    }
+
     #[test]
     fn send_signer_message_should_succeed() {
         let config = GlobalConfig::load_from_file("./src/tests/conf/signer-1.toml").unwrap();
@@ -412,7 +1590,7 @@ mod tests {
         let sk = StacksPrivateKey::new();
         let tx = StacksTransaction {
             version: TransactionVersion::Testnet,
-            chain_id: 0,
+            chain_id: CHAIN_ID_TESTNET,
             auth: TransactionAuth::from_p2pkh(&sk).unwrap(),
             anchor_mode: TransactionAnchorMode::Any,
             post_condition_mode: TransactionPostConditionMode::Allow,
@@ -427,19 +1605,1094 @@ mod tests {
         };
 
         let signer_message = SignerMessage::Transactions(vec![tx]);
-        let ack = StackerDBChunkAckData {
-            accepted: true,
-            reason: None,
-            metadata: None,
-            code: None,
-        };
-        let mock_server = mock_server_from_config(&config);
         let h = spawn(move || stackerdb.send_message_with_retry(signer_message));
+        mock_ack_server(&config, &[accepted_ack()]);
+        assert_eq!(accepted_ack(), h.join().unwrap().unwrap());
+    }
+
+    #[test]
+    fn seconds_since_last_write_should_reflect_sends() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-1.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut stackerdb = StackerDB::from(&signer_config);
+        let msg_id = MessageSlotID::Transactions;
+        assert_eq!(stackerdb.seconds_since_last_write(&msg_id), None);
+
+        let h = spawn(move || {
+            let result = stackerdb.send_message_with_retry(SignerMessage::Transactions(vec![]));
+            (stackerdb, result)
+        });
+        mock_ack_server(&config, &[accepted_ack()]);
+
+        let (stackerdb, result) = h.join().unwrap();
+        assert_eq!(result.unwrap(), accepted_ack());
+        let since_last_write = stackerdb
+            .seconds_since_last_write(&msg_id)
+            .expect("No write recorded after a send");
+        assert!(since_last_write <= 1);
+        // other message types haven't been written to
+        assert_eq!(
+            stackerdb.seconds_since_last_write(&MessageSlotID::BlockResponse),
+            None
+        );
+    }
+
+    /// Build a read-only response for `stackerdb-get-signer-slots-page` with one slot per owner
+    fn build_signer_slots_response(owners: &[StacksAddress]) -> String {
+        let slots = owners
+            .iter()
+            .map(|owner| {
+                ClarityValue::Tuple(
+                    TupleData::from_data(vec![
+                        (
+                            "signer".into(),
+                            ClarityValue::Principal(PrincipalData::from(*owner)),
+                        ),
+                        ("num-slots".into(), ClarityValue::UInt(1)),
+                    ])
+                    .unwrap(),
+                )
+            })
+            .collect();
+        let value =
+            ClarityValue::okay(ClarityValue::cons_list_unsanitized(slots).unwrap()).unwrap();
+        build_read_only_response(&value)
+    }
+
+    #[test]
+    fn verify_slot_ownership_should_match_on_contract_owner() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-1.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let slot_index = signer_config.signer_slot_id.0 as usize;
+        let other = StacksAddress::p2pkh(
+            false,
+            &StacksPublicKey::from_private(&StacksPrivateKey::new()),
+        );
+
+        // our key owns our slot
+        let mut owners = vec![other; 5];
+        owners[slot_index] = config.stacks_address;
+        let mut stackerdb = StackerDB::from(&signer_config);
+        let stacks_client = StacksClient::from(&config);
+        let mock_server = mock_server_from_config(&config);
+        let h = spawn(move || stackerdb.verify_slot_ownership(&stacks_client));
+        write_response(mock_server, build_signer_slots_response(&owners).as_bytes());
+        assert!(h.join().unwrap().unwrap());
+
+        // someone else owns our slot
+        owners[slot_index] = other;
+        let mut stackerdb = StackerDB::from(&signer_config);
+        let stacks_client = StacksClient::from(&config);
+        let mock_server = mock_server_from_config(&config);
+        let h = spawn(move || stackerdb.verify_slot_ownership(&stacks_client));
+        write_response(mock_server, build_signer_slots_response(&owners).as_bytes());
+        assert!(!h.join().unwrap().unwrap());
+
+        // our slot isn't in the signer set at all
+        owners.truncate(slot_index);
+        let mut stackerdb = StackerDB::from(&signer_config);
+        let stacks_client = StacksClient::from(&config);
+        let mock_server = mock_server_from_config(&config);
+        let h = spawn(move || stackerdb.verify_slot_ownership(&stacks_client));
+        write_response(mock_server, build_signer_slots_response(&owners).as_bytes());
+        assert!(!h.join().unwrap().unwrap());
+    }
+
+    #[test]
+    fn send_message_with_mismatched_chain_id_should_fail() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-1.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        assert!(!signer_config.mainnet);
+        let mut stackerdb = StackerDB::from(&signer_config);
+
+        let sk = StacksPrivateKey::new();
+        let tx = StacksTransaction {
+            version: TransactionVersion::Mainnet,
+            chain_id: CHAIN_ID_MAINNET,
+            auth: TransactionAuth::from_p2pkh(&sk).unwrap(),
+            anchor_mode: TransactionAnchorMode::Any,
+            post_condition_mode: TransactionPostConditionMode::Allow,
+            post_conditions: vec![],
+            payload: TransactionPayload::SmartContract(
+                TransactionSmartContract {
+                    name: "test-contract".into(),
+                    code_body: StacksString::from_str("(/ 1 0)").unwrap(),
+                },
+                None,
+            ),
+        };
+
+        // no mock server: the send must be refused before anything hits the network
+        let signer_message = SignerMessage::Transactions(vec![tx]);
+        match stackerdb.send_message_with_retry(signer_message) {
+            Err(ClientError::NetworkMismatch(msg_chain_id, client_chain_id)) => {
+                assert_eq!(msg_chain_id, CHAIN_ID_MAINNET);
+                assert_eq!(client_chain_id, CHAIN_ID_TESTNET);
+            }
+            res => panic!("Expected a network mismatch, got {res:?}"),
+        }
+        assert!(stackerdb.slot_versions.is_empty());
+    }
+
+    fn self_test_transaction() -> StacksTransaction {
+        let sk = StacksPrivateKey::new();
+        StacksTransaction {
+            version: TransactionVersion::Testnet,
+            chain_id: 0,
+            auth: TransactionAuth::from_p2pkh(&sk).unwrap(),
+            anchor_mode: TransactionAnchorMode::Any,
+            post_condition_mode: TransactionPostConditionMode::Allow,
+            post_conditions: vec![],
+            payload: TransactionPayload::SmartContract(
+                TransactionSmartContract {
+                    name: "test-contract".into(),
+                    code_body: StacksString::from_str("(/ 1 0)").unwrap(),
+                },
+                None,
+            ),
+        }
+    }
+
+    /// Serve `metadata` as the listing of the transactions contract, then, if asked for a chunk,
+    /// `data`.  Returns the chunk request, if there was one.
+    fn mock_self_test_server(
+        config: &GlobalConfig,
+        metadata: &[SlotMetadata],
+        data: Option<&[u8]>,
+    ) -> Option<String> {
         let mut response_bytes = b"HTTP/1.1 200 OK\n\n".to_vec();
-        let payload = serde_json::to_string(&ack).expect("Failed to serialize ack");
-        response_bytes.extend(payload.as_bytes());
-        std::thread::sleep(Duration::from_millis(500));
+        response_bytes.extend(serde_json::to_vec(metadata).unwrap());
+        let mock_server = mock_server_from_config(config);
         write_response(mock_server, response_bytes.as_slice());
-        assert_eq!(ack, h.join().unwrap().unwrap());
+
+        let data = data?;
+        let mut response_bytes = b"HTTP/1.1 200 OK\n\n".to_vec();
+        response_bytes.extend(data);
+        let mock_server = mock_server_from_config(config);
+        let request_bytes = write_response(mock_server, response_bytes.as_slice());
+        Some(String::from_utf8_lossy(&request_bytes).into_owned())
+    }
+
+    /// Our slot's latest chunk, holding a list of transactions, signed with `key`
+    fn self_test_chunk(signer_config: &SignerConfig, key: &StacksPrivateKey) -> StackerDBChunkData {
+        let data = SignerMessage::Transactions(vec![self_test_transaction()]).serialize_to_vec();
+        let mut chunk = StackerDBChunkData::new(signer_config.signer_slot_id.0, 3, data);
+        chunk.sign(key).unwrap();
+        chunk
+    }
+
+    #[test]
+    fn self_test_should_succeed() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let slot_id = signer_config.signer_slot_id.0;
+        let chunk = self_test_chunk(&signer_config, &signer_config.stacks_private_key);
+        let mut stackerdb = StackerDB::from(&signer_config);
+
+        let h = spawn(move || stackerdb.self_test());
+        let request = mock_self_test_server(
+            &config,
+            &[
+                SlotMetadata::new_unsigned(slot_id + 1, 1, Sha512Trunc256Sum([0u8; 32])),
+                chunk.get_slot_metadata(),
+            ],
+            Some(&chunk.data),
+        )
+        .unwrap();
+        h.join().unwrap().unwrap();
+
+        // nothing is written; the latest version of our slot is read back
+        assert!(request.starts_with("GET "));
+        assert!(request.contains(&format!("/{slot_id}/3 ")));
+
+        // an empty slot can't be checked against our key, but the node and contract can
+        let mut stackerdb = StackerDB::from(&signer_config);
+        let h = spawn(move || stackerdb.self_test());
+        let empty = SlotMetadata::new_unsigned(slot_id, 0, Sha512Trunc256Sum([0u8; 32]));
+        assert!(mock_self_test_server(&config, &[empty], None).is_none());
+        h.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn self_test_should_fail_on_mismatch() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-1.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let chunk = self_test_chunk(&signer_config, &signer_config.stacks_private_key);
+
+        // the slot holds some other data than its metadata commits to
+        let mut stackerdb = StackerDB::from(&signer_config);
+        let h = spawn(move || stackerdb.self_test());
+        let other_data = SignerMessage::Transactions(vec![]).serialize_to_vec();
+        mock_self_test_server(&config, &[chunk.get_slot_metadata()], Some(&other_data));
+        assert!(matches!(
+            h.join().unwrap(),
+            Err(ClientError::SelfTestFailed(_))
+        ));
+
+        // the slot was written with another key than ours
+        let chunk = self_test_chunk(&signer_config, &StacksPrivateKey::new());
+        let mut stackerdb = StackerDB::from(&signer_config);
+        let h = spawn(move || stackerdb.self_test());
+        mock_self_test_server(&config, &[chunk.get_slot_metadata()], None);
+        assert!(matches!(
+            h.join().unwrap(),
+            Err(ClientError::SelfTestFailed(_))
+        ));
+    }
+
+    #[test]
+    fn poll_new_chunks_should_return_newer_versions() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut stackerdb = StackerDB::from(&signer_config);
+
+        let slot_metadata = |slot_id, slot_version| SlotMetadata {
+            slot_id,
+            slot_version,
+            data_hash: Sha512Trunc256Sum([0u8; 32]),
+            signature: MessageSignature::empty(),
+        };
+        // only slot 1 is newer than the last-seen version
+        let metadata = vec![slot_metadata(0, 1), slot_metadata(1, 3)];
+
+        let signer_message = SignerMessage::Transactions(vec![]);
+        let h = spawn(move || stackerdb.poll_new_chunks(&MessageSlotID::Transactions, 2));
+
+        let mut response_bytes = b"HTTP/1.1 200 OK\n\n".to_vec();
+        response_bytes.extend(serde_json::to_vec(&metadata).unwrap());
+        let mock_server = mock_server_from_config(&config);
+        write_response(mock_server, response_bytes.as_slice());
+
+        let mut response_bytes = b"HTTP/1.1 200 OK\n\n".to_vec();
+        response_bytes.extend(signer_message.serialize_to_vec());
+        let mock_server = mock_server_from_config(&config);
+        let request_bytes = write_response(mock_server, response_bytes.as_slice());
+        let request = String::from_utf8_lossy(&request_bytes);
+        assert!(request.contains("/1/3 "));
+
+        let new_chunks = h.join().unwrap().unwrap();
+        assert_eq!(new_chunks, vec![(SignerSlotID(1), signer_message)]);
+    }
+
+    #[test]
+    fn get_authored_messages_should_pair_messages_with_their_slot() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut stackerdb = StackerDB::from(&signer_config);
+
+        let signer_message = block_rejection(1);
+        let expected_message = signer_message.clone();
+        let h = spawn(move || {
+            stackerdb.get_authored_messages(
+                &MessageSlotID::BlockResponse,
+                &[SignerSlotID(2), SignerSlotID(4)],
+            )
+        });
+
+        // slot 2 is empty, so the only message comes from slot 4
+        let mock_server = mock_server_from_config(&config);
+        write_response(mock_server, b"HTTP/1.1 404 Not Found\n\n");
+        let mut response_bytes = b"HTTP/1.1 200 OK\n\n".to_vec();
+        response_bytes.extend(signer_message.serialize_to_vec());
+        let mock_server = mock_server_from_config(&config);
+        let request_bytes = write_response(mock_server, response_bytes.as_slice());
+        assert!(String::from_utf8_lossy(&request_bytes).contains("/4 "));
+
+        let messages = h.join().unwrap().unwrap();
+        assert_eq!(
+            messages,
+            vec![AuthoredSignerMessage {
+                author_slot: SignerSlotID(4),
+                message: expected_message,
+            }]
+        );
+    }
+
+    #[test]
+    fn get_latest_message_should_decode_chunk_and_refresh_version() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut stackerdb = StackerDB::from(&signer_config);
+        let slot_id = stackerdb.get_signer_slot_id();
+        let msg_id = MessageSlotID::BlockResponse;
+
+        let metadata = vec![SlotMetadata {
+            slot_id: slot_id.0,
+            slot_version: 4,
+            data_hash: Sha512Trunc256Sum([0u8; 32]),
+            signature: MessageSignature::empty(),
+        }];
+        let signer_message = block_rejection(3);
+        let h = spawn(move || {
+            let message = stackerdb.get_latest_message(&msg_id, slot_id).unwrap();
+            (message, stackerdb.slot_versions[&msg_id][&slot_id])
+        });
+
+        let mut response_bytes = b"HTTP/1.1 200 OK\n\n".to_vec();
+        response_bytes.extend(serde_json::to_vec(&metadata).unwrap());
+        let mock_server = mock_server_from_config(&config);
+        write_response(mock_server, response_bytes.as_slice());
+
+        let mut response_bytes = b"HTTP/1.1 200 OK\n\n".to_vec();
+        response_bytes.extend(signer_message.serialize_to_vec());
+        let mock_server = mock_server_from_config(&config);
+        let request_bytes = write_response(mock_server, response_bytes.as_slice());
+        assert!(String::from_utf8_lossy(&request_bytes).contains(&format!("/{slot_id} ")));
+
+        let (message, next_version) = h.join().unwrap();
+        assert_eq!(message, Some(signer_message));
+        // the next send goes straight to the version after the one the node has
+        assert_eq!(next_version, 5);
+    }
+
+    #[test]
+    fn get_latest_message_should_return_none_for_empty_slot() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut stackerdb = StackerDB::from(&signer_config);
+        let slot_id = stackerdb.get_signer_slot_id();
+
+        let h = spawn(move || stackerdb.get_latest_message(&MessageSlotID::BlockResponse, slot_id));
+
+        // the slot was never written, so its chunk isn't fetched
+        let mut response_bytes = b"HTTP/1.1 200 OK\n\n".to_vec();
+        response_bytes.extend(serde_json::to_vec(&Vec::<SlotMetadata>::new()).unwrap());
+        let mock_server = mock_server_from_config(&config);
+        write_response(mock_server, response_bytes.as_slice());
+
+        assert_eq!(h.join().unwrap().unwrap(), None);
+    }
+
+    #[test]
+    fn slot_versions_should_survive_restart() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let path = std::env::temp_dir().join(format!(
+            "slot_versions_should_survive_restart-{}.bin",
+            rand::random::<u64>()
+        ));
+        let new_stackerdb = || {
+            StackerDB::new(
+                &signer_config.node_host,
+                signer_config.stacks_private_key,
+                signer_config.mainnet,
+                signer_config.reward_cycle,
+                signer_config.signer_slot_id,
+                Some(path.clone()),
+            )
+        };
+        // accept the next chunk, and return its version
+        let serve_put = || mock_ack_server(&config, &[accepted_ack()])[0].slot_version;
+
+        let mut stackerdb = new_stackerdb();
+        let h = spawn(move || {
+            for _ in 0..2 {
+                stackerdb
+                    .send_message_with_retry(block_rejection(1))
+                    .unwrap();
+            }
+        });
+        assert_eq!(serve_put(), 1);
+        assert_eq!(serve_put(), 2);
+        h.join().unwrap();
+
+        // a new client picks up from the saved versions, without a collision
+        let mut stackerdb = new_stackerdb();
+        let h = spawn(move || stackerdb.send_message_with_retry(block_rejection(1)));
+        assert_eq!(serve_put(), 3);
+        assert_eq!(h.join().unwrap().unwrap(), accepted_ack());
+
+        // a corrupt file is ignored
+        fs::write(&path, b"garbage").unwrap();
+        let stackerdb = new_stackerdb();
+        assert!(stackerdb.slot_versions.is_empty());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn invalidate_slot_should_rediscover_version() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut stackerdb = StackerDB::from(&signer_config);
+        let slot_id = stackerdb.get_signer_slot_id();
+        let msg_id = MessageSlotID::Transactions;
+
+        let h = spawn(move || {
+            stackerdb
+                .send_message_with_retry(SignerMessage::Transactions(vec![]))
+                .unwrap();
+            let version_before = stackerdb.slot_versions[&msg_id][&slot_id];
+            stackerdb.invalidate_slot(&msg_id);
+            let invalidated = !stackerdb.slot_versions.contains_key(&msg_id);
+            stackerdb
+                .send_message_with_retry(SignerMessage::Transactions(vec![]))
+                .unwrap();
+            let version_after = stackerdb.slot_versions[&msg_id][&slot_id];
+            (version_before, invalidated, version_after)
+        });
+
+        let already_exists = StackerDBChunkAckData {
+            accepted: false,
+            reason: Some("Data for this slot and version already exist".to_string()),
+            metadata: Some(SlotMetadata {
+                slot_id: slot_id.0,
+                slot_version: 4,
+                data_hash: Sha512Trunc256Sum([0u8; 32]),
+                signature: MessageSignature::empty(),
+            }),
+            code: Some(StackerDBErrorCodes::DataAlreadyExists.code()),
+        };
+
+        // first send, then the post-invalidation send starts over at version 1 and learns the
+        // real version from the node
+        let chunks = mock_ack_server(&config, &[accepted_ack(), already_exists, accepted_ack()]);
+        let versions: Vec<_> = chunks.iter().map(|chunk| chunk.slot_version).collect();
+        assert_eq!(versions, vec![1, 1, 5]);
+
+        let (version_before, invalidated, version_after) = h.join().unwrap();
+        assert_eq!(version_before, 2);
+        assert!(invalidated);
+        assert_eq!(version_after, 6);
+    }
+
+    #[test]
+    fn to_signed_chunk_should_roundtrip() {
+        let key = StacksPrivateKey::new();
+        let message = SignerMessage::Transactions(vec![]);
+
+        let chunk = to_signed_chunk(&message, 3, 7, &key).unwrap();
+        assert_eq!(chunk.slot_id, 3);
+        assert_eq!(chunk.slot_version, 7);
+        assert_eq!(
+            chunk.recover_pk().unwrap(),
+            StacksPublicKey::from_private(&key)
+        );
+        assert_eq!(
+            read_next::<SignerMessage, _>(&mut &chunk.data[..]).unwrap(),
+            message
+        );
+    }
+
+    #[test]
+    fn estimate_signing_ops_should_match_chunks_sent() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut stackerdb = StackerDB::from(&signer_config);
+
+        // the block responses share a chunk, and the transactions get their own
+        let messages = vec![
+            block_rejection(1),
+            block_rejection(2),
+            SignerMessage::Transactions(vec![]),
+        ];
+        let estimate = StackerDB::estimate_signing_ops(&messages);
+        assert_eq!(estimate, 2);
+
+        let mock_server = mock_server_from_config(&config);
+        mock_server.set_nonblocking(true).unwrap();
+        let h = spawn(move || {
+            for batch in StackerDB::batch_by_slot(&messages) {
+                stackerdb.send_messages_with_retry(batch.to_vec()).unwrap();
+            }
+        });
+
+        // accept every chunk the client sends, until it goes quiet
+        let mut chunks_sent = 0;
+        let mut last_chunk = Instant::now();
+        while last_chunk.elapsed() < Duration::from_secs(3) {
+            let Ok((mut stream, _)) = mock_server.accept() else {
+                std::thread::sleep(Duration::from_millis(10));
+                continue;
+            };
+            stream.set_nonblocking(false).unwrap();
+            reply_to_put(&mut stream, &accepted_ack());
+            chunks_sent += 1;
+            last_chunk = Instant::now();
+        }
+        h.join().unwrap();
+        assert_eq!(chunks_sent, estimate);
+    }
+
+    #[test]
+    fn send_large_message_should_roundtrip_in_parts() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut stackerdb = StackerDB::from(&signer_config);
+        let slot_id = stackerdb.get_signer_slot_id();
+
+        let message = block_rejection(7);
+        let message_len = message.serialize_to_vec().len();
+        // three parts: two full ones, and the rest
+        stackerdb.set_max_message_part_len(message_len.div_ceil(3));
+
+        let sent_message = message.clone();
+        let h = spawn(move || stackerdb.send_large_message_with_retry(sent_message));
+
+        let chunks = mock_ack_server(&config, &vec![accepted_ack(); 3]);
+        let mut reassembler = MessageReassembler::default();
+        let mut received = None;
+        for (chunk, expected_version) in chunks.iter().zip(1..) {
+            assert_eq!(chunk.slot_id, slot_id.0);
+            assert_eq!(chunk.slot_version, expected_version);
+            assert!(received.is_none());
+            received = reassembler.add_chunk(slot_id, &chunk.data).unwrap();
+        }
+
+        assert_eq!(h.join().unwrap().unwrap(), vec![accepted_ack(); 3]);
+        assert_eq!(received, Some(message));
+        assert_eq!(reassembler.pending_len(), 0);
+    }
+
+    #[test]
+    fn send_batched_messages_should_share_one_chunk() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut stackerdb = StackerDB::from(&signer_config);
+        let slot_id = stackerdb.get_signer_slot_id();
+
+        // messages for different slots can't share a chunk
+        assert!(matches!(
+            stackerdb.send_messages_with_retry(vec![
+                block_rejection(1),
+                SignerMessage::Transactions(vec![])
+            ]),
+            Err(ClientError::InvalidBatch(_))
+        ));
+        assert!(matches!(
+            stackerdb.send_messages_with_retry(vec![]),
+            Err(ClientError::InvalidBatch(_))
+        ));
+
+        let messages = vec![block_rejection(1), block_rejection(2)];
+        let sent_messages = messages.clone();
+        let h = spawn(move || stackerdb.send_messages_with_retry(sent_messages));
+
+        let chunk = mock_ack_server(&config, &[accepted_ack()]).remove(0);
+        assert_eq!(chunk.slot_id, slot_id.0);
+        assert_eq!(chunk.slot_version, 1);
+
+        assert_eq!(h.join().unwrap().unwrap(), accepted_ack());
+        let received = SignerMessage::read_batch(&mut &chunk.data[..]).unwrap();
+        assert_eq!(received, messages);
+    }
+
+    #[test]
+    fn put_chunk_rejected_should_report_attempts() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut stackerdb = StackerDB::from(&signer_config);
+        let slot_id = stackerdb.get_signer_slot_id();
+
+        let h =
+            spawn(move || stackerdb.send_message_with_retry(SignerMessage::Transactions(vec![])));
+
+        // the node keeps reporting a newer version, forcing retries...
+        let mut acks: Vec<_> = (1..=3)
+            .map(|version| StackerDBChunkAckData {
+                accepted: false,
+                reason: Some("Data for this slot and version already exist".to_string()),
+                metadata: Some(SlotMetadata {
+                    slot_id: slot_id.0,
+                    slot_version: version * 2,
+                    data_hash: Sha512Trunc256Sum([0u8; 32]),
+                    signature: MessageSignature::empty(),
+                }),
+                code: Some(StackerDBErrorCodes::DataAlreadyExists.code()),
+            })
+            .collect();
+        // ...and then rejects the chunk outright
+        acks.push(StackerDBChunkAckData {
+            accepted: false,
+            reason: Some("Signature does not match slot signer".to_string()),
+            metadata: None,
+            code: Some(StackerDBErrorCodes::BadSigner.code()),
+        });
+        mock_ack_server(&config, &acks);
+
+        match h.join().unwrap() {
+            Err(ClientError::PutChunkRejected {
+                reason,
+                attempts,
+                elapsed,
+            }) => {
+                assert_eq!(reason, "Signature does not match slot signer");
+                assert_eq!(attempts, 4);
+                assert!(elapsed > Duration::ZERO);
+            }
+            res => panic!("Expected a rejected chunk, got {res:?}"),
+        }
+    }
+
+    #[test]
+    fn send_message_should_handle_each_rejection_code() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let msg_id = MessageSlotID::Transactions;
+
+        // send a message, and have the node reject it with the given code
+        let reject_with = |code: u32| {
+            let mut stackerdb = StackerDB::from(&signer_config);
+            let h = spawn(move || {
+                let result = stackerdb.send_message_with_retry(SignerMessage::Transactions(vec![]));
+                (stackerdb, result)
+            });
+            let ack = StackerDBChunkAckData {
+                accepted: false,
+                reason: Some(format!("Rejected with code {code}")),
+                metadata: None,
+                code: Some(code),
+            };
+            mock_ack_server(&config, &[ack]);
+            h.join().unwrap()
+        };
+        let assert_rejected = |result: Result<StackerDBChunkAckData, ClientError>, code: u32| {
+            match result {
+                Err(ClientError::PutChunkRejected {
+                    reason, attempts, ..
+                }) => {
+                    assert_eq!(reason, format!("Rejected with code {code}"));
+                    // no retries
+                    assert_eq!(attempts, 1);
+                }
+                res => panic!("Expected a rejected chunk, got {res:?}"),
+            }
+        };
+
+        // the slot doesn't exist, so its cached version is forgotten
+        let code = StackerDBErrorCodes::NoSuchSlot.code();
+        let (stackerdb, result) = reject_with(code);
+        assert_rejected(result, code);
+        assert!(stackerdb.slot_versions.get(&msg_id).is_none());
+
+        // the slot isn't ours, but its version still moves on
+        let code = StackerDBErrorCodes::BadSigner.code();
+        let (stackerdb, result) = reject_with(code);
+        assert_rejected(result, code);
+        assert_eq!(
+            stackerdb
+                .slot_versions
+                .get(&msg_id)
+                .and_then(|versions| versions.get(&stackerdb.signer_slot_id)),
+            Some(&2)
+        );
+
+        // a code from a newer node
+        let code = 99;
+        assert!(StackerDBErrorCodes::from_code(code).is_none());
+        let (_, result) = reject_with(code);
+        assert_rejected(result, code);
+    }
+
+    #[test]
+    fn max_version_for_should_return_highest_slot_version() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut stackerdb = StackerDB::from(&signer_config);
+        let metadata_path = stackerdb_get_metadata_path(
+            MessageSlotID::BlockResponse
+                .stacker_db_contract(signer_config.mainnet, signer_config.reward_cycle),
+        );
+
+        let slot_metadata = |slot_id, slot_version| SlotMetadata {
+            slot_id,
+            slot_version,
+            data_hash: Sha512Trunc256Sum([0u8; 32]),
+            signature: MessageSignature::empty(),
+        };
+        let metadata = vec![
+            slot_metadata(0, 3),
+            slot_metadata(1, 7),
+            slot_metadata(2, 0),
+            slot_metadata(3, 5),
+        ];
+
+        let h = spawn(move || {
+            let max_version = stackerdb
+                .max_version_for(&MessageSlotID::BlockResponse)
+                .unwrap();
+            let empty_max_version = stackerdb
+                .max_version_for(&MessageSlotID::BlockResponse)
+                .unwrap();
+            (max_version, empty_max_version)
+        });
+
+        let mut response_bytes = b"HTTP/1.1 200 OK\n\n".to_vec();
+        response_bytes.extend(serde_json::to_vec(&metadata).unwrap());
+        let mock_server = mock_server_from_config(&config);
+        let request_bytes = write_response(mock_server, response_bytes.as_slice());
+        // only the metadata was asked for
+        assert!(String::from_utf8_lossy(&request_bytes)
+            .starts_with(&format!("GET {metadata_path} HTTP/1.1\r\n")));
+
+        let mut response_bytes = b"HTTP/1.1 200 OK\n\n".to_vec();
+        response_bytes.extend(serde_json::to_vec(&Vec::<SlotMetadata>::new()).unwrap());
+        let mock_server = mock_server_from_config(&config);
+        write_response(mock_server, response_bytes.as_slice());
+
+        let (max_version, empty_max_version) = h.join().unwrap();
+        assert_eq!(max_version, 7);
+        assert_eq!(empty_max_version, 0);
+    }
+
+    #[test]
+    fn read_new_signer_messages_should_dedup_versions() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut stackerdb = StackerDB::from(&signer_config);
+
+        let slot_metadata = |slot_id, slot_version| SlotMetadata {
+            slot_id,
+            slot_version,
+            data_hash: Sha512Trunc256Sum([0u8; 32]),
+            signature: MessageSignature::empty(),
+        };
+        // slot 0 is listed with a stale version and a duplicate; slot 1 isn't asked for
+        let metadata = vec![
+            slot_metadata(0, 1),
+            slot_metadata(0, 3),
+            slot_metadata(0, 3),
+            slot_metadata(1, 2),
+        ];
+        let signer_message = SignerMessage::Transactions(vec![]);
+
+        let h = spawn(move || {
+            let first = stackerdb
+                .read_new_signer_messages(&MessageSlotID::Transactions, &[SignerSlotID(0)])
+                .unwrap();
+            let second = stackerdb
+                .read_new_signer_messages(&MessageSlotID::Transactions, &[SignerSlotID(0)])
+                .unwrap();
+            (first, second)
+        });
+
+        // first poll: list, then fetch only the latest version of slot 0
+        let mut response_bytes = b"HTTP/1.1 200 OK\n\n".to_vec();
+        response_bytes.extend(serde_json::to_vec(&metadata).unwrap());
+        let mock_server = mock_server_from_config(&config);
+        write_response(mock_server, response_bytes.as_slice());
+
+        let mut response_bytes = b"HTTP/1.1 200 OK\n\n".to_vec();
+        response_bytes.extend(signer_message.serialize_to_vec());
+        let mock_server = mock_server_from_config(&config);
+        let request_bytes = write_response(mock_server, response_bytes.as_slice());
+        let request = String::from_utf8_lossy(&request_bytes);
+        assert!(request.contains("/0/3 "));
+
+        // second poll: same versions, so nothing is fetched
+        let mut response_bytes = b"HTTP/1.1 200 OK\n\n".to_vec();
+        response_bytes.extend(serde_json::to_vec(&metadata).unwrap());
+        let mock_server = mock_server_from_config(&config);
+        write_response(mock_server, response_bytes.as_slice());
+
+        let (first, second) = h.join().unwrap();
+        assert_eq!(first, vec![(SignerSlotID(0), 3, signer_message)]);
+        assert!(second.is_empty());
+    }
+
+    /// Drive the mock node through one `put_and_await_quorum()` poll: accept the put, list slots
+    /// 0 through 2, and serve each slot's chunk
+    fn serve_quorum_poll(config: &GlobalConfig, chunks: &[SignerMessage]) {
+        mock_ack_server(config, &[accepted_ack()]);
+
+        let metadata: Vec<_> = (0..chunks.len())
+            .map(|slot_id| SlotMetadata {
+                slot_id: slot_id as u32,
+                slot_version: 1,
+                data_hash: Sha512Trunc256Sum([0u8; 32]),
+                signature: MessageSignature::empty(),
+            })
+            .collect();
+        let mut response_bytes = b"HTTP/1.1 200 OK\n\n".to_vec();
+        response_bytes.extend(serde_json::to_vec(&metadata).unwrap());
+        let mock_server = mock_server_from_config(config);
+        write_response(mock_server, response_bytes.as_slice());
+
+        for chunk in chunks.iter() {
+            let mut response_bytes = b"HTTP/1.1 200 OK\n\n".to_vec();
+            response_bytes.extend(chunk.serialize_to_vec());
+            let mock_server = mock_server_from_config(config);
+            write_response(mock_server, response_bytes.as_slice());
+        }
+    }
+
+    #[test]
+    fn read_unknown_slots_should_return_raw_chunks() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut stackerdb = StackerDB::from(&signer_config);
+        let first_unknown = MessageSlotID::ALL.len() as u32;
+        let unknown_contract =
+            NakamotoSigners::make_signers_db_name(signer_config.reward_cycle, first_unknown);
+
+        // off by default
+        assert!(stackerdb
+            .read_unknown_slots(SignerSlotID(1))
+            .unwrap()
+            .is_empty());
+
+        stackerdb.set_unknown_slots_to_read(2);
+        let h = spawn(move || stackerdb.read_unknown_slots(SignerSlotID(1)));
+
+        // the first unknown slot has data we can't parse
+        let raw_chunk = vec![0xff, 0x01, 0x02, 0x03];
+        let mut response_bytes = b"HTTP/1.1 200 OK\n\n".to_vec();
+        response_bytes.extend(raw_chunk.clone());
+        let mock_server = mock_server_from_config(&config);
+        let request = write_response(mock_server, response_bytes.as_slice());
+        assert!(String::from_utf8_lossy(&request).contains(&unknown_contract));
+
+        // the second one doesn't exist
+        let mock_server = mock_server_from_config(&config);
+        write_response(mock_server, b"HTTP/1.1 404 Not Found\n\n");
+
+        let chunks = h.join().unwrap().unwrap();
+        assert_eq!(chunks, vec![(first_unknown, raw_chunk)]);
+    }
+
+    /// A rejection of the block with the given signer signature hash
+    fn block_rejection(hash: u8) -> SignerMessage {
+        SignerMessage::BlockResponse(block_rejection_response(hash))
+    }
+
+    fn block_rejection_response(hash: u8) -> BlockResponse {
+        // a fixed timestamp, so that rejections of the same block compare equal
+        let mut rejection = BlockRejection::new(
+            Sha512Trunc256Sum([hash; 32]),
+            RejectCode::ConnectivityIssues,
+        );
+        rejection.metadata.timestamp = Some(1_700_000_000);
+        BlockResponse::Rejected(rejection)
+    }
+
+    #[test]
+    fn state_checkpoint_should_capture_latest_state() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut stackerdb = StackerDB::from(&signer_config);
+
+        let h = spawn(move || {
+            stackerdb
+                .send_message_with_retry(block_rejection(1))
+                .unwrap();
+            stackerdb
+                .send_message_with_retry(block_rejection(2))
+                .unwrap();
+            stackerdb.state_checkpoint().clone()
+        });
+        mock_ack_server(&config, &[accepted_ack(), accepted_ack()]);
+        let checkpoint = h.join().unwrap();
+
+        // only the latest block response is kept, along with the version it was put at
+        assert_eq!(checkpoint.reward_cycle, signer_config.reward_cycle);
+        assert_eq!(
+            checkpoint
+                .last_block_response
+                .clone()
+                .map(SignerMessage::BlockResponse),
+            Some(block_rejection(2))
+        );
+        assert!(checkpoint.transactions.is_none());
+        assert_eq!(
+            checkpoint.slot_version(&MessageSlotID::BlockResponse),
+            Some(2)
+        );
+    }
+
+    /// Signs with a key it keeps to itself, like an HSM would, and counts what it signs
+    #[derive(Debug)]
+    struct MockChunkSigner {
+        key: Option<StacksPrivateKey>,
+        num_signed: Arc<AtomicUsize>,
+    }
+
+    impl ChunkSigner for MockChunkSigner {
+        fn sign(&self, chunk: &mut StackerDBChunkData) -> Result<(), ClientError> {
+            let key = self.key.as_ref().ok_or(ClientError::NotConnected)?;
+            self.num_signed.fetch_add(1, Ordering::SeqCst);
+            chunk.sign(key)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn chunk_signer_should_sign_sent_chunks() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let hsm_key = StacksPrivateKey::new();
+        let num_signed = Arc::new(AtomicUsize::new(0));
+        let mut stackerdb = StackerDB::new_with_chunk_signer(
+            &signer_config.node_host,
+            Box::new(MockChunkSigner {
+                key: Some(hsm_key.clone()),
+                num_signed: num_signed.clone(),
+            }),
+            signer_config.mainnet,
+            signer_config.reward_cycle,
+            signer_config.signer_slot_id,
+        );
+
+        let h = spawn(move || stackerdb.send_message_with_retry(block_rejection(1)));
+        mock_ack_server(&config, &[accepted_ack()]);
+        assert_eq!(h.join().unwrap().unwrap(), accepted_ack());
+        assert_eq!(num_signed.load(Ordering::SeqCst), 1);
+
+        // chunks are signed with the signer's key
+        let signer = MockChunkSigner {
+            key: Some(hsm_key.clone()),
+            num_signed: Arc::new(AtomicUsize::new(0)),
+        };
+        let chunk = sign_chunk_bytes(block_rejection(1).serialize_to_vec(), 0, 1, &signer).unwrap();
+        assert_eq!(
+            chunk.recover_pk().unwrap(),
+            StacksPublicKey::from_private(&hsm_key)
+        );
+
+        // if the signer fails, nothing is sent
+        let mut stackerdb = StackerDB::new_with_chunk_signer(
+            &signer_config.node_host,
+            Box::new(MockChunkSigner {
+                key: None,
+                num_signed: num_signed.clone(),
+            }),
+            signer_config.mainnet,
+            signer_config.reward_cycle,
+            signer_config.signer_slot_id,
+        );
+        assert!(stackerdb
+            .send_message_with_retry(block_rejection(1))
+            .is_err());
+        assert_eq!(num_signed.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn put_and_await_quorum_should_succeed_with_quorum() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut stackerdb = StackerDB::from(&signer_config);
+
+        let h = spawn(move || {
+            stackerdb.put_and_await_quorum(block_rejection_response(1), 2, Duration::from_secs(30))
+        });
+
+        // our slot, another signer's response to the same block, and a response to another block
+        serve_quorum_poll(
+            &config,
+            &[block_rejection(1), block_rejection(1), block_rejection(2)],
+        );
+        h.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn put_and_await_quorum_should_time_out_without_quorum() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut stackerdb = StackerDB::from(&signer_config);
+
+        let h = spawn(move || {
+            stackerdb.put_and_await_quorum(block_rejection_response(1), 3, Duration::ZERO)
+        });
+
+        serve_quorum_poll(
+            &config,
+            &[block_rejection(1), block_rejection(1), block_rejection(2)],
+        );
+        match h.join().unwrap() {
+            Err(ClientError::QuorumTimeout {
+                acknowledged,
+                quorum,
+            }) => {
+                assert_eq!(acknowledged, 2);
+                assert_eq!(quorum, 3);
+            }
+            res => panic!("Expected a quorum timeout, got {res:?}"),
+        }
+    }
+
+    #[test]
+    fn put_and_await_quorum_should_time_out_while_sending() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut stackerdb = StackerDB::from(&signer_config);
+
+        let h = spawn(move || {
+            stackerdb.put_and_await_quorum(block_rejection_response(1), 1, Duration::ZERO)
+        });
+
+        // the put is tried once, but not retried at the version the node wants
+        let already_exists = StackerDBChunkAckData {
+            accepted: false,
+            reason: Some("Data for this slot and version already exist".to_string()),
+            metadata: Some(SlotMetadata {
+                slot_id: 0,
+                slot_version: 4,
+                data_hash: Sha512Trunc256Sum([0u8; 32]),
+                signature: MessageSignature::empty(),
+            }),
+            code: Some(StackerDBErrorCodes::DataAlreadyExists.code()),
+        };
+        mock_ack_server(&config, &[already_exists]);
+
+        match h.join().unwrap() {
+            Err(ClientError::QuorumTimeout {
+                acknowledged,
+                quorum,
+            }) => {
+                assert_eq!(acknowledged, 0);
+                assert_eq!(quorum, 1);
+            }
+            res => panic!("Expected a quorum timeout, got {res:?}"),
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn version_invariants_should_hold_for_sane_versions() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut stackerdb = StackerDB::from(&signer_config);
+        let slot_id = stackerdb.get_signer_slot_id();
+        let msg_id = MessageSlotID::Transactions;
+
+        stackerdb.assert_version_invariants();
+
+        stackerdb
+            .slot_versions
+            .insert(msg_id, HashMap::from([(slot_id, 4)]));
+        stackerdb.sent_versions.insert(msg_id, 3);
+        stackerdb.assert_version_invariants();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "another signer's slot")]
+    fn version_invariants_should_catch_foreign_slot() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut stackerdb = StackerDB::from(&signer_config);
+        let slot_id = stackerdb.get_signer_slot_id();
+        let other_slot_id = SignerSlotID(slot_id.0.wrapping_add(1));
+
+        stackerdb.slot_versions.insert(
+            MessageSlotID::Transactions,
+            HashMap::from([(slot_id, 1), (other_slot_id, 1)]),
+        );
+        stackerdb.assert_version_invariants();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "is not ahead of last-sent version")]
+    fn version_invariants_should_catch_version_regression() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut stackerdb = StackerDB::from(&signer_config);
+        let slot_id = stackerdb.get_signer_slot_id();
+        let msg_id = MessageSlotID::Transactions;
+
+        stackerdb
+            .slot_versions
+            .insert(msg_id, HashMap::from([(slot_id, 2)]));
+        stackerdb.sent_versions.insert(msg_id, 5);
+        stackerdb.assert_version_invariants();
     }
 }
@@ -138,12 +138,11 @@ impl StacksClient {
             &function_name,
             function_args,
         )?;
-        self.parse_signer_slots(value)
+        Self::parse_signer_slots(value)
     }
 
     /// Helper function  that attempts to deserialize a clarity hext string as a list of signer slots and their associated number of signer slots
-    fn parse_signer_slots(
-        &self,
+    pub(crate) fn parse_signer_slots(
         value: ClarityValue,
     ) -> Result<Vec<(StacksAddress, u128)>, ClientError> {
         debug!("Parsing signer slots...");
@@ -1065,8 +1064,6 @@ mod tests {
 
     #[test]
     fn parse_valid_signer_slots_should_succeed() {
-        let mock = MockServerClient::new();
-
         let signers = [
             "ST20SA6BAK9YFKGVWP4Z1XNMTFF04FA2E0M8YRNNQ",
             "ST1JGAHRH8VEFE8QGB04H261Z52ZF62MAH40CD6ZN",
@@ -1119,7 +1116,7 @@ mod tests {
             data: Box::new(sequence),
         });
 
-        let signer_slots = mock.client.parse_signer_slots(value).unwrap();
+        let signer_slots = StacksClient::parse_signer_slots(value).unwrap();
         assert_eq!(signer_slots.len(), 5);
         signer_slots
             .into_iter()
@@ -149,6 +149,8 @@ pub struct SignerConfig {
     pub max_tx_fee_ustx: Option<u64>,
     /// The path to the signer's database file
     pub db_path: PathBuf,
+    /// Whether to send a timestamp and signer slot id with block responses
+    pub block_response_metadata: bool,
 }
 
 /// The parsed configuration for the signer
@@ -188,6 +190,8 @@ pub struct GlobalConfig {
     pub db_path: PathBuf,
     /// Metrics endpoint
     pub metrics_endpoint: Option<SocketAddr>,
+    /// Whether to send a timestamp and signer slot id with block responses
+    pub block_response_metadata: bool,
 }
 
 /// Internal struct for loading up the config file
@@ -225,6 +229,9 @@ struct RawConfigFile {
     pub db_path: String,
     /// Metrics endpoint
     pub metrics_endpoint: Option<String>,
+    /// Whether to send a timestamp and signer slot id with block responses. Miners that predate
+    /// them cannot read such responses, so this defaults to false.
+    pub block_response_metadata: Option<bool>,
 }
 
 impl RawConfigFile {
@@ -333,6 +340,7 @@ impl TryFrom<RawConfigFile> for GlobalConfig {
             auth_password: raw_data.auth_password,
             db_path,
             metrics_endpoint,
+            block_response_metadata: raw_data.block_response_metadata.unwrap_or(false),
         })
     }
 }
@@ -645,4 +653,15 @@ Metrics endpoint: 0.0.0.0:9090
             )
         );
     }
+
+    #[test]
+    fn block_response_metadata_should_default_to_off() {
+        let config_toml = fs::read_to_string("./src/tests/conf/signer-0.toml").unwrap();
+        let config = GlobalConfig::load_from_str(&config_toml).unwrap();
+        assert!(!config.block_response_metadata);
+
+        let config_toml = format!("{config_toml}\nblock_response_metadata = true\n");
+        let config = GlobalConfig::load_from_str(&config_toml).unwrap();
+        assert!(config.block_response_metadata);
+    }
 }
@@ -138,6 +138,15 @@ pub fn update_signer_nonce(nonce: u64) {
     prometheus::SIGNER_NONCE.set(nonce as i64);
 }
 
+/// Update when the signer last wrote a chunk to its slot for the given message type
+#[allow(unused_variables)]
+pub fn update_signer_slot_last_write(message_id: &str, timestamp: u64) {
+    #[cfg(feature = "monitoring_prom")]
+    prometheus::SIGNER_SLOT_LAST_WRITE
+        .with_label_values(&[message_id])
+        .set(timestamp as i64);
+}
+
 /// Start a new RPC call timer.
 /// The `origin` parameter is the base path of the RPC call, e.g. `http://node.com`.
 /// The `origin` parameter is removed from `full_path` when storing in prometheus.
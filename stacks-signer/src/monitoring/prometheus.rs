@@ -17,8 +17,8 @@
 use lazy_static::lazy_static;
 use prometheus::{
     gather, histogram_opts, opts, register_histogram_vec, register_int_counter,
-    register_int_counter_vec, register_int_gauge, Encoder, HistogramVec, IntCounter, IntCounterVec,
-    IntGauge, TextEncoder,
+    register_int_counter_vec, register_int_gauge, register_int_gauge_vec, Encoder, HistogramVec,
+    IntCounter, IntCounterVec, IntGauge, IntGaugeVec, TextEncoder,
 };
 
 lazy_static! {
@@ -88,6 +88,12 @@ lazy_static! {
         "stacks_signer_nonce",
         "The current nonce of the signer"
     )).unwrap();
+    pub static ref SIGNER_SLOT_LAST_WRITE: IntGaugeVec = register_int_gauge_vec!(
+        "stacks_signer_slot_last_write_timestamp",
+        "When (UNIX time in seconds) the signer last wrote a chunk to its StackerDB slot. `message_id` is the slot's message type",
+        &["message_id"]
+    )
+    .unwrap();
 
     pub static ref SIGNER_RPC_CALL_LATENCIES_HISTOGRAM: HistogramVec = register_histogram_vec!(histogram_opts!(
         "stacks_signer_node_rpc_call_latencies_histogram",
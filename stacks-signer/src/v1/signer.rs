@@ -28,7 +28,7 @@ use blockstack_lib::net::api::postblock_proposal::BlockValidateResponse;
 use blockstack_lib::util_lib::db::Error as DBError;
 use hashbrown::HashSet;
 use libsigner::v1::messages::{
-    BlockRejection, BlockResponse, MessageSlotID, RejectCode, SignerMessage,
+    BlockRejection, BlockResponse, BlockResponseMetadata, MessageSlotID, RejectCode, SignerMessage,
 };
 use libsigner::{BlockProposal, SignerEvent};
 use rand_core::OsRng;
@@ -169,6 +169,8 @@ pub struct Signer {
     pub db_path: PathBuf,
     /// SignerDB for state management
     pub signer_db: SignerDb,
+    /// Whether to send a timestamp and signer slot id with block responses
+    pub block_response_metadata: bool,
 }
 
 impl std::fmt::Display for Signer {
@@ -515,6 +517,7 @@ impl From<SignerConfig> for Signer {
             miner_key: None,
             db_path: signer_config.db_path,
             signer_db,
+            block_response_metadata: signer_config.block_response_metadata,
         }
     }
 }
@@ -701,9 +704,11 @@ impl Signer {
                 // Submit a rejection response to the .signers contract for miners
                 // to observe so they know to send another block and to prove signers are doing work);
                 warn!("{self}: Broadcasting a block rejection due to stacks node validation failure...");
+                let block_rejection = BlockRejection::from(block_validate_reject.clone())
+                    .with_metadata(self.block_response_metadata());
                 if let Err(e) = self
                     .stackerdb
-                    .send_message_with_retry(block_validate_reject.clone().into())
+                    .send_message_with_retry(block_rejection.into())
                 {
                     warn!("{self}: Failed to send block rejection to stacker-db: {e:?}",);
                 }
@@ -746,6 +751,7 @@ impl Signer {
                 SignerMessage::DkgResults { .. }
                 | SignerMessage::BlockResponse(_)
                 | SignerMessage::EncryptedSignerState(_)
+                | SignerMessage::StateCheckpoint(_)
                 | SignerMessage::Transactions(_) => None,
                 // TODO: if a signer tries to trigger DKG and we already have one set in the contract, ignore the request.
                 SignerMessage::Packet(packet) => {
@@ -992,7 +998,8 @@ impl Signer {
                 let block_rejection = BlockRejection::new(
                     block.header.signer_signature_hash(),
                     RejectCode::MissingTransactions(missing_transactions),
-                );
+                )
+                .with_metadata(self.block_response_metadata());
                 // Submit signature result to miners to observe
                 if let Err(e) = self
                     .stackerdb
@@ -1008,7 +1015,8 @@ impl Signer {
             let block_rejection = BlockRejection::new(
                 block.header.signer_signature_hash(),
                 RejectCode::ConnectivityIssues,
-            );
+            )
+            .with_metadata(self.block_response_metadata());
             // Submit signature result to miners to observe
             if let Err(e) = self
                 .stackerdb
@@ -1320,6 +1328,15 @@ impl Signer {
         Ok(())
     }
 
+    /// The metadata to send with this signer's block responses: none, unless the signer is
+    /// configured to send it
+    fn block_response_metadata(&mut self) -> BlockResponseMetadata {
+        if !self.block_response_metadata {
+            return BlockResponseMetadata::default();
+        }
+        BlockResponseMetadata::now(self.stackerdb.get_signer_slot_id().0)
+    }
+
     /// Process a signature from a signing round by deserializing the signature and
     /// broadcasting an appropriate Reject or Approval message to stackerdb
     fn process_signature(&mut self, signature: &Signature) {
@@ -1338,7 +1355,8 @@ impl Signer {
             crate::monitoring::increment_block_responses_sent(true);
             // we agreed to sign the block hash. Return an approval message
             BlockResponse::accepted(block_vote.signer_signature_hash, signature.clone())
-        };
+        }
+        .with_metadata(self.block_response_metadata());
 
         // Submit signature result to miners to observe
         info!("{self}: Submit block response: {block_submission}");
@@ -1379,7 +1397,8 @@ impl Signer {
             block_info.block
         });
         let block_rejection =
-            BlockRejection::new(block.header.signer_signature_hash(), RejectCode::from(e));
+            BlockRejection::new(block.header.signer_signature_hash(), RejectCode::from(e))
+                .with_metadata(self.block_response_metadata());
         debug!("{self}: Broadcasting block rejection: {block_rejection:?}");
         // Submit signature result to miners to observe
         if let Err(e) = self
@@ -0,0 +1,113 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+
+use crate::net::http::{
+    Error, HttpContentType, HttpRequest, HttpRequestContents, HttpRequestPreamble, HttpResponse,
+    HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{RPCRequestHandler, StacksHttpRequest};
+use crate::net::{Error as NetError, StacksNodeState};
+
+/// The request to OPTIONS any path, which browsers send as a CORS preflight.
+/// The CORS headers themselves are added by the conversation, since they go on every response.
+#[derive(Clone)]
+pub struct RPCCorsPreflightRequestHandler {}
+impl RPCCorsPreflightRequestHandler {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCCorsPreflightRequestHandler {
+    fn verb(&self) -> &'static str {
+        "OPTIONS"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^.*$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "OPTIONS"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body for CORS preflight".to_string(),
+            ));
+        }
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCCorsPreflightRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {}
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        _node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let preamble = HttpResponsePreamble::from_http_request_preamble(
+            &preamble,
+            204,
+            "No Content",
+            Some(0),
+            HttpContentType::Bytes,
+        );
+        Ok((preamble, HttpResponseContents::from_ram(vec![])))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCCorsPreflightRequestHandler {
+    fn try_parse_response(
+        &self,
+        _preamble: &HttpResponsePreamble,
+        _body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        Ok(HttpResponsePayload::Empty)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new CORS preflight request for the given path
+    pub fn new_cors_preflight(host: PeerHost, path: &str) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "OPTIONS".into(),
+            path.to_string(),
+            HttpRequestContents::new(),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
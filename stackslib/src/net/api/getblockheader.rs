@@ -0,0 +1,199 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::codec::{StacksMessageCodec, MAX_MESSAGE_LEN};
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::types::net::PeerHost;
+
+use crate::chainstate::nakamoto::{NakamotoBlockHeader, NakamotoChainState};
+use crate::chainstate::stacks::db::StacksBlockHeaderTypes;
+use crate::chainstate::stacks::StacksBlockHeader;
+use crate::net::http::{
+    parse_bytes, Error, HttpContentType, HttpNotFound, HttpRequest, HttpRequestContents,
+    HttpRequestPreamble, HttpResponse, HttpResponseContents, HttpResponsePayload,
+    HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{request, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse};
+use crate::net::{Error as NetError, StacksNodeState};
+
+#[derive(Clone)]
+pub struct RPCBlockHeaderRequestHandler {
+    pub block_id: Option<StacksBlockId>,
+}
+
+impl RPCBlockHeaderRequestHandler {
+    pub fn new() -> Self {
+        Self { block_id: None }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCBlockHeaderRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v2/headers/(?P<block_id>[0-9a-f]{64})$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v2/headers/:block_id"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let block_id = request::get_block_hash(captures, "block_id")?;
+        self.block_id = Some(block_id);
+
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCBlockHeaderRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.block_id = None;
+    }
+
+    /// Make the response.
+    /// Only the header is read from the chainstate; the block body is never loaded.
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let block_id = self
+            .block_id
+            .take()
+            .ok_or(NetError::SendError("Missing `block_id`".into()))?;
+
+        let header_res =
+            node.with_node_state(|_network, _sortdb, chainstate, _mempool, _rpc_args| {
+                NakamotoChainState::get_block_header(chainstate.db(), &block_id)
+            });
+
+        let header_info = match header_res {
+            Ok(Some(header_info)) => header_info,
+            Ok(None) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new(format!("No such block {:?}\n", &block_id)),
+                )
+                .try_into_contents()
+                .map_err(NetError::from)
+            }
+            Err(e) => {
+                // nope -- error trying to check
+                let msg = format!("Failed to load block header: {:?}\n", &e);
+                warn!("{}", &msg);
+                return StacksHttpResponse::new_error(&preamble, &HttpServerError::new(msg))
+                    .try_into_contents()
+                    .map_err(NetError::from);
+            }
+        };
+
+        let header_bytes = match header_info.anchored_header {
+            StacksBlockHeaderTypes::Epoch2(header) => header.serialize_to_vec(),
+            StacksBlockHeaderTypes::Nakamoto(header) => header.serialize_to_vec(),
+        };
+
+        let resp_preamble = HttpResponsePreamble::from_http_request_preamble(
+            &preamble,
+            200,
+            "OK",
+            None,
+            HttpContentType::Bytes,
+        );
+
+        Ok((resp_preamble, HttpResponseContents::from_ram(header_bytes)))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCBlockHeaderRequestHandler {
+    /// Decode this response from a byte stream.  This is called by the client to decode this
+    /// message
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let bytes = parse_bytes(preamble, body, MAX_MESSAGE_LEN.into())?;
+        Ok(HttpResponsePayload::Bytes(bytes))
+    }
+}
+
+impl StacksHttpRequest {
+    pub fn new_get_block_header(
+        host: PeerHost,
+        index_block_hash: StacksBlockId,
+    ) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!("/v2/headers/{}", &index_block_hash),
+            HttpRequestContents::new(),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    /// Decode an HTTP response into a block header.
+    /// The encoding does not say which kind of header it is, so this tries an epoch 2.x header
+    /// first and then a Nakamoto header, and only accepts a decoding that consumes all of the
+    /// bytes.
+    /// If it fails, return Self::Error(..)
+    pub fn decode_block_header(self) -> Result<StacksBlockHeaderTypes, NetError> {
+        let contents = self.get_http_payload_ok()?;
+
+        // contents will be raw bytes
+        let header_bytes: Vec<u8> = contents.try_into()?;
+
+        let mut cursor = &header_bytes[..];
+        if let Ok(header) = StacksBlockHeader::consensus_deserialize(&mut cursor) {
+            if cursor.is_empty() {
+                return Ok(StacksBlockHeaderTypes::Epoch2(header));
+            }
+        }
+
+        let mut cursor = &header_bytes[..];
+        let header = NakamotoBlockHeader::consensus_deserialize(&mut cursor)?;
+        if !cursor.is_empty() {
+            return Err(NetError::DeserializeError(format!(
+                "Trailing bytes after block header ({} bytes)",
+                cursor.len()
+            )));
+        }
+        Ok(StacksBlockHeaderTypes::Nakamoto(header))
+    }
+}
@@ -0,0 +1,329 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TryRecvError, TrySendError};
+use std::sync::{Arc, Weak};
+
+use regex::{Captures, Regex};
+use stacks_common::codec::MAX_MESSAGE_LEN;
+use stacks_common::types::chainstate::{BlockHeaderHash, ConsensusHash, StacksBlockId};
+use stacks_common::types::net::PeerHost;
+use stacks_common::util::get_epoch_time_secs;
+
+use crate::net::http::common::parse_raw_bytes;
+use crate::net::http::{
+    Error, HttpChunkGenerator, HttpContentType, HttpRequest, HttpRequestContents,
+    HttpRequestPreamble, HttpResponse, HttpResponseContents, HttpResponsePayload,
+    HttpResponsePreamble, HttpServiceUnavailable,
+};
+use crate::net::httpcore::{RPCRequestHandler, StacksHttpRequest, StacksHttpResponse};
+use crate::net::{Error as NetError, StacksNodeState};
+
+/// How many announcements can be queued up for a subscriber that isn't reading them, before the
+/// subscriber is dropped
+pub const MAX_PENDING_BLOCK_ANNOUNCEMENTS: usize = 64;
+
+/// A new Stacks chain tip, as streamed to subscribers (one JSON object per line)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockAnnouncement {
+    pub consensus_hash: ConsensusHash,
+    pub block_hash: BlockHeaderHash,
+    pub index_block_hash: StacksBlockId,
+    pub height: u64,
+}
+
+impl BlockAnnouncement {
+    /// Newline-terminated JSON encoding of this announcement
+    pub fn to_json_line(&self) -> Vec<u8> {
+        let mut line =
+            serde_json::to_vec(self).expect("FATAL: failed to serialize infallible data");
+        line.push(b'\n');
+        line
+    }
+}
+
+/// One client's subscription to new blocks.  The subscription ends when this is dropped.
+#[derive(Debug)]
+pub struct BlockSubscription {
+    announcements: Receiver<Vec<u8>>,
+    /// lets `BlockSubscribers` tell that this subscription is gone without sending to it
+    _alive: Arc<()>,
+}
+
+/// The clients that are subscribed to new blocks, and the last tip they were told about
+#[derive(Debug, Default)]
+pub struct BlockSubscribers {
+    /// each subscriber's channel, and whether or not its `BlockSubscription` is still around
+    subscribers: Vec<(SyncSender<Vec<u8>>, Weak<()>)>,
+    last_tip: Option<StacksBlockId>,
+    /// when we last sent anything to the subscribers
+    last_sent: u64,
+}
+
+impl BlockSubscribers {
+    /// Add a subscriber.  It receives each announcement as a JSON line.
+    pub fn subscribe(&mut self) -> BlockSubscription {
+        let (announcement_tx, announcement_rx) = sync_channel(MAX_PENDING_BLOCK_ANNOUNCEMENTS);
+        let alive = Arc::new(());
+        self.subscribers
+            .push((announcement_tx, Arc::downgrade(&alive)));
+        BlockSubscription {
+            announcements: announcement_rx,
+            _alive: alive,
+        }
+    }
+
+    /// Number of subscribers.  Subscribers that went away are only noticed (and no longer
+    /// counted) after `prune()`, or the next time something is sent to them.
+    pub fn len(&self) -> usize {
+        self.subscribers.len()
+    }
+
+    /// Drop the subscribers that have gone away
+    pub fn prune(&mut self) {
+        self.subscribers
+            .retain(|(_, alive)| alive.strong_count() > 0);
+    }
+
+    /// Send `line` to every subscriber, and drop the ones that have gone away or that have
+    /// fallen too far behind, which ends their streams.
+    fn send(&mut self, line: Vec<u8>, now: u64) {
+        self.last_sent = now;
+        self.subscribers
+            .retain(|(subscriber, _)| match subscriber.try_send(line.clone()) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    debug!("Dropping block subscriber that is not keeping up");
+                    false
+                }
+                Err(TrySendError::Disconnected(_)) => false,
+            });
+    }
+
+    /// Send a blank keep-alive line to all subscribers if nothing has been sent to them in the
+    /// last `interval_secs`.  Returns true if a heartbeat was sent to anyone.
+    pub fn heartbeat(&mut self, now: u64, interval_secs: u64) -> bool {
+        if interval_secs == 0
+            || self.subscribers.is_empty()
+            || self.last_sent.saturating_add(interval_secs) > now
+        {
+            return false;
+        }
+        self.send(b"\n".to_vec(), now);
+        !self.subscribers.is_empty()
+    }
+
+    /// Tell all subscribers about the given Stacks tip, if it's new.  Subscribers that have gone
+    /// away or that have fallen too far behind are dropped, which ends their streams.
+    /// Returns true if the tip was announced to anyone.
+    pub fn announce_tip(&mut self, stacks_tip: &(ConsensusHash, BlockHeaderHash, u64)) -> bool {
+        let (consensus_hash, block_hash, height) = stacks_tip;
+        let index_block_hash = StacksBlockId::new(consensus_hash, block_hash);
+        if self.last_tip.as_ref() == Some(&index_block_hash) {
+            return false;
+        }
+        self.last_tip = Some(index_block_hash.clone());
+        if self.subscribers.is_empty() {
+            return false;
+        }
+
+        let line = BlockAnnouncement {
+            consensus_hash: consensus_hash.clone(),
+            block_hash: block_hash.clone(),
+            index_block_hash,
+            height: *height,
+        }
+        .to_json_line();
+
+        self.send(line, get_epoch_time_secs());
+        !self.subscribers.is_empty()
+    }
+}
+
+/// Live stream of block announcements to one subscriber
+pub struct BlockAnnouncementStream {
+    subscription: BlockSubscription,
+    live: bool,
+}
+
+impl BlockAnnouncementStream {
+    pub fn new(subscription: BlockSubscription) -> Self {
+        Self {
+            subscription,
+            live: true,
+        }
+    }
+}
+
+impl HttpChunkGenerator for BlockAnnouncementStream {
+    fn hint_chunk_size(&self) -> usize {
+        4096
+    }
+
+    fn generate_next_chunk(&mut self) -> Result<Vec<u8>, String> {
+        match self.subscription.announcements.try_recv() {
+            Ok(line) => Ok(line),
+            Err(TryRecvError::Empty) => Ok(vec![]),
+            Err(TryRecvError::Disconnected) => {
+                // no more announcements will come
+                self.live = false;
+                Ok(vec![])
+            }
+        }
+    }
+
+    fn is_live(&self) -> bool {
+        self.live
+    }
+}
+
+/// The request to GET /v2/blocks/subscribe
+#[derive(Clone)]
+pub struct RPCBlocksSubscribeRequestHandler {}
+impl RPCBlocksSubscribeRequestHandler {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCBlocksSubscribeRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v2/blocks/subscribe$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v2/blocks/subscribe"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body for SubscribeBlocks".to_string(),
+            ));
+        }
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCBlocksSubscribeRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {}
+
+    /// Make the response.
+    /// The response body never ends on its own; it carries one JSON line per new Stacks tip
+    /// (and blank keep-alive lines in between) until the client hangs up.
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let announcements_opt =
+            node.with_node_state(|network, _sortdb, _chainstate, _mempool, _rpc_args| {
+                let max_subscriptions = network.connection_opts.max_subscriptions;
+                // clients that hung up shouldn't keep new ones out
+                network.block_subscribers.prune();
+                if max_subscriptions > 0
+                    && (network.block_subscribers.len() as u64) >= max_subscriptions
+                {
+                    return None;
+                }
+                Some(network.block_subscribers.subscribe())
+            });
+        let Some(announcements) = announcements_opt else {
+            return StacksHttpResponse::new_error(
+                &preamble,
+                &HttpServiceUnavailable::new("Too many block subscriptions".to_string()),
+            )
+            .try_into_contents()
+            .map_err(NetError::from);
+        };
+        let resp_preamble = HttpResponsePreamble::from_http_request_preamble(
+            &preamble,
+            200,
+            "OK",
+            None,
+            HttpContentType::JSON,
+        );
+        Ok((
+            resp_preamble,
+            HttpResponseContents::from_stream(Box::new(BlockAnnouncementStream::new(
+                announcements,
+            ))),
+        ))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCBlocksSubscribeRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let bytes = parse_raw_bytes(
+            preamble,
+            body,
+            MAX_MESSAGE_LEN.into(),
+            HttpContentType::JSON,
+        )?;
+        Ok(HttpResponsePayload::Bytes(bytes))
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to subscribe to new blocks
+    pub fn new_subscribe_blocks(host: PeerHost) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            "/v2/blocks/subscribe".into(),
+            HttpRequestContents::new(),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    /// Decode the block announcements received so far
+    pub fn decode_block_announcements(self) -> Result<Vec<BlockAnnouncement>, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let bytes: Vec<u8> = contents.try_into()?;
+        let mut announcements = vec![];
+        for line in bytes.split(|byte| *byte == b'\n') {
+            if line.is_empty() {
+                continue;
+            }
+            let announcement = serde_json::from_slice(line)
+                .map_err(|_e| Error::DecodeError("Failed to decode JSON".to_string()))?;
+            announcements.push(announcement);
+        }
+        Ok(announcements)
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+
+use crate::net::http::{
+    parse_json, Error, HttpRequest, HttpRequestContents, HttpRequestPreamble, HttpResponse,
+    HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{RPCRequestHandler, StacksHttpRequest, StacksHttpResponse};
+use crate::net::{Error as NetError, StacksNodeState};
+
+/// What `GET /v2/health` says about the node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RPCHealthData {
+    /// always "ok"; a node that can answer at all is healthy enough to stay in rotation
+    pub status: String,
+    /// number of established HTTP conversations
+    pub connections: u64,
+}
+
+/// The request to GET /v2/health, a liveness probe for load balancers.  Unlike `/v2/info`, it
+/// never reads the chain state or sortition DBs, so it stays fast even while the node is busy
+/// (e.g. in initial block download).
+#[derive(Clone)]
+pub struct RPCGetHealthRequestHandler {}
+impl RPCGetHealthRequestHandler {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetHealthRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v2/health$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v2/health"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body for GetHealth".to_string(),
+            ));
+        }
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCGetHealthRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {}
+
+    /// Make the response.
+    /// NOTE: only in-memory network state may be used here, so that the probe never waits on a
+    /// DB lock.
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let connections = node.with_node_state(|network, _sortdb, _chainstate, _mempool, _| {
+            network.http_metrics.num_connections
+        });
+        let health = RPCHealthData {
+            status: "ok".to_string(),
+            connections,
+        };
+        let preamble = HttpResponsePreamble::ok_json(&preamble);
+        let body = HttpResponseContents::try_from_json(&health)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetHealthRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let health: RPCHealthData = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(health)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for the health probe
+    pub fn new_gethealth(host: PeerHost) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            "/v2/health".into(),
+            HttpRequestContents::new(),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_health(self) -> Result<RPCHealthData, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let response_json: serde_json::Value = contents.try_into()?;
+        let health: RPCHealthData = serde_json::from_value(response_json)
+            .map_err(|_e| Error::DecodeError("Failed to decode JSON".to_string()))?;
+        Ok(health)
+    }
+}
@@ -22,6 +22,7 @@ use stacks_common::types::chainstate::{
 };
 use stacks_common::types::net::PeerHost;
 use stacks_common::types::StacksPublicKeyBuffer;
+use stacks_common::util::get_epoch_time_secs;
 use stacks_common::util::hash::{Hash160, Sha256Sum};
 
 use crate::burnchains::affirmation::AffirmationMap;
@@ -30,8 +31,9 @@ use crate::chainstate::burn::db::sortdb::SortitionDB;
 use crate::chainstate::stacks::db::StacksChainState;
 use crate::core::mempool::MemPoolDB;
 use crate::net::http::{
-    parse_json, Error, HttpRequest, HttpRequestContents, HttpRequestPreamble, HttpResponse,
-    HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+    http_date, http_reason, if_none_match, parse_http_date, parse_json, Error, HttpContentType,
+    HttpRequest, HttpRequestContents, HttpRequestPreamble, HttpResponse, HttpResponseContents,
+    HttpResponsePayload, HttpResponsePreamble,
 };
 use crate::net::httpcore::{
     HttpPreambleExtensions, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
@@ -167,6 +169,48 @@ impl RPCPeerInfoData {
             ),
         }
     }
+    /// Fingerprint of this node's chain view.  Only the consensus-relevant fields (the Stacks
+    /// tip, its consensus hash and height, and the PoX consensus hash) are hashed, so two nodes
+    /// on the same view have the same fingerprint even if they run different software or have
+    /// seen different burnchain heights.
+    pub fn view_fingerprint(&self) -> Sha256Sum {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(self.stacks_tip.as_bytes());
+        bytes.extend_from_slice(self.stacks_tip_consensus_hash.as_bytes());
+        bytes.extend_from_slice(&self.stacks_tip_height.to_be_bytes());
+        bytes.extend_from_slice(self.pox_consensus.as_bytes());
+        Sha256Sum::from_data(&bytes)
+    }
+
+    /// Entity tag for a /v2/info response carrying this data.  It is derived from
+    /// `view_fingerprint()`, so it changes with every new Stacks block, even within a tenure.
+    /// It is weak, since other fields of the body (e.g. the burnchain height) can change while
+    /// the view stays the same.
+    pub fn etag(&self) -> String {
+        format!("W/\"{}\"", self.view_fingerprint().to_hex())
+    }
+}
+
+/// The Last-Modified time (in unix seconds) of a /v2/info response for the view with the given
+/// fingerprint, given the last view served (if any) and when it was last modified.  A new view
+/// is modified `now`, but always at least a second after the last one, so that a client that
+/// saw the last view within the same second does not get a 304 for the new one.
+pub fn view_last_modified(
+    last_modified: &mut Option<(Sha256Sum, u64)>,
+    fingerprint: Sha256Sum,
+    now: u64,
+) -> u64 {
+    match last_modified {
+        Some((last_fingerprint, modified)) if *last_fingerprint == fingerprint => *modified,
+        _ => {
+            let modified = match last_modified {
+                Some((_, last)) => now.max(last.saturating_add(1)),
+                None => now,
+            };
+            *last_modified = Some((fingerprint, modified));
+            modified
+        }
+    }
 }
 
 /// Decode the HTTP request
@@ -212,18 +256,53 @@ impl RPCRequestHandler for RPCPeerInfoRequestHandler {
         _contents: HttpRequestContents,
         node: &mut StacksNodeState,
     ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
-        let rpc_peer_info =
+        let (rpc_peer_info, last_modified) =
             node.with_node_state(|network, _sortdb, chainstate, _mempool, rpc_args| {
-                RPCPeerInfoData::from_network(
+                let rpc_peer_info = RPCPeerInfoData::from_network(
                     network,
                     chainstate,
                     rpc_args.exit_at_block_height.clone(),
                     &rpc_args.genesis_chainstate_hash,
-                )
+                );
+                // NOTE: a block timestamp would not do here, since every Stacks block in a
+                // Nakamoto tenure shares its burnchain block's timestamp
+                let last_modified = view_last_modified(
+                    &mut network.info_last_modified,
+                    rpc_peer_info.view_fingerprint(),
+                    get_epoch_time_secs(),
+                );
+                (rpc_peer_info, last_modified)
             });
-        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+
+        // per RFC 7232, If-Modified-Since only counts if there's no If-None-Match
+        let etag = rpc_peer_info.etag();
+        let not_modified = match preamble.get_header("if-none-match".to_string()) {
+            Some(header) => if_none_match(&header, &etag),
+            None => preamble
+                .get_header("if-modified-since".to_string())
+                .and_then(|header| parse_http_date(&header))
+                .map(|since| last_modified <= since)
+                .unwrap_or(false),
+        };
+
+        let (mut preamble, body) = if not_modified {
+            let preamble = HttpResponsePreamble::from_http_request_preamble(
+                &preamble,
+                304,
+                http_reason(304),
+                Some(0),
+                HttpContentType::JSON,
+            );
+            (preamble, HttpResponseContents::from_ram(vec![]))
+        } else {
+            (
+                HttpResponsePreamble::ok_json(&preamble),
+                HttpResponseContents::try_from_json(&rpc_peer_info)?,
+            )
+        };
         preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
-        let body = HttpResponseContents::try_from_json(&rpc_peer_info)?;
+        preamble.add_header("ETag".to_string(), etag);
+        preamble.add_header("Last-Modified".to_string(), http_date(last_modified));
         Ok((preamble, body))
     }
 }
@@ -235,6 +314,10 @@ impl HttpResponse for RPCPeerInfoRequestHandler {
         preamble: &HttpResponsePreamble,
         body: &[u8],
     ) -> Result<HttpResponsePayload, Error> {
+        if preamble.status_code == 304 {
+            // conditional GET, and the tip has not advanced
+            return Ok(HttpResponsePayload::Empty);
+        }
         let peer_info: RPCPeerInfoData = parse_json(preamble, body)?;
         Ok(HttpResponsePayload::try_from_json(peer_info)?)
     }
@@ -0,0 +1,213 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::fmt::Write;
+
+use regex::{Captures, Regex};
+use stacks_common::codec::MAX_MESSAGE_LEN;
+use stacks_common::types::net::PeerHost;
+
+use crate::net::http::common::parse_raw_bytes;
+use crate::net::http::{
+    Error, HttpContentType, HttpRequest, HttpRequestContents, HttpRequestPreamble, HttpResponse,
+    HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    HttpPreambleExtensions, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::server::HttpPeerMetrics;
+use crate::net::{Error as NetError, StacksNodeState};
+
+/// The request to GET /metrics
+#[derive(Clone)]
+pub struct RPCMetricsRequestHandler {}
+impl RPCMetricsRequestHandler {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Append one gauge to a Prometheus text-format exposition
+fn write_gauge(out: &mut String, name: &str, help: &str, value: u64) {
+    // NOTE: writing to a String is infallible
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+/// Render the node's in-memory network counters in the Prometheus text format
+pub fn render_metrics(http_metrics: &HttpPeerMetrics, num_p2p_connections: u64) -> String {
+    let mut out = String::new();
+    write_gauge(
+        &mut out,
+        "stacks_node_p2p_connections",
+        "Number of established p2p conversations",
+        num_p2p_connections,
+    );
+    write_gauge(
+        &mut out,
+        "stacks_node_http_connections",
+        "Number of established HTTP conversations",
+        http_metrics.num_connections,
+    );
+    write_gauge(
+        &mut out,
+        "stacks_node_http_connecting",
+        "Number of outbound HTTP sockets that are still connecting",
+        http_metrics.num_connecting,
+    );
+    write_gauge(
+        &mut out,
+        "stacks_node_http_requests_received",
+        "Requests received over the established HTTP conversations",
+        http_metrics.requests_received,
+    );
+    write_gauge(
+        &mut out,
+        "stacks_node_http_bytes_received",
+        "Bytes received over the established HTTP conversations",
+        http_metrics.bytes_received,
+    );
+    write_gauge(
+        &mut out,
+        "stacks_node_http_bytes_sent",
+        "Bytes sent over the established HTTP conversations",
+        http_metrics.bytes_sent,
+    );
+    write_gauge(
+        &mut out,
+        "stacks_node_http_estimated_memory_bytes",
+        "Approximate number of bytes the HTTP server is holding in memory",
+        http_metrics.estimated_memory_bytes,
+    );
+    write_gauge(
+        &mut out,
+        "stacks_node_http_requests_served",
+        "Requests the HTTP server has replied to since it started",
+        http_metrics.total_requests_served,
+    );
+    write_gauge(
+        &mut out,
+        "stacks_node_http_error_responses",
+        "Replies with a 400, 429, or 5xx status since the HTTP server started",
+        http_metrics.total_error_responses,
+    );
+    out
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCMetricsRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/metrics$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/metrics"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body for GetMetrics".to_string(),
+            ));
+        }
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCMetricsRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {}
+
+    /// Make the response.
+    /// Only in-memory counters are read; the chainstate is not touched.
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let metrics = node.with_node_state(|network, _sortdb, _chainstate, _mempool, _rpc_args| {
+            render_metrics(&network.http_metrics, network.get_num_p2p_convos() as u64)
+        });
+        let body = metrics.into_bytes();
+        let preamble = HttpResponsePreamble::from_http_request_preamble(
+            &preamble,
+            200,
+            "OK",
+            Some(body.len() as u32),
+            HttpContentType::Text,
+        );
+        Ok((preamble, HttpResponseContents::from_ram(body)))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCMetricsRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let bytes = parse_raw_bytes(
+            preamble,
+            body,
+            MAX_MESSAGE_LEN.into(),
+            HttpContentType::Text,
+        )?;
+        let text = String::from_utf8(bytes)
+            .map_err(|_e| Error::DecodeError("Metrics are not UTF-8".to_string()))?;
+        Ok(HttpResponsePayload::Text(text))
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to scrape the metrics endpoint, authenticating with the given bearer
+    /// token if one is given
+    pub fn new_getmetrics(host: PeerHost, auth_token: Option<&str>) -> StacksHttpRequest {
+        let mut req = StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            "/metrics".into(),
+            HttpRequestContents::new(),
+        )
+        .expect("FATAL: failed to construct request from infallible data");
+        if let Some(auth_token) = auth_token {
+            req.add_header("authorization".into(), format!("Bearer {}", auth_token));
+        }
+        req
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_metrics(self) -> Result<String, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let metrics: String = contents.try_into()?;
+        Ok(metrics)
+    }
+}
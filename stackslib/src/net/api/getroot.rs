@@ -0,0 +1,140 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::codec::MAX_MESSAGE_LEN;
+use stacks_common::types::net::PeerHost;
+
+use crate::net::http::common::parse_raw_bytes;
+use crate::net::http::{
+    Error, HttpContentType, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{RPCRequestHandler, StacksHttpRequest, StacksHttpResponse};
+use crate::net::{Error as NetError, StacksNodeState};
+
+/// What `GET /` says if no `root_response` is configured
+pub const DEFAULT_ROOT_NOT_FOUND_TEXT: &str =
+    "This is a Stacks node.  Its API lives under /v2 and /v3; try GET /v2/info.";
+
+/// The request to GET the root path, i.e. someone pointing their browser at the node
+#[derive(Clone)]
+pub struct RPCGetRootRequestHandler {
+    /// Text to serve with a 200.  If not set, a 404 with a pointer to the API is served instead.
+    pub root_response: Option<String>,
+}
+impl RPCGetRootRequestHandler {
+    pub fn new(root_response: Option<String>) -> Self {
+        Self { root_response }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetRootRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body for GetRoot".to_string(),
+            ));
+        }
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCGetRootRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {}
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        _node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let Some(root_response) = self.root_response.as_ref() else {
+            return StacksHttpResponse::new_error(
+                &preamble,
+                &HttpNotFound::new(DEFAULT_ROOT_NOT_FOUND_TEXT.to_string()),
+            )
+            .try_into_contents();
+        };
+        let body = root_response.as_bytes().to_vec();
+        let preamble = HttpResponsePreamble::from_http_request_preamble(
+            &preamble,
+            200,
+            "OK",
+            Some(body.len() as u32),
+            HttpContentType::Text,
+        );
+        Ok((preamble, HttpResponseContents::from_ram(body)))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetRootRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let bytes = parse_raw_bytes(
+            preamble,
+            body,
+            MAX_MESSAGE_LEN.into(),
+            HttpContentType::Text,
+        )?;
+        let text = String::from_utf8(bytes)
+            .map_err(|_e| Error::DecodeError("Root response is not UTF-8".to_string()))?;
+        Ok(HttpResponsePayload::Text(text))
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for the root path
+    pub fn new_getroot(host: PeerHost) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(host, "GET".into(), "/".into(), HttpRequestContents::new())
+            .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_root_response(self) -> Result<String, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let text: String = contents.try_into()?;
+        Ok(text)
+    }
+}
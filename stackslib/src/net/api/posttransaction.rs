@@ -42,6 +42,7 @@ use crate::net::http::{
 };
 use crate::net::httpcore::{
     HttpPreambleExtensions, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+    IDEMPOTENCY_KEY,
 };
 use crate::net::p2p::PeerNetwork;
 use crate::net::relay::Relayer;
@@ -182,7 +183,9 @@ impl RPCRequestHandler for RPCPostTransactionRequestHandler {
         self.attachment = None;
     }
 
-    /// Make the response
+    /// Make the response.
+    /// If the client sent an `Idempotency-Key`, and a response was already made for that client
+    /// and key, then reply with that response instead of submitting the transaction again.
     fn try_handle_request(
         &mut self,
         preamble: HttpRequestPreamble,
@@ -195,6 +198,65 @@ impl RPCRequestHandler for RPCPostTransactionRequestHandler {
             .ok_or(NetError::SendError("`tx` not set".into()))?;
         let attachment_opt = self.attachment.take();
 
+        let txid = tx.txid();
+        // keys are only meaningful to the client that chose them
+        let idempotency_key = preamble
+            .get_header(IDEMPOTENCY_KEY.to_string())
+            .zip(node.client_ip());
+
+        if let Some((key, client_ip)) = idempotency_key.as_ref() {
+            let cached = node
+                .with_idempotency_cache(|cache| cache.get(client_ip, key).cloned())
+                .flatten();
+            if let Some(cached) = cached {
+                if cached.txid != txid {
+                    return StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpBadRequest::new(format!(
+                            "Idempotency-Key '{}' was already used for transaction {}",
+                            key, &cached.txid
+                        )),
+                    )
+                    .try_into_contents()
+                    .map_err(NetError::from);
+                }
+                debug!(
+                    "Replying to POSTed transaction {} with the response cached for its idempotency key",
+                    &txid
+                );
+                return Ok((cached.preamble, HttpResponseContents::RAM(cached.body)));
+            }
+        }
+
+        let (response_preamble, response_contents) =
+            self.submit_transaction(&preamble, tx, attachment_opt, node)?;
+
+        if let (Some((key, client_ip)), HttpResponseContents::RAM(body)) =
+            (idempotency_key, &response_contents)
+        {
+            node.with_idempotency_cache(|cache| {
+                cache.insert(
+                    client_ip,
+                    key,
+                    txid,
+                    response_preamble.clone(),
+                    body.clone(),
+                )
+            });
+        }
+        Ok((response_preamble, response_contents))
+    }
+}
+
+impl RPCPostTransactionRequestHandler {
+    /// Submit the transaction (and attachment, if given) to the mempool, and make the response
+    fn submit_transaction(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        tx: StacksTransaction,
+        attachment_opt: Option<Attachment>,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
         let txid = tx.txid();
 
         let data_resp = node.with_node_state(|network, sortdb, chainstate, mempool, rpc_args| {
@@ -205,8 +267,8 @@ impl RPCRequestHandler for RPCPostTransactionRequestHandler {
             }
 
             let event_observer = rpc_args.event_observer.as_deref();
-            let burn_tip = self.get_canonical_burn_chain_tip(&preamble, sortdb)?;
-            let stacks_epoch = self.get_stacks_epoch(&preamble, sortdb, burn_tip.block_height)?;
+            let burn_tip = self.get_canonical_burn_chain_tip(preamble, sortdb)?;
+            let stacks_epoch = self.get_stacks_epoch(preamble, sortdb, burn_tip.block_height)?;
 
             // check for defects which can be determined statically
             if Relayer::do_static_problematic_checks()
@@ -227,7 +289,7 @@ impl RPCRequestHandler for RPCPostTransactionRequestHandler {
                 return Ok(false);
             }
 
-            let stacks_tip = self.get_stacks_chain_tip(&preamble, sortdb, chainstate)?;
+            let stacks_tip = self.get_stacks_chain_tip(preamble, sortdb, chainstate)?;
 
             // accept to mempool
             if let Err(e) = mempool.submit(
@@ -241,7 +303,7 @@ impl RPCRequestHandler for RPCPostTransactionRequestHandler {
                 &stacks_epoch.epoch_id,
             ) {
                 return Err(StacksHttpResponse::new_error(
-                    &preamble,
+                    preamble,
                     &HttpBadRequest::new_json(e.into_json(&txid)),
                 ));
             };
@@ -258,7 +320,7 @@ impl RPCRequestHandler for RPCPostTransactionRequestHandler {
                             .insert_uninstantiated_attachment(attachment)
                             .map_err(|e| {
                                 StacksHttpResponse::new_error(
-                                    &preamble,
+                                    preamble,
                                     &HttpServerError::new(format!(
                                         "Failed to store contract-call attachment: {:?}",
                                         &e
@@ -284,7 +346,7 @@ impl RPCRequestHandler for RPCPostTransactionRequestHandler {
             node.set_relay_message(StacksMessageType::Transaction(tx));
         }
 
-        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        let mut preamble = HttpResponsePreamble::ok_json(preamble);
         preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
         let body = HttpResponseContents::try_from_json(&txid)?;
         Ok((preamble, body))
@@ -0,0 +1,104 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use super::TestRPC;
+use crate::net::api::*;
+use crate::net::connection::{ConnectionOptions, CorsConfig};
+use crate::net::httpcore::{StacksHttp, StacksHttpRequest};
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut conn_opts = ConnectionOptions::default();
+    conn_opts.cors = Some(CorsConfig::default());
+    let mut http = StacksHttp::new(addr.clone(), &conn_opts);
+
+    let request = StacksHttpRequest::new_cors_preflight(addr.into(), "/v2/info");
+    let bytes = request.try_serialize().unwrap();
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = corspreflight::RPCCorsPreflightRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+    assert_eq!(preamble.verb, "OPTIONS");
+    assert_eq!(preamble.path_and_query_str, "/v2/info");
+
+    // not served unless enabled
+    let http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+    assert!(!http.enable_cors_preflight);
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let cors = CorsConfig {
+        allow_origin: "https://app.example.com".to_string(),
+        allow_methods: "GET, POST".to_string(),
+        allow_headers: "content-type, x-request-id".to_string(),
+    };
+    // (the client needs CORS enabled to know how to decode the response, too)
+    let rpc_test = TestRPC::setup_with_conn_opts(function_name!(), |conn_opts| {
+        conn_opts.cors = Some(cors.clone());
+    });
+
+    let requests = vec![
+        StacksHttpRequest::new_cors_preflight(addr.into(), "/v2/info"),
+        StacksHttpRequest::new_getinfo(addr.into(), None),
+    ];
+    let mut responses = rpc_test.run(requests);
+
+    // preflight gets a 204 with the configured headers
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let (preamble, _body) = response.destruct();
+    assert_eq!(preamble.status_code, 204);
+    assert_eq!(
+        preamble.get_header("access-control-allow-origin".to_string()),
+        Some(cors.allow_origin.clone())
+    );
+    assert_eq!(
+        preamble.get_header("access-control-allow-methods".to_string()),
+        Some(cors.allow_methods.clone())
+    );
+    assert_eq!(
+        preamble.get_header("access-control-allow-headers".to_string()),
+        Some(cors.allow_headers.clone())
+    );
+
+    // actual responses carry the configured origin
+    let response = responses.remove(0);
+    let (preamble, _body) = response.destruct();
+    assert_eq!(preamble.status_code, 200);
+    assert_eq!(
+        preamble.get_header("access-control-allow-origin".to_string()),
+        Some(cors.allow_origin.clone())
+    );
+}
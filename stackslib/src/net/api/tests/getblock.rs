@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::io::Read;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
 use clarity::vm::types::{QualifiedContractIdentifier, StacksAddressExtensions};
@@ -34,12 +35,14 @@ use crate::chainstate::stacks::{
 use crate::net::api::getblock::StacksBlockStream;
 use crate::net::api::*;
 use crate::net::connection::ConnectionOptions;
-use crate::net::http::HttpChunkGenerator;
+use crate::net::http::{
+    GzipChunkGenerator, HttpChunkGenerator, HttpErrorResponse, HttpResponsePayload,
+};
 use crate::net::httpcore::{
     HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp,
     StacksHttpRequest,
 };
-use crate::net::{ProtocolFamily, TipRequest};
+use crate::net::{Error as NetError, ProtocolFamily, TipRequest};
 use crate::util_lib::db::DBConn;
 
 #[test]
@@ -75,6 +78,47 @@ fn test_try_parse_request() {
     assert!(handler.block_id.is_none());
 }
 
+#[test]
+fn test_try_parse_percent_encoded_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    // same as `/v2/blocks/1111...`, but with the first two hex digits percent-encoded
+    let request_bytes = format!(
+        "GET /v2/blocks/%31%31{} HTTP/1.1\r\nHost: 127.0.0.1:33333\r\n\r\n",
+        "1".repeat(62)
+    )
+    .into_bytes();
+    let (parsed_preamble, offset) = http.read_preamble(&request_bytes).unwrap();
+    let mut handler = getblock::RPCBlocksRequestHandler::new();
+    http.handle_try_parse_request(
+        &mut handler,
+        &parsed_preamble.expect_request(),
+        &request_bytes[offset..],
+    )
+    .unwrap();
+    assert_eq!(handler.block_id, Some(StacksBlockId([0x11; 32])));
+
+    // a truncated escape is a bad request, not a missing route
+    let request_bytes = format!(
+        "GET /v2/blocks/%3{} HTTP/1.1\r\nHost: 127.0.0.1:33333\r\n\r\n",
+        "1".repeat(63)
+    )
+    .into_bytes();
+    let (parsed_preamble, offset) = http.read_preamble(&request_bytes).unwrap();
+    match http.try_parse_request(&parsed_preamble.expect_request(), &request_bytes[offset..]) {
+        Err(NetError::Http(http_error)) => {
+            let http_error = http_error.into_http_error();
+            assert_eq!(http_error.code(), 400);
+            let HttpResponsePayload::Text(reason) = http_error.payload() else {
+                panic!("Expected a text error");
+            };
+            assert!(reason.contains("Malformed percent-encoding"), "{}", reason);
+        }
+        res => panic!("Expected a 400, got {:?}", res),
+    }
+}
+
 #[test]
 fn test_try_make_response() {
     let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
@@ -111,6 +155,125 @@ fn test_try_make_response() {
     assert_eq!(preamble.status_code, 404);
 }
 
+#[test]
+fn test_try_make_chunked_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let rpc_test = TestRPC::setup(function_name!());
+    let stacks_chain_tip = rpc_test.canonical_tip.clone();
+    let consensus_hash = rpc_test.consensus_hash.clone();
+
+    let mut requests = vec![];
+
+    // HTTP/1.1 client gets a chunked response.  (HTTP/1.0 clients get the body as-is, up to
+    // the connection closing; see `test_http_10_streams_without_chunked_encoding()`)
+    let request = StacksHttpRequest::new_getblock(addr.into(), stacks_chain_tip.clone());
+    requests.push(request);
+
+    let mut responses = rpc_test.run(requests);
+
+    // chunks reassemble into the block
+    let response = responses.remove(0);
+    assert!(response.preamble().is_chunked());
+    let chunked_block = response.decode_block().unwrap();
+    assert_eq!(
+        StacksBlockHeader::make_index_block_hash(&consensus_hash, &chunked_block.block_hash()),
+        stacks_chain_tip
+    );
+}
+
+#[test]
+fn test_try_make_gzip_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let rpc_test = TestRPC::setup(function_name!());
+    let stacks_chain_tip = rpc_test.canonical_tip.clone();
+
+    let mut requests = vec![];
+
+    // client takes gzip
+    let mut request = StacksHttpRequest::new_getblock(addr.into(), stacks_chain_tip.clone());
+    request
+        .preamble_mut()
+        .add_header("Accept-Encoding".into(), "deflate, gzip".into());
+    requests.push(request);
+
+    // client does not
+    let request = StacksHttpRequest::new_getblock(addr.into(), stacks_chain_tip.clone());
+    requests.push(request);
+
+    let mut responses = rpc_test.run(requests);
+
+    // compressed body is still streamed, and decompresses into the block
+    let response = responses.remove(0);
+    assert!(response.preamble().is_chunked());
+    assert_eq!(
+        response
+            .preamble()
+            .get_header("content-encoding".to_string()),
+        Some("gzip".to_string())
+    );
+    let gzip_block = response.decode_block().unwrap();
+
+    let response = responses.remove(0);
+    assert!(response.preamble().is_chunked());
+    assert!(response
+        .preamble()
+        .get_header("content-encoding".to_string())
+        .is_none());
+    let block = response.decode_block().unwrap();
+
+    assert_eq!(gzip_block, block);
+}
+
+#[test]
+fn test_stream_blocks_gzip() {
+    let mut chainstate = instantiate_chainstate(false, 0x80000000, function_name!());
+    let privk = StacksPrivateKey::from_hex(
+        "eb05c83546fdd2c79f10f5ad5434a90dd28f7e3acb7c092157aa1bc3656b012c01",
+    )
+    .unwrap();
+
+    let block = make_16k_block(&privk);
+
+    let consensus_hash = ConsensusHash([2u8; 20]);
+    let parent_consensus_hash = ConsensusHash([1u8; 20]);
+    let index_block_header =
+        StacksBlockHeader::make_index_block_hash(&consensus_hash, &block.block_hash());
+
+    store_staging_block(
+        &mut chainstate,
+        &consensus_hash,
+        &block,
+        &parent_consensus_hash,
+        1,
+        2,
+    );
+
+    let stream = StacksBlockStream::new(&chainstate, &index_block_header).unwrap();
+    let mut stream = GzipChunkGenerator::new(Box::new(stream)).unwrap();
+
+    // stream it back, compressed
+    let mut num_chunks = 0;
+    let mut all_gzip_bytes = vec![];
+    loop {
+        let mut next_bytes = stream.generate_next_chunk().unwrap();
+        if next_bytes.is_empty() {
+            break;
+        }
+        num_chunks += 1;
+        all_gzip_bytes.append(&mut next_bytes);
+    }
+    // compressed as it went, not all at once
+    assert!(num_chunks > 2);
+
+    // should decompress into the block's bytes
+    let mut decoder = libflate::gzip::Decoder::new(&all_gzip_bytes[..]).unwrap();
+    let mut all_block_bytes = vec![];
+    decoder.read_to_end(&mut all_block_bytes).unwrap();
+    assert_eq!(all_block_bytes, block.serialize_to_vec());
+}
+
 #[test]
 fn test_stream_blocks() {
     let mut chainstate = instantiate_chainstate(false, 0x80000000, function_name!());
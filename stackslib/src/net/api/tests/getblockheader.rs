@@ -0,0 +1,138 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use stacks_common::codec::StacksMessageCodec;
+use stacks_common::types::chainstate::StacksBlockId;
+
+use super::TestRPC;
+use crate::chainstate::stacks::db::StacksBlockHeaderTypes;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{RPCRequestHandler, StacksHttp, StacksHttpRequest};
+use crate::net::test::TestEventObserver;
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request = StacksHttpRequest::new_get_block_header(addr.into(), StacksBlockId([0x11; 32]));
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getblockheader::RPCBlockHeaderRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, contents) = parsed_request.destruct();
+
+    // consumed path args
+    assert_eq!(handler.block_id, Some(StacksBlockId([0x11; 32])));
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.block_id.is_none());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let rpc_test = TestRPC::setup(function_name!());
+    let stacks_chain_tip = rpc_test.canonical_tip.clone();
+
+    let mut requests = vec![];
+
+    // query existing block header
+    let request = StacksHttpRequest::new_get_block_header(addr.into(), stacks_chain_tip.clone());
+    requests.push(request);
+
+    // query the whole block, for comparison
+    let request = StacksHttpRequest::new_getblock(addr.into(), stacks_chain_tip.clone());
+    requests.push(request);
+
+    // query non-existant block header
+    let request = StacksHttpRequest::new_get_block_header(addr.into(), StacksBlockId([0x11; 32]));
+    requests.push(request);
+
+    let mut responses = rpc_test.run(requests);
+
+    // got the header
+    let response = responses.remove(0);
+    let header = response.decode_block_header().unwrap();
+    let StacksBlockHeaderTypes::Epoch2(header) = header else {
+        panic!("Expected an epoch 2.x header, got {:?}", &header);
+    };
+
+    // it's the stored block's header, and it's a lot smaller than the block
+    let response = responses.remove(0);
+    let block = response.decode_block().unwrap();
+    assert_eq!(header, block.header);
+    assert!(header.serialize_to_vec().len() * 2 < block.serialize_to_vec().len());
+
+    // no block
+    let response = responses.remove(0);
+    let (preamble, body) = response.destruct();
+
+    assert_eq!(preamble.status_code, 404);
+}
+
+#[test]
+fn test_try_make_nakamoto_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let test_observer = TestEventObserver::new();
+    let rpc_test = TestRPC::setup_nakamoto(function_name!(), &test_observer);
+    let nakamoto_chain_tip = rpc_test.canonical_tip.clone();
+
+    let mut requests = vec![];
+
+    // query existing block header
+    let request = StacksHttpRequest::new_get_block_header(addr.into(), nakamoto_chain_tip.clone());
+    requests.push(request);
+
+    // query the whole block, for comparison
+    let request =
+        StacksHttpRequest::new_get_nakamoto_block(addr.into(), nakamoto_chain_tip.clone());
+    requests.push(request);
+
+    let mut responses = rpc_test.run(requests);
+
+    // got the header
+    let response = responses.remove(0);
+    let header = response.decode_block_header().unwrap();
+    let StacksBlockHeaderTypes::Nakamoto(header) = header else {
+        panic!("Expected a Nakamoto header, got {:?}", &header);
+    };
+
+    // it's the stored block's header, and it's smaller than the block
+    let response = responses.remove(0);
+    let block = response.decode_nakamoto_block().unwrap();
+    assert_eq!(header, block.header);
+    assert!(header.serialize_to_vec().len() < block.serialize_to_vec().len());
+}
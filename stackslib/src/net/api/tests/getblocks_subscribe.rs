@@ -0,0 +1,170 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use stacks_common::types::chainstate::{BlockHeaderHash, ConsensusHash, StacksBlockId};
+
+use super::TestRPC;
+use crate::net::api::getblocks_subscribe::{
+    BlockAnnouncement, BlockAnnouncementStream, BlockSubscribers, MAX_PENDING_BLOCK_ANNOUNCEMENTS,
+};
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::http::HttpChunkGenerator;
+use crate::net::httpcore::{StacksHttp, StacksHttpRequest};
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request = StacksHttpRequest::new_subscribe_blocks(addr.into());
+    let bytes = request.try_serialize().unwrap();
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getblocks_subscribe::RPCBlocksSubscribeRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+}
+
+#[test]
+fn test_block_subscribers() {
+    let tip_1 = (ConsensusHash([0x01; 20]), BlockHeaderHash([0x11; 32]), 1);
+    let tip_2 = (ConsensusHash([0x02; 20]), BlockHeaderHash([0x22; 32]), 2);
+
+    let mut subscribers = BlockSubscribers::default();
+    let mut stream = BlockAnnouncementStream::new(subscribers.subscribe());
+    let gone = subscribers.subscribe();
+    drop(gone);
+    let slow = subscribers.subscribe();
+    assert_eq!(subscribers.len(), 3);
+
+    // nothing to send yet, but the stream stays open
+    assert!(stream.generate_next_chunk().unwrap().is_empty());
+    assert!(stream.is_live());
+
+    assert!(subscribers.announce_tip(&tip_1));
+    // the subscriber that went away is dropped
+    assert_eq!(subscribers.len(), 2);
+    // the same tip is only announced once
+    assert!(!subscribers.announce_tip(&tip_1));
+
+    let line = stream.generate_next_chunk().unwrap();
+    assert_eq!(line.last(), Some(&b'\n'));
+    let announcement: BlockAnnouncement = serde_json::from_slice(&line).unwrap();
+    assert_eq!(
+        announcement,
+        BlockAnnouncement {
+            consensus_hash: tip_1.0.clone(),
+            block_hash: tip_1.1.clone(),
+            index_block_hash: StacksBlockId::new(&tip_1.0, &tip_1.1),
+            height: 1,
+        }
+    );
+    assert!(stream.generate_next_chunk().unwrap().is_empty());
+
+    // a subscriber that doesn't read its announcements is eventually dropped
+    for i in 0..MAX_PENDING_BLOCK_ANNOUNCEMENTS {
+        let tip = (
+            ConsensusHash([0x03; 20]),
+            BlockHeaderHash([0x33; 32]),
+            i as u64,
+        );
+        let tip = if i % 2 == 0 { tip } else { tip_2.clone() };
+        subscribers.announce_tip(&tip);
+        stream.generate_next_chunk().unwrap();
+    }
+    assert_eq!(subscribers.len(), 1);
+    drop(slow);
+
+    // the stream ends once the subscription is gone
+    drop(subscribers);
+    assert!(stream.generate_next_chunk().unwrap().is_empty());
+    assert!(!stream.is_live());
+}
+
+#[test]
+fn test_block_subscribers_prune() {
+    let mut subscribers = BlockSubscribers::default();
+    let mut kept = BlockAnnouncementStream::new(subscribers.subscribe());
+    let gone = subscribers.subscribe();
+    let stream = BlockAnnouncementStream::new(subscribers.subscribe());
+    assert_eq!(subscribers.len(), 3);
+
+    // nothing to prune yet
+    subscribers.prune();
+    assert_eq!(subscribers.len(), 3);
+
+    // subscribers that went away are pruned without sending anything to the rest
+    drop(gone);
+    drop(stream);
+    subscribers.prune();
+    assert_eq!(subscribers.len(), 1);
+    assert!(kept.generate_next_chunk().unwrap().is_empty());
+    assert!(kept.is_live());
+}
+
+#[test]
+fn test_block_subscribers_heartbeat() {
+    let mut subscribers = BlockSubscribers::default();
+
+    // no one to send a heartbeat to
+    assert!(!subscribers.heartbeat(1000, 30));
+
+    let mut stream = BlockAnnouncementStream::new(subscribers.subscribe());
+    assert!(subscribers.heartbeat(1000, 30));
+    assert_eq!(stream.generate_next_chunk().unwrap(), b"\n".to_vec());
+
+    // not due yet
+    assert!(!subscribers.heartbeat(1029, 30));
+    assert!(subscribers.heartbeat(1030, 30));
+    assert_eq!(stream.generate_next_chunk().unwrap(), b"\n".to_vec());
+
+    // disabled
+    assert!(!subscribers.heartbeat(2000, 0));
+    assert!(stream.generate_next_chunk().unwrap().is_empty());
+}
+
+#[test]
+fn test_try_make_response_over_capacity() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut rpc_test = TestRPC::setup(function_name!());
+
+    // the serving peer is already at capacity
+    rpc_test.peer_2.network.connection_opts.max_subscriptions = 2;
+    let _subscriptions: Vec<_> = (0..2)
+        .map(|_| rpc_test.peer_2.network.block_subscribers.subscribe())
+        .collect();
+
+    let request = StacksHttpRequest::new_subscribe_blocks(addr.into());
+    let mut responses = rpc_test.run(vec![request]);
+
+    let response = responses.remove(0);
+    let (preamble, _body) = response.destruct();
+    assert_eq!(preamble.status_code, 503);
+}
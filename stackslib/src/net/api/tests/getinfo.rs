@@ -20,19 +20,22 @@ use clarity::vm::types::{QualifiedContractIdentifier, StacksAddressExtensions};
 use clarity::vm::{ClarityName, ContractName};
 use serde_json;
 use stacks_common::codec::StacksMessageCodec;
-use stacks_common::types::chainstate::StacksAddress;
+use stacks_common::types::chainstate::{BlockHeaderHash, ConsensusHash, StacksAddress};
 use stacks_common::types::net::PeerHost;
 use stacks_common::types::Address;
+use stacks_common::util::get_epoch_time_secs;
+use stacks_common::util::hash::Sha256Sum;
 
-use super::test_rpc;
-use crate::net::api::getinfo::RPCPeerInfoData;
+use super::{convo_send_recv, test_rpc, TestRPC};
+use crate::net::api::getinfo::{view_last_modified, RPCPeerInfoData};
 use crate::net::api::*;
 use crate::net::connection::ConnectionOptions;
+use crate::net::http::{http_date, if_none_match, parse_http_date, HttpVersion};
 use crate::net::httpcore::{
     HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp,
     StacksHttpRequest,
 };
-use crate::net::{ProtocolFamily, TipRequest};
+use crate::net::{ProtocolFamily, RPCHandlerArgs, StacksNodeState, TipRequest};
 
 #[test]
 fn test_try_parse_request() {
@@ -79,6 +82,39 @@ fn test_getinfo_compat() {
     }
 }
 
+#[test]
+fn test_view_fingerprint() {
+    let getinfo_json = r#"{"peer_version":402653189,"pox_consensus":"b712eb731b613eebae814a8f416c5c15bc8391ec","burn_block_height":727631,"stable_pox_consensus":"53b5ed79842080500d7d83daa36aa1069dedf983","stable_burn_block_height":727624,"server_version":"stacks-node 0.0.1 (feat/faster-inv-generation:68f33190a, release build, linux [x86_64])","network_id":1,"parent_network_id":3652501241,"stacks_tip_height":52537,"stacks_tip":"b3183f2ac588e12319ff0fde78f97e62c92a218d87828c35710c29aaf7adbedc","stacks_tip_consensus_hash":"b712eb731b613eebae814a8f416c5c15bc8391ec","genesis_chainstate_hash":"74237aa39aa50a83de11a4f53e9d3bb7d43461d1de9873f402e5453ae60bc59b","unanchored_tip":"e76f68d607480e9984b4062b2691fb60a88423177898f5780b40ace17ae8982a","unanchored_seq":0,"exit_at_block_height":null}"#;
+    let peer_info: RPCPeerInfoData = serde_json::from_str(getinfo_json).unwrap();
+
+    // same view, different software and burnchain progress
+    let mut other_peer_info = peer_info.clone();
+    other_peer_info.server_version = "stacks-node 0.0.2".to_string();
+    other_peer_info.burn_block_height += 1;
+    assert_ne!(peer_info, other_peer_info);
+    assert_eq!(
+        peer_info.view_fingerprint(),
+        other_peer_info.view_fingerprint()
+    );
+
+    // different tip
+    let mut other_peer_info = peer_info.clone();
+    other_peer_info.stacks_tip = BlockHeaderHash([0x11; 32]);
+    other_peer_info.stacks_tip_height += 1;
+    assert_ne!(
+        peer_info.view_fingerprint(),
+        other_peer_info.view_fingerprint()
+    );
+
+    // different PoX view
+    let mut other_peer_info = peer_info.clone();
+    other_peer_info.pox_consensus = ConsensusHash([0x22; 20]);
+    assert_ne!(
+        peer_info.view_fingerprint(),
+        other_peer_info.view_fingerprint()
+    );
+}
+
 #[test]
 fn test_try_make_response() {
     let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
@@ -103,3 +139,242 @@ fn test_try_make_response() {
     );
     let resp = response.decode_peer_info().unwrap();
 }
+
+/// Have the serving peer's conversation handle whatever requests it has received
+fn serve_received_requests(rpc_test: &mut TestRPC) {
+    let peer_2 = &mut rpc_test.peer_2;
+    let sortdb = peer_2.sortdb.take().unwrap();
+    let mut stacks_node = peer_2.stacks_node.take().unwrap();
+    let mut mempool = peer_2.mempool.take().unwrap();
+    {
+        let rpc_args = RPCHandlerArgs::default();
+        let mut node_state = StacksNodeState::new(
+            &mut peer_2.network,
+            &sortdb,
+            &mut stacks_node.chainstate,
+            &mut mempool,
+            &rpc_args,
+        );
+        rpc_test.convo_2.chat(&mut node_state).unwrap();
+    }
+    peer_2.sortdb = Some(sortdb);
+    peer_2.stacks_node = Some(stacks_node);
+    peer_2.mempool = Some(mempool);
+}
+
+#[test]
+fn test_conversation_byte_counters() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut rpc_test = TestRPC::setup(function_name!());
+
+    let client_sent = rpc_test.convo_1.bytes_sent();
+    let client_received = rpc_test.convo_1.bytes_received();
+    let server_sent = rpc_test.convo_2.bytes_sent();
+    let server_received = rpc_test.convo_2.bytes_received();
+
+    let request = StacksHttpRequest::new_getinfo(addr.into(), Some(123));
+    let request_len = request.try_serialize().unwrap().len() as u64;
+
+    // client sends the request to the server
+    rpc_test.convo_1.send_request(request).unwrap();
+    convo_send_recv(&mut rpc_test.convo_1, &mut rpc_test.convo_2);
+
+    assert_eq!(rpc_test.convo_1.bytes_sent() - client_sent, request_len);
+    assert_eq!(
+        rpc_test.convo_2.bytes_received() - server_received,
+        request_len
+    );
+
+    // server handles it and replies
+    serve_received_requests(&mut rpc_test);
+
+    convo_send_recv(&mut rpc_test.convo_2, &mut rpc_test.convo_1);
+
+    let response_len = rpc_test.convo_2.bytes_sent() - server_sent;
+    assert!(response_len > 0);
+    assert_eq!(
+        rpc_test.convo_1.bytes_received() - client_received,
+        response_len
+    );
+
+    // nothing else went over the wire
+    assert_eq!(rpc_test.convo_1.bytes_sent() - client_sent, request_len);
+    assert_eq!(
+        rpc_test.convo_2.bytes_received() - server_received,
+        request_len
+    );
+}
+
+#[test]
+fn test_conversation_protocol_info() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut rpc_test = TestRPC::setup(function_name!());
+
+    // nothing handled yet
+    let info = rpc_test.convo_2.protocol_info();
+    assert_eq!(info.http_version, None);
+    assert_eq!(info.last_method, None);
+    assert_eq!(info.last_path, None);
+    assert!(info.keep_alive);
+
+    // HTTP/1.0 client
+    let mut request = StacksHttpRequest::new_getinfo(addr.into(), Some(123));
+    request.preamble_mut().version = HttpVersion::Http10;
+    request.preamble_mut().keep_alive = false;
+
+    rpc_test.convo_1.send_request(request).unwrap();
+    convo_send_recv(&mut rpc_test.convo_1, &mut rpc_test.convo_2);
+
+    serve_received_requests(&mut rpc_test);
+
+    let info = rpc_test.convo_2.protocol_info();
+    assert_eq!(info.http_version, Some(HttpVersion::Http10));
+    assert_eq!(info.last_method, Some("GET".to_string()));
+    assert_eq!(info.last_path, Some("/v2/info".to_string()));
+    assert!(!info.keep_alive);
+}
+
+#[test]
+fn test_try_make_conditional_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let mut rpc_test = TestRPC::setup(function_name!());
+
+    // the serving peer's current view, and its view as of the Stacks block before its tip.  Both
+    // have the same burnchain view, as Stacks blocks in the same Nakamoto tenure would.
+    rpc_test.peer_2.refresh_burnchain_view();
+    let peer_info = RPCPeerInfoData::from_network(
+        &rpc_test.peer_2.network,
+        &rpc_test.peer_2.stacks_node.as_ref().unwrap().chainstate,
+        None,
+        &Sha256Sum([0x00; 32]),
+    );
+    let mut old_peer_info = peer_info.clone();
+    old_peer_info.stacks_tip = BlockHeaderHash([0x11; 32]);
+    old_peer_info.stacks_tip_height -= 1;
+    assert_ne!(peer_info.etag(), old_peer_info.etag());
+
+    let mut requests = vec![];
+
+    // unconditional
+    let request = StacksHttpRequest::new_getinfo(addr.into(), Some(123));
+    requests.push(request);
+
+    // tip has not advanced since the client's copy
+    let mut request = StacksHttpRequest::new_getinfo(addr.into(), Some(123));
+    request.add_header("If-None-Match".to_string(), peer_info.etag());
+    requests.push(request);
+
+    // tip has advanced since the client's copy
+    let mut request = StacksHttpRequest::new_getinfo(addr.into(), Some(123));
+    request.add_header("If-None-Match".to_string(), old_peer_info.etag());
+    requests.push(request);
+
+    // client's copy is from after the view was first served
+    let mut request = StacksHttpRequest::new_getinfo(addr.into(), Some(123));
+    request.add_header(
+        "If-Modified-Since".to_string(),
+        http_date(get_epoch_time_secs() + 3600),
+    );
+    requests.push(request);
+
+    // client's copy is from long before
+    let mut request = StacksHttpRequest::new_getinfo(addr.into(), Some(123));
+    request.add_header("If-Modified-Since".to_string(), http_date(0));
+    requests.push(request);
+
+    // If-None-Match wins over If-Modified-Since
+    let mut request = StacksHttpRequest::new_getinfo(addr.into(), Some(123));
+    request.add_header("If-None-Match".to_string(), old_peer_info.etag());
+    request.add_header(
+        "If-Modified-Since".to_string(),
+        http_date(get_epoch_time_secs() + 3600),
+    );
+    requests.push(request);
+
+    let mut responses = rpc_test.run(requests);
+
+    let response = responses.remove(0);
+    assert_eq!(response.preamble().status_code, 200);
+    assert_eq!(
+        response.preamble().get_header("ETag".to_string()),
+        Some(peer_info.etag())
+    );
+
+    let response = responses.remove(0);
+    assert_eq!(response.preamble().status_code, 304);
+    assert_eq!(
+        response.preamble().get_header("ETag".to_string()),
+        Some(peer_info.etag())
+    );
+
+    let response = responses.remove(0);
+    assert_eq!(response.preamble().status_code, 200);
+    assert_eq!(
+        response.preamble().get_header("ETag".to_string()),
+        Some(peer_info.etag())
+    );
+    assert_eq!(
+        response.decode_peer_info().unwrap().view_fingerprint(),
+        peer_info.view_fingerprint()
+    );
+
+    let response = responses.remove(0);
+    assert_eq!(response.preamble().status_code, 304);
+    let last_modified = response
+        .preamble()
+        .get_header("Last-Modified".to_string())
+        .unwrap();
+    assert!(parse_http_date(&last_modified).unwrap() <= get_epoch_time_secs());
+
+    let response = responses.remove(0);
+    assert_eq!(response.preamble().status_code, 200);
+    assert_eq!(
+        response.preamble().get_header("Last-Modified".to_string()),
+        Some(last_modified)
+    );
+
+    let response = responses.remove(0);
+    assert_eq!(response.preamble().status_code, 200);
+}
+
+#[test]
+fn test_view_last_modified() {
+    let view_1 = Sha256Sum([0x01; 32]);
+    let view_2 = Sha256Sum([0x02; 32]);
+    let mut last_modified = None;
+
+    // first view is modified when it is first served, and stays that way
+    assert_eq!(view_last_modified(&mut last_modified, view_1, 100), 100);
+    assert_eq!(view_last_modified(&mut last_modified, view_1, 200), 100);
+
+    // a new view is modified when it is first served...
+    assert_eq!(view_last_modified(&mut last_modified, view_2, 300), 300);
+    // ...but always after the last one, even within the same second
+    assert_eq!(view_last_modified(&mut last_modified, view_1, 300), 301);
+    assert_eq!(last_modified, Some((view_1, 301)));
+}
+
+#[test]
+fn test_http_date() {
+    assert_eq!(http_date(784111777), "Sun, 06 Nov 1994 08:49:37 GMT");
+    assert_eq!(
+        parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+        Some(784111777)
+    );
+    assert_eq!(parse_http_date(&http_date(0)), Some(0));
+    assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), None);
+    assert_eq!(parse_http_date("yesterday"), None);
+}
+
+#[test]
+fn test_if_none_match() {
+    let etag = "\"abcd\"";
+    assert!(if_none_match("\"abcd\"", etag));
+    assert!(if_none_match("W/\"abcd\"", etag));
+    assert!(if_none_match("\"0123\", \"abcd\"", etag));
+    assert!(if_none_match("*", etag));
+    assert!(!if_none_match("\"0123\"", etag));
+    assert!(!if_none_match("abcd", etag));
+    assert!(!if_none_match("", etag));
+}
@@ -0,0 +1,111 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use super::TestRPC;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{StacksHttp, StacksHttpRequest};
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut conn_opts = ConnectionOptions::default();
+    conn_opts.enable_metrics_endpoint = true;
+    let mut http = StacksHttp::new(addr.clone(), &conn_opts);
+
+    let request = StacksHttpRequest::new_getmetrics(addr.into(), Some("secret"));
+    let bytes = request.try_serialize().unwrap();
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getmetrics::RPCMetricsRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+    assert_eq!(preamble.path_and_query_str, "/metrics");
+
+    // not served unless enabled
+    let http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+    assert!(!http.enable_metrics_endpoint);
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    // the serving peer exposes its metrics to holders of its token (and the client needs to
+    // know how to decode the response, too)
+    let mut rpc_test = TestRPC::setup_with_conn_opts(function_name!(), |conn_opts| {
+        conn_opts.enable_metrics_endpoint = true;
+        conn_opts.auth_token = Some("secret".to_string());
+    });
+    rpc_test.peer_2.network.http_metrics.num_connections = 3;
+    rpc_test.peer_2.network.http_metrics.bytes_sent = 1234;
+
+    let requests = vec![
+        StacksHttpRequest::new_getmetrics(addr.into(), Some("secret")),
+        StacksHttpRequest::new_getmetrics(addr.into(), None),
+    ];
+    let mut responses = rpc_test.run(requests);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let metrics = response.decode_metrics().unwrap();
+
+    // every sample is a `name value` pair, preceded by its HELP and TYPE
+    let mut declared = vec![];
+    let mut samples = vec![];
+    for line in metrics.lines() {
+        if let Some(comment) = line.strip_prefix("# ") {
+            let mut parts = comment.splitn(3, ' ');
+            let kind = parts.next().unwrap();
+            let name = parts.next().unwrap();
+            assert!(kind == "HELP" || kind == "TYPE");
+            if kind == "TYPE" {
+                assert_eq!(parts.next(), Some("gauge"));
+                declared.push(name.to_string());
+            }
+            continue;
+        }
+        let (name, value) = line.split_once(' ').unwrap();
+        value.parse::<u64>().unwrap();
+        assert_eq!(declared.last().map(|s| s.as_str()), Some(name));
+        samples.push((name.to_string(), value.to_string()));
+    }
+    assert_eq!(declared.len(), samples.len());
+    assert!(samples.contains(&("stacks_node_http_connections".to_string(), "3".to_string())));
+    assert!(samples.contains(&(
+        "stacks_node_http_bytes_sent".to_string(),
+        "1234".to_string()
+    )));
+
+    // no token, no metrics
+    let response = responses.remove(0);
+    let (preamble, _body) = response.destruct();
+    assert_eq!(preamble.status_code, 401);
+}
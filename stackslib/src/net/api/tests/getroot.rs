@@ -0,0 +1,80 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use super::TestRPC;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::http::HttpResponsePayload;
+use crate::net::httpcore::{StacksHttp, StacksHttpRequest};
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request = StacksHttpRequest::new_getroot(addr.into());
+    let bytes = request.try_serialize().unwrap();
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getroot::RPCGetRootRequestHandler::new(None);
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+    assert_eq!(preamble.verb, "GET");
+    assert_eq!(preamble.path_and_query_str, "/");
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    // by default, the root is a 404 that points at the API
+    let rpc_test = TestRPC::setup(function_name!());
+    let mut responses = rpc_test.run(vec![StacksHttpRequest::new_getroot(addr.into())]);
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let (preamble, body) = response.destruct();
+    assert_eq!(preamble.status_code, 404);
+    let HttpResponsePayload::Text(text) = body else {
+        panic!("Expected a text body, got {:?}", &body);
+    };
+    assert_eq!(text, getroot::DEFAULT_ROOT_NOT_FOUND_TEXT);
+
+    // the operator can serve their own greeting instead
+    let rpc_test =
+        TestRPC::setup_with_conn_opts(&format!("{}-configured", function_name!()), |conn_opts| {
+            conn_opts.root_response = Some("Hello from a Stacks node!".to_string());
+        });
+    let mut responses = rpc_test.run(vec![StacksHttpRequest::new_getroot(addr.into())]);
+    let response = responses.remove(0);
+    assert_eq!(
+        response.decode_root_response().unwrap(),
+        "Hello from a Stacks node!"
+    );
+}
@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::mem;
 use std::net::SocketAddr;
 
 use clarity::vm::costs::ExecutionCost;
@@ -40,35 +41,44 @@ use crate::chainstate::stacks::{
     TransactionAuth, TransactionPayload, TransactionPostConditionMode, TransactionVersion,
 };
 use crate::core::MemPoolDB;
+use crate::net::connection::ConnectionOptions;
 use crate::net::db::PeerDB;
 use crate::net::httpcore::{StacksHttpRequest, StacksHttpResponse};
 use crate::net::relay::Relayer;
 use crate::net::rpc::ConversationHttp;
+use crate::net::server::IdempotencyCache;
 use crate::net::test::{TestEventObserver, TestPeer, TestPeerConfig};
 use crate::net::tests::inv::nakamoto::make_nakamoto_peers_from_invs;
 use crate::net::{
-    Attachment, AttachmentInstance, RPCHandlerArgs, StackerDBConfig, StacksNodeState, UrlString,
+    Attachment, AttachmentInstance, RPCHandlerArgs, StackerDBConfig, StacksMessageType,
+    StacksNodeState, UrlString,
 };
 
 mod callreadonly;
+mod corspreflight;
 mod getaccount;
 mod getattachment;
 mod getattachmentsinv;
 mod getblock;
 mod getblock_v3;
+mod getblockheader;
+mod getblocks_subscribe;
 mod getconstantval;
 mod getcontractabi;
 mod getcontractsrc;
 mod getdatavar;
 mod getheaders;
+mod gethealth;
 mod getinfo;
 mod getistraitimplemented;
 mod getmapentry;
+mod getmetrics;
 mod getmicroblocks_confirmed;
 mod getmicroblocks_indexed;
 mod getmicroblocks_unconfirmed;
 mod getneighbors;
 mod getpoxinfo;
+mod getroot;
 mod getstackerdbchunk;
 mod getstackerdbmetadata;
 mod getstxtransfercost;
@@ -213,6 +223,40 @@ impl<'a> TestRPC<'a> {
         Self::setup_ex(test_name, true)
     }
 
+    /// Set up the peers, and have both conversations use connection options modified by
+    /// `set_conn_opts`.  The serving conversation (`convo_2`) gets the serving peer's options,
+    /// and the client conversation (`convo_1`) gets the client peer's options.
+    pub fn setup_with_conn_opts<F>(test_name: &str, set_conn_opts: F) -> TestRPC<'a>
+    where
+        F: Fn(&mut ConnectionOptions),
+    {
+        let mut rpc_test = Self::setup(test_name);
+
+        let mut conn_opts = rpc_test.peer_1.config.connection_opts.clone();
+        set_conn_opts(&mut conn_opts);
+        rpc_test.convo_1 = ConversationHttp::new(
+            rpc_test.convo_1.get_peer_addr().clone(),
+            rpc_test.convo_1.get_url().cloned(),
+            rpc_test.convo_1.get_peer_host(),
+            &conn_opts,
+            0,
+            32,
+        );
+
+        let mut conn_opts = rpc_test.peer_2.config.connection_opts.clone();
+        set_conn_opts(&mut conn_opts);
+        rpc_test.convo_2 = ConversationHttp::new(
+            rpc_test.convo_2.get_peer_addr().clone(),
+            rpc_test.convo_2.get_url().cloned(),
+            rpc_test.convo_2.get_peer_host(),
+            &conn_opts,
+            1,
+            32,
+        );
+
+        rpc_test
+    }
+
     pub fn setup_ex(test_name: &str, process_microblock: bool) -> TestRPC<'a> {
         // ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R
         let privk1 = StacksPrivateKey::from_hex(
@@ -910,6 +954,29 @@ impl<'a> TestRPC<'a> {
     /// Run zero or more HTTP requests on this setup RPC test harness.
     /// Return the list of responses.
     pub fn run(self, requests: Vec<StacksHttpRequest>) -> Vec<StacksHttpResponse> {
+        self.run_with_forwarded(requests)
+            .into_iter()
+            .map(|(resp, _)| resp)
+            .collect()
+    }
+
+    /// Run zero or more HTTP requests on this setup RPC test harness.
+    /// Return the list of responses, each paired with the messages that the serving peer would
+    /// forward to the peer network as a result of the request (tagged with correlation IDs).
+    pub fn run_with_forwarded(
+        self,
+        requests: Vec<StacksHttpRequest>,
+    ) -> Vec<(StacksHttpResponse, Vec<(u64, StacksMessageType)>)> {
+        self.run_with_rpc_args(requests, &RPCHandlerArgs::default())
+    }
+
+    /// Like `run_with_forwarded()`, but the serving peer handles the requests with the given
+    /// handler arguments (e.g. to give it fee and cost estimators).
+    pub fn run_with_rpc_args(
+        self,
+        requests: Vec<StacksHttpRequest>,
+        peer_2_rpc_args: &RPCHandlerArgs,
+    ) -> Vec<(StacksHttpResponse, Vec<(u64, StacksMessageType)>)> {
         let mut peer_1 = self.peer_1;
         let mut peer_2 = self.peer_2;
         let peer_1_indexer = self.peer_1_indexer;
@@ -918,6 +985,12 @@ impl<'a> TestRPC<'a> {
         let mut convo_2 = self.convo_2;
         let unconfirmed_state = self.unconfirmed_state;
 
+        // peer 2 serves the requests, so it keeps the idempotency cache (as its HttpPeer would)
+        let mut idempotency_cache = IdempotencyCache::new(
+            peer_2.network.connection_opts.idempotency_key_ttl_secs,
+            peer_2.network.connection_opts.max_idempotency_keys,
+        );
+
         let mut responses = vec![];
         for request in requests.into_iter() {
             peer_1.refresh_burnchain_view();
@@ -984,17 +1057,19 @@ impl<'a> TestRPC<'a> {
                 .unwrap();
             }
 
-            {
-                let rpc_args = RPCHandlerArgs::default();
+            let forwarded = {
                 let mut node_state = StacksNodeState::new(
                     &mut peer_2.network,
                     &peer_2_sortdb,
                     &mut peer_2_stacks_node.chainstate,
                     &mut peer_2_mempool,
-                    &rpc_args,
+                    peer_2_rpc_args,
                 );
-                convo_2.chat(&mut node_state).unwrap();
-            }
+                node_state.set_idempotency_cache(mem::take(&mut idempotency_cache));
+                let forwarded = convo_2.chat(&mut node_state).unwrap();
+                idempotency_cache = node_state.take_idempotency_cache().unwrap_or_default();
+                forwarded
+            };
 
             peer_2.sortdb = Some(peer_2_sortdb);
             peer_2.stacks_node = Some(peer_2_stacks_node);
@@ -1053,7 +1128,7 @@ impl<'a> TestRPC<'a> {
             assert!(resp_opt.is_some());
 
             let resp = resp_opt.unwrap();
-            responses.push(resp);
+            responses.push((resp, forwarded));
         }
 
         return responses;
@@ -16,6 +16,7 @@
 
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
+use clarity::vm::costs::ExecutionCost;
 use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier, StacksAddressExtensions};
 use clarity::vm::{ClarityName, ContractName, Value};
 use stacks_common::types::chainstate::StacksAddress;
@@ -23,15 +24,19 @@ use stacks_common::types::net::PeerHost;
 use stacks_common::types::Address;
 use stacks_common::util::hash::to_hex;
 
-use super::test_rpc;
+use super::{test_rpc, TestRPC};
+use crate::chainstate::stacks::db::blocks::MINIMUM_TX_FEE_RATE_PER_BYTE;
+use crate::chainstate::stacks::db::StacksEpochReceipt;
 use crate::chainstate::stacks::TransactionPayload;
 use crate::core::BLOCK_LIMIT_MAINNET_21;
+use crate::cost_estimates::metrics::UnitMetric;
+use crate::cost_estimates::{EstimatorError, FeeEstimator, FeeRateEstimate, UnitEstimator};
 use crate::net::api::*;
 use crate::net::connection::ConnectionOptions;
 use crate::net::httpcore::{
     HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp, StacksHttpRequest,
 };
-use crate::net::{ProtocolFamily, TipRequest};
+use crate::net::{ProtocolFamily, RPCHandlerArgs, TipRequest};
 
 #[test]
 fn test_try_parse_request() {
@@ -110,3 +115,76 @@ fn test_try_make_response() {
     let (preamble, body) = response.destruct();
     assert_eq!(preamble.status_code, 400);
 }
+
+/// Fee estimator with fixed fee rates
+struct FixedFeeRates;
+
+impl FeeEstimator for FixedFeeRates {
+    fn notify_block(
+        &mut self,
+        _receipt: &StacksEpochReceipt,
+        _block_limit: &ExecutionCost,
+    ) -> Result<(), EstimatorError> {
+        Ok(())
+    }
+
+    fn get_rate_estimates(&self) -> Result<FeeRateEstimate, EstimatorError> {
+        Ok(FeeRateEstimate {
+            high: 300.0,
+            middle: 200.0,
+            low: 100.0,
+        })
+    }
+}
+
+#[test]
+fn test_try_make_response_with_estimators() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let sender_addr =
+        StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap();
+    let tx_payload =
+        TransactionPayload::new_contract_call(sender_addr, "hello-world", "add-unit", vec![])
+            .unwrap();
+
+    let request = StacksHttpRequest::new_post_fee_rate(
+        addr.into(),
+        postfeerate::FeeRateEstimateRequestBody {
+            estimated_len: Some(123),
+            transaction_payload: to_hex(&tx_payload.serialize_to_vec()),
+        },
+    );
+
+    let cost_estimator = UnitEstimator;
+    let fee_estimator = FixedFeeRates;
+    let cost_metric = UnitMetric;
+    let rpc_args = RPCHandlerArgs {
+        cost_estimator: Some(&cost_estimator),
+        fee_estimator: Some(&fee_estimator),
+        cost_metric: Some(&cost_metric),
+        ..RPCHandlerArgs::default()
+    };
+
+    let rpc_test = TestRPC::setup(function_name!());
+    let (response, _) = rpc_test
+        .run_with_rpc_args(vec![request], &rpc_args)
+        .remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let fee_estimate = response.decode_fee_estimate().unwrap();
+    assert_eq!(fee_estimate.estimated_cost_scalar, 1);
+
+    // low, middle, and high, each at least the minimum fee for the transaction's length
+    let fee_rates: Vec<_> = fee_estimate
+        .estimations
+        .iter()
+        .map(|estimate| estimate.fee_rate)
+        .collect();
+    assert_eq!(fee_rates, vec![100.0, 200.0, 300.0]);
+    for estimate in fee_estimate.estimations.iter() {
+        assert!(estimate.fee >= 123 * MINIMUM_TX_FEE_RATE_PER_BYTE);
+    }
+}
@@ -14,7 +14,8 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::io::Write;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream};
 
 use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier, StacksAddressExtensions};
 use clarity::vm::{ClarityName, ContractName, Value};
@@ -22,6 +23,7 @@ use stacks_common::address::{AddressHashMode, C32_ADDRESS_VERSION_TESTNET_SINGLE
 use stacks_common::types::chainstate::{StacksAddress, StacksPrivateKey, StacksPublicKey};
 use stacks_common::types::net::PeerHost;
 use stacks_common::types::Address;
+use stacks_common::util::sleep_ms;
 
 use super::TestRPC;
 use crate::chainstate::stacks::{
@@ -32,9 +34,13 @@ use crate::core::BLOCK_LIMIT_MAINNET_21;
 use crate::net::api::*;
 use crate::net::connection::ConnectionOptions;
 use crate::net::httpcore::{
-    HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp, StacksHttpRequest,
+    HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp,
+    StacksHttpRequest, IDEMPOTENCY_KEY, STACKS_CORRELATION_ID,
+};
+use crate::net::server::ForwardedMessage;
+use crate::net::{
+    Attachment, ProtocolFamily, RPCHandlerArgs, StacksMessageType, StacksNodeState, TipRequest,
 };
-use crate::net::{Attachment, ProtocolFamily, TipRequest};
 
 #[test]
 fn test_try_parse_request() {
@@ -239,3 +245,175 @@ fn test_try_make_response() {
     let (preamble, body) = response.destruct();
     assert_eq!(preamble.status_code, 400);
 }
+
+#[test]
+fn test_correlation_id_on_forwarded_tx() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let rpc_test = TestRPC::setup(function_name!());
+    let sendable_txs = rpc_test.sendable_txs.clone();
+
+    let mut requests = vec![];
+
+    // no correlation ID given, so the node assigns one
+    let request = StacksHttpRequest::new_post_transaction_with_attachment(
+        addr.into(),
+        sendable_txs[0].clone(),
+        None,
+    );
+    requests.push(request);
+
+    // client-supplied correlation ID is carried through
+    let mut request = StacksHttpRequest::new_post_transaction_with_attachment(
+        addr.into(),
+        sendable_txs[1].clone(),
+        None,
+    );
+    request.add_header(STACKS_CORRELATION_ID.to_string(), "12345".to_string());
+    requests.push(request);
+
+    let mut results = rpc_test.run_with_forwarded(requests);
+
+    let (response, forwarded) = results.remove(0);
+    let correlation_id = response.preamble().get_correlation_id().unwrap();
+    assert_eq!(forwarded.len(), 1);
+    assert_eq!(forwarded[0].0, correlation_id);
+    match &forwarded[0].1 {
+        StacksMessageType::Transaction(tx) => assert_eq!(tx.txid(), sendable_txs[0].txid()),
+        msg => panic!("Unexpected forwarded message {:?}", msg),
+    }
+
+    let (response, forwarded) = results.remove(0);
+    assert_eq!(response.preamble().get_correlation_id(), Some(12345));
+    assert_eq!(forwarded.len(), 1);
+    assert_eq!(forwarded[0].0, 12345);
+    match &forwarded[0].1 {
+        StacksMessageType::Transaction(tx) => assert_eq!(tx.txid(), sendable_txs[1].txid()),
+        msg => panic!("Unexpected forwarded message {:?}", msg),
+    }
+}
+
+#[test]
+fn test_idempotency_key() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let mut rpc_test = TestRPC::setup(function_name!());
+    let sendable_txs = rpc_test.sendable_txs.clone();
+
+    // keys are off by default
+    rpc_test
+        .peer_2
+        .network
+        .connection_opts
+        .idempotency_key_ttl_secs = 300;
+
+    let mut requests = vec![];
+
+    // first post with a key (should succeed and be forwarded)
+    let mut request = StacksHttpRequest::new_post_transaction_with_attachment(
+        addr.into(),
+        sendable_txs[0].clone(),
+        None,
+    );
+    request.add_header(IDEMPOTENCY_KEY.to_string(), "wallet-retry-1".to_string());
+    request.add_header(STACKS_CORRELATION_ID.to_string(), "111".to_string());
+    requests.push(request.clone());
+
+    // identical retry (should get the cached response, and not be forwarded again)
+    requests.push(request);
+
+    // same key, different transaction (should fail)
+    let mut request = StacksHttpRequest::new_post_transaction_with_attachment(
+        addr.into(),
+        sendable_txs[1].clone(),
+        None,
+    );
+    request.add_header(IDEMPOTENCY_KEY.to_string(), "wallet-retry-1".to_string());
+    requests.push(request);
+
+    let mut results = rpc_test.run_with_forwarded(requests);
+
+    let (first_response, forwarded) = results.remove(0);
+    assert_eq!(forwarded.len(), 1);
+    let first_bytes = first_response.try_serialize().unwrap();
+    assert_eq!(
+        first_response.decode_txid().unwrap(),
+        sendable_txs[0].txid()
+    );
+
+    let (retry_response, forwarded) = results.remove(0);
+    assert!(forwarded.is_empty());
+    assert_eq!(retry_response.try_serialize().unwrap(), first_bytes);
+
+    let (response, forwarded) = results.remove(0);
+    assert!(forwarded.is_empty());
+    let (preamble, _body) = response.destruct();
+    assert_eq!(preamble.status_code, 400);
+}
+
+#[test]
+fn test_forwarded_message_source_event() {
+    let rpc_test = TestRPC::setup(function_name!());
+    let tx = rpc_test.sendable_txs[0].clone();
+    let mut peer = rpc_test.peer_2;
+    peer.refresh_burnchain_view();
+    let http_addr: SocketAddr = format!("127.0.0.1:{}", peer.config.http_port)
+        .parse()
+        .unwrap();
+
+    let mut request = StacksHttpRequest::new_post_transaction_with_attachment(
+        PeerHost::from_socketaddr(&http_addr),
+        tx.clone(),
+        None,
+    );
+    request.add_header(STACKS_CORRELATION_ID.to_string(), "777".to_string());
+    let mut client = TcpStream::connect(&http_addr).unwrap();
+    client.write_all(&request.try_serialize().unwrap()).unwrap();
+
+    let mut network_state = peer.network.network.take().unwrap();
+    let mut http = peer.network.http.take().unwrap();
+    let sortdb = peer.sortdb.take().unwrap();
+    let mut stacks_node = peer.stacks_node.take().unwrap();
+    let mut mempool = peer.mempool.take().unwrap();
+    let rpc_args = RPCHandlerArgs::default();
+    let mut forwarded: Vec<ForwardedMessage> = vec![];
+    for _ in 0..50 {
+        let mut poll_states = network_state.poll(100).unwrap();
+        let poll_state = poll_states.remove(&http.http_server_handle).unwrap();
+        let mut node_state = StacksNodeState::new(
+            &mut peer.network,
+            &sortdb,
+            &mut stacks_node.chainstate,
+            &mut mempool,
+            &rpc_args,
+        );
+        forwarded.extend(http.run(&mut network_state, &mut node_state, poll_state, None));
+        if !forwarded.is_empty() {
+            break;
+        }
+        sleep_ms(100);
+    }
+
+    // the forwarded transaction is tagged with the conversation and request it came from
+    assert_eq!(http.peers.len(), 1);
+    let source_event = *http.peers.keys().next().unwrap();
+    assert_eq!(
+        forwarded,
+        vec![ForwardedMessage {
+            msg: StacksMessageType::Transaction(tx.clone()),
+            source_event,
+            correlation_id: Some(777),
+            priority: 0,
+        }]
+    );
+    assert_eq!(
+        ForwardedMessage::into_msgs(forwarded),
+        vec![StacksMessageType::Transaction(tx)]
+    );
+
+    peer.network.network = Some(network_state);
+    peer.network.http = Some(http);
+    peer.sortdb = Some(sortdb);
+    peer.stacks_node = Some(stacks_node);
+    peer.mempool = Some(mempool);
+}
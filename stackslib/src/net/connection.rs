@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::io::{Read, Write};
 use std::ops::{Deref, DerefMut};
 use std::sync::mpsc::{
@@ -39,16 +39,21 @@ use crate::core::mempool::MAX_BLOOM_COUNTER_TXS;
 use crate::monitoring::{update_inbound_bandwidth, update_outbound_bandwidth};
 use crate::net::codec::*;
 use crate::net::download::BLOCK_DOWNLOAD_INTERVAL;
+use crate::net::http::HttpResponsePreamble;
 use crate::net::inv::{INV_REWARD_CYCLES, INV_SYNC_INTERVAL};
 use crate::net::neighbors::{
     MAX_NEIGHBOR_AGE, NEIGHBOR_REQUEST_TIMEOUT, NEIGHBOR_WALK_INTERVAL, NUM_INITIAL_WALKS,
     WALK_MAX_DURATION, WALK_MIN_DURATION, WALK_RESET_INTERVAL, WALK_RESET_PROB, WALK_RETRY_COUNT,
     WALK_STATE_TIMEOUT,
 };
+use crate::net::tls::TlsConfig;
 use crate::net::{
     Error as net_error, MessageSequence, Preamble, ProtocolFamily, RelayData, StacksHttp, StacksP2P,
 };
 
+/// How many bytes of buffer space an idle connection keeps, once its buffers are shrunk
+pub const IDLE_BUFFER_CAPACITY: usize = 4096;
+
 /// Receiver notification handle.
 /// When a message with the expected `seq` value arrives, send it to an expected receiver (possibly
 /// in another thread) via the given `receiver_input` channel.
@@ -322,6 +327,45 @@ struct ConnectionOutbox<P: ProtocolFamily> {
     inflight: VecDeque<ReceiverNotify<P>>,
 }
 
+/// CORS headers the HTTP server sends, so browser-based clients can query the node directly
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorsConfig {
+    /// value of `Access-Control-Allow-Origin`
+    pub allow_origin: String,
+    /// value of `Access-Control-Allow-Methods`
+    pub allow_methods: String,
+    /// value of `Access-Control-Allow-Headers`
+    pub allow_headers: String,
+}
+
+impl std::default::Default for CorsConfig {
+    fn default() -> CorsConfig {
+        CorsConfig {
+            allow_origin: "*".to_string(),
+            allow_methods: "POST, GET, OPTIONS".to_string(),
+            allow_headers: "origin, content-type".to_string(),
+        }
+    }
+}
+
+impl CorsConfig {
+    /// Set the CORS headers on an outbound response
+    pub fn add_headers(&self, preamble: &mut HttpResponsePreamble) {
+        preamble.add_header(
+            "Access-Control-Allow-Origin".to_string(),
+            self.allow_origin.clone(),
+        );
+        preamble.add_header(
+            "Access-Control-Allow-Methods".to_string(),
+            self.allow_methods.clone(),
+        );
+        preamble.add_header(
+            "Access-Control-Allow-Headers".to_string(),
+            self.allow_headers.clone(),
+        );
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ConnectionOptions {
     pub inbox_maxlen: usize,
@@ -417,6 +461,123 @@ pub struct ConnectionOptions {
     pub force_nakamoto_epoch_transition: bool,
     /// The authorization token to enable the block proposal RPC endpoint
     pub block_proposal_token: Option<String>,
+    /// Hard cap (in seconds) on how long an outbound HTTP socket can stay in a connecting state,
+    /// regardless of readiness signals.  Complements `connect_timeout`.  0 means no cap.
+    pub max_connecting_lifetime: u64,
+    /// Maximum number of ready HTTP sockets to service in a single pass of the HTTP server.
+    /// Sockets beyond this are serviced on subsequent passes.  0 means no limit.
+    pub max_sockets_per_poll: u64,
+    /// Maximum number of outbound HTTP requests this node may have outstanding at once, across
+    /// all conversations.  0 means no limit.
+    pub max_outstanding_outbound_requests: u64,
+    /// Whether or not to log (at debug level) the raw bytes of HTTP requests that fail to parse.
+    /// Off by default, since the bytes may contain anything the client sent.
+    pub log_bad_requests: bool,
+    /// If set, the HTTP server speaks TLS to inbound clients, using this certificate and key.
+    pub tls: Option<TlsConfig>,
+    /// Bearer token that clients must present (as `Authorization: Bearer <token>`) to use the
+    /// endpoints in `privileged_endpoints`.  If unset, privileged endpoints are unreachable.
+    pub auth_token: Option<String>,
+    /// Endpoints which require `auth_token`, named by their metrics identifier (e.g. `/v2/info`).
+    /// All other endpoints remain public.
+    pub privileged_endpoints: HashSet<String>,
+    /// Subjects of client TLS certificates, as RFC 4514 strings (see
+    /// `tls::certificate_subject()`), which may use privileged endpoints without `auth_token`.
+    /// Only takes effect if `tls` verifies client certificates.
+    pub privileged_client_subjects: HashSet<String>,
+    /// Whether or not to serve the node's network counters in the Prometheus text format at
+    /// `/metrics`.  The endpoint is always privileged, so `auth_token` must be set to scrape it.
+    pub enable_metrics_endpoint: bool,
+    /// Maximum number of sockets the HTTP server processes in a single pass, counting accepted,
+    /// newly-connected, and ready sockets together.  The rest are deferred to the next pass.
+    /// 0 means no limit.
+    pub max_work_per_run: u64,
+    /// How long (in milliseconds) a finished, non-keep-alive HTTP conversation stays registered
+    /// after its reply has been flushed, so that slow clients can read the tail of the reply
+    /// before the socket is closed.  No new requests are read in this time.  0 means close
+    /// right away.
+    pub close_linger_ms: u64,
+    /// If set, the HTTP server answers CORS preflight (`OPTIONS`) requests with a 204, and sends
+    /// these CORS headers on every response.
+    pub cors: Option<CorsConfig>,
+    /// Maximum rate at which the HTTP server accepts new connections, across all clients.
+    /// Connections beyond this are closed as soon as they are accepted.  0 means no limit.
+    pub max_accepts_per_sec: u64,
+    /// Remote IP addresses whose connections the HTTP server closes as soon as they are
+    /// accepted.  More can be banned at runtime with `HttpPeer::ban_host()`.
+    pub http_banned_hosts: HashSet<net::IpAddr>,
+    /// How long (in seconds) the HTTP server remembers the response to a transaction posted with
+    /// an `Idempotency-Key` header, so that a retry with the same key gets the same response.
+    /// 0 disables idempotency keys altogether.
+    pub idempotency_key_ttl_secs: u64,
+    /// Maximum number of idempotency keys the HTTP server remembers at once.  When full, the
+    /// oldest key is forgotten first.
+    pub max_idempotency_keys: usize,
+    /// Maximum number of clients that can be subscribed to new blocks at once.  Subscriptions
+    /// beyond this get a 503.  0 means no limit.
+    pub max_subscriptions: u64,
+    /// How often (in seconds) the HTTP server writes a blank keep-alive line to each subscribed
+    /// client, if there's nothing else to send.  0 disables heartbeats.
+    pub subscription_heartbeat_secs: u64,
+    /// How long (in seconds) a subscribed client can go without the HTTP server being able to
+    /// send it anything before it is disconnected for not reading.  Only enforced when
+    /// heartbeats are on, and should be longer than the heartbeat interval.
+    pub subscription_write_timeout_secs: u64,
+    /// How long (in seconds) an HTTP conversation can go without sending or receiving anything
+    /// before its socket buffers are shrunk down to `IDLE_BUFFER_CAPACITY`.  They grow back on
+    /// the next request.  0 disables shrinking.
+    pub idle_buffer_shrink_secs: u64,
+    /// How long (in seconds) an HTTP socket can go without traffic before the OS starts sending
+    /// TCP keepalive probes on it, to detect peers that went away without closing the
+    /// connection.  0 leaves TCP keepalive settings alone.
+    pub tcp_keepalive_idle_secs: u64,
+    /// How long (in seconds) the OS waits between TCP keepalive probes (Linux only)
+    pub tcp_keepalive_interval_secs: u64,
+    /// How many unanswered TCP keepalive probes it takes for the OS to reset the connection
+    /// (Linux only)
+    pub tcp_keepalive_probes: u32,
+    /// How long (in seconds) a peer has to answer an HTTP request this node sent it, before the
+    /// conversation is closed.  0 means no limit.
+    pub outbound_request_timeout: u64,
+    /// Whether or not to answer requests with a 503 while the node is in initial block download
+    /// (as reported via `HttpPeer::set_ibd_progress()`).  Endpoints in `IBD_EXEMPT_ENDPOINTS`
+    /// are always served.
+    pub reject_requests_during_ibd: bool,
+    /// Maximum number of distinct hosts (and host prefixes) the HTTP server keeps per-host state
+    /// for, such as bans.  Once full, the least-recently-seen host's state is forgotten.
+    pub max_tracked_hosts: u64,
+    /// How far back (in seconds) the HTTP server looks at outbound connection failures to a
+    /// data URL, when deciding whether or not it's flapping
+    pub flapping_window_secs: u64,
+    /// How many outbound connection failures to a data URL within `flapping_window_secs` make
+    /// it flapping
+    pub flapping_min_failures: u32,
+    /// Maximum rate at which the HTTP server handles requests from a single remote IP address,
+    /// across all of its connections.  Requests beyond this get a 429.  0 means no limit.
+    pub max_requests_per_second_per_host: u64,
+    /// Whether or not an outbound GET that is identical to one still in flight to the same host
+    /// waits on that request's response, instead of being sent again
+    pub coalesce_outbound_requests: bool,
+    /// The largest request body the HTTP server will take.  A request that declares a bigger one
+    /// gets a 413 before any of its body is read, and the connection is closed.  If not set,
+    /// there is no limit beyond `MAX_MESSAGE_LEN`.
+    pub max_request_body_len: Option<u64>,
+    /// If set, the text the HTTP server answers `GET /` with (as a 200).  Otherwise, `GET /` gets
+    /// a 404 pointing at the API.
+    pub root_response: Option<String>,
+    /// Whether to believe the `X-Forwarded-For` header of requests that arrive from one of
+    /// `trusted_proxies`.  If so, the client it names stands in for the proxy wherever the HTTP
+    /// server limits or counts requests per host.
+    pub trust_forwarded_for: bool,
+    /// Reverse proxies whose `X-Forwarded-For` headers are believed, as (prefix, mask) pairs.  As
+    /// with `HostMatcher::IpPrefix`, the mask counts leading bits of the 16-byte `PeerAddress`.
+    pub trusted_proxies: Vec<(PeerAddress, u32)>,
+    /// For how long (in seconds) after the HTTP server starts to admit more inbound clients than
+    /// `max_http_clients`, so the clients of a restarted node can all reconnect at once.  0 to
+    /// always enforce `max_http_clients`.
+    pub startup_grace_secs: u64,
+    /// By how much `max_http_clients` is multiplied during `startup_grace_secs`
+    pub startup_grace_multiplier: u64,
 }
 
 impl std::default::Default for ConnectionOptions {
@@ -511,6 +672,42 @@ impl std::default::Default for ConnectionOptions {
             force_disconnect_interval: None,
             force_nakamoto_epoch_transition: false,
             block_proposal_token: None,
+            max_connecting_lifetime: 0, // only `connect_timeout` applies
+            max_sockets_per_poll: 0,    // service all ready HTTP sockets in each pass
+            max_outstanding_outbound_requests: 0, // don't bound the node's own HTTP fan-out
+            log_bad_requests: false,
+            tls: None, // serve plain HTTP
+            auth_token: None,
+            privileged_endpoints: HashSet::new(), // all endpoints are public
+            privileged_client_subjects: HashSet::new(), // no client certificate is privileged
+            enable_metrics_endpoint: false,
+            max_work_per_run: 0, // only the per-phase limits apply
+            close_linger_ms: 0,
+            cors: None,             // no preflight support
+            max_accepts_per_sec: 0, // only the per-host limits apply
+            http_banned_hosts: HashSet::new(),
+            idempotency_key_ttl_secs: 0, // no idempotency keys
+            max_idempotency_keys: 4096,
+            max_subscriptions: 64,
+            subscription_heartbeat_secs: 30,
+            subscription_write_timeout_secs: 90, // a few missed heartbeats
+            idle_buffer_shrink_secs: 0,
+            tcp_keepalive_idle_secs: 0,
+            tcp_keepalive_interval_secs: 75, // Linux default
+            tcp_keepalive_probes: 9,         // Linux default
+            outbound_request_timeout: 0,
+            reject_requests_during_ibd: false,
+            max_tracked_hosts: 65536,
+            flapping_window_secs: 600,
+            flapping_min_failures: 3,
+            max_requests_per_second_per_host: 0, // only the per-host connection limit applies
+            coalesce_outbound_requests: false,
+            max_request_body_len: None, // only MAX_MESSAGE_LEN applies
+            root_response: None,
+            trust_forwarded_for: false,
+            trusted_proxies: vec![],
+            startup_grace_secs: 0, // no grace period
+            startup_grace_multiplier: 2,
         }
     }
 }
@@ -523,6 +720,19 @@ pub struct NetworkConnection<P: ProtocolFamily> {
     outbox: ConnectionOutbox<P>,
 }
 
+/// Run a socket read or write, retrying it if it was interrupted by a signal before it could
+/// transfer any data (EINTR).  This is not an error; the call just needs to be made again.
+fn retry_interrupted<F: FnMut() -> io::Result<usize>>(mut io_op: F) -> io::Result<usize> {
+    loop {
+        match io_op() {
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {
+                test_debug!("Socket I/O was interrupted; retrying");
+            }
+            res => return res,
+        }
+    }
+}
+
 impl<P: ProtocolFamily> ConnectionInbox<P> {
     pub fn new(
         max_messages: usize,
@@ -939,7 +1149,7 @@ impl<P: ProtocolFamily> ConnectionInbox<P> {
             // to be copied if a message boundary isn't aligned with buf (which is usually the
             // case).
             let mut buf = [0u8; 4096];
-            let num_read = match fd.read(&mut buf) {
+            let num_read = match retry_interrupted(|| fd.read(&mut buf)) {
                 Ok(0) => {
                     // remote fd is closed, but do try to consume all remaining bytes in the buffer
                     socket_closed = true;
@@ -996,6 +1206,21 @@ impl<P: ProtocolFamily> ConnectionInbox<P> {
     pub fn num_messages(&self) -> usize {
         self.inbox.len()
     }
+
+    /// How many bytes of not-yet-parsed data are we holding?
+    pub fn buffered_bytes(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Release buffer space beyond `capacity` bytes (or beyond the unparsed data, if more)
+    fn shrink_buffer(&mut self, capacity: usize) {
+        self.buf.shrink_to(capacity);
+    }
+
+    /// How much buffer space is allocated?
+    fn buffer_capacity(&self) -> usize {
+        self.buf.capacity()
+    }
 }
 
 impl<P: ProtocolFamily> ConnectionOutbox<P> {
@@ -1142,7 +1367,8 @@ impl<P: ProtocolFamily> ConnectionOutbox<P> {
             if self.socket_out_ptr < self.socket_out_buf.len() {
                 // have pending bytes.
                 // send as many bytes as we can
-                let num_written_res = fd.write(&self.socket_out_buf[self.socket_out_ptr..]);
+                let num_written_res =
+                    retry_interrupted(|| fd.write(&self.socket_out_buf[self.socket_out_ptr..]));
                 let num_written = match num_written_res {
                     Ok(0) => {
                         // indicates that the remote peer is no longer receiving
@@ -1210,6 +1436,24 @@ impl<P: ProtocolFamily> ConnectionOutbox<P> {
     pub fn num_messages(&self) -> usize {
         self.outbox.len()
     }
+
+    /// How many bytes of not-yet-sent data are we holding?
+    /// Data still in the queued messages' pipes is not counted.
+    pub fn buffered_bytes(&self) -> usize {
+        self.socket_out_buf
+            .len()
+            .saturating_sub(self.socket_out_ptr)
+    }
+
+    /// Release buffer space beyond `capacity` bytes (or beyond the unsent data, if more)
+    fn shrink_buffer(&mut self, capacity: usize) {
+        self.socket_out_buf.shrink_to(capacity);
+    }
+
+    /// How much buffer space is allocated?
+    fn buffer_capacity(&self) -> usize {
+        self.socket_out_buf.capacity()
+    }
 }
 
 impl<P: ProtocolFamily + Clone> NetworkConnection<P> {
@@ -1382,6 +1626,22 @@ impl<P: ProtocolFamily + Clone> NetworkConnection<P> {
         self.outbox.num_messages()
     }
 
+    /// how many bytes are buffered in the inbox and outbox?
+    pub fn buffered_bytes(&self) -> usize {
+        self.inbox.buffered_bytes() + self.outbox.buffered_bytes()
+    }
+
+    /// shrink the inbox and outbox buffers to `IDLE_BUFFER_CAPACITY`, without dropping any data
+    pub fn shrink_buffers(&mut self) {
+        self.inbox.shrink_buffer(IDLE_BUFFER_CAPACITY);
+        self.outbox.shrink_buffer(IDLE_BUFFER_CAPACITY);
+    }
+
+    /// how many bytes are allocated for the inbox and outbox buffers?
+    pub fn buffer_capacity(&self) -> usize {
+        self.inbox.buffer_capacity() + self.outbox.buffer_capacity()
+    }
+
     /// get the next inbox message
     pub fn next_inbox_message(&mut self) -> Option<P::Message> {
         self.inbox.next_message()
@@ -2166,6 +2426,89 @@ mod test {
         })
     }
 
+    /// A socket that fails its first reads and writes with EINTR
+    struct InterruptedSocket<'a> {
+        inner: NetCursor<&'a mut [u8]>,
+        interrupts: usize,
+    }
+
+    impl<'a> InterruptedSocket<'a> {
+        fn interrupt(&mut self) -> io::Result<()> {
+            if self.interrupts > 0 {
+                self.interrupts -= 1;
+                return Err(io::Error::from(io::ErrorKind::Interrupted));
+            }
+            Ok(())
+        }
+    }
+
+    impl<'a> Read for InterruptedSocket<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.interrupt()?;
+            self.inner.read(buf)
+        }
+    }
+
+    impl<'a> Write for InterruptedSocket<'a> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.interrupt()?;
+            self.inner.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.inner.flush()
+        }
+    }
+
+    #[test]
+    fn connection_send_recv_interrupted() {
+        let privkey = Secp256k1PrivateKey::new();
+        let pubkey = Secp256k1PublicKey::from_private(&privkey);
+        let conn_opts = ConnectionOptions::default();
+        let mut conn = ConnectionP2P::new(StacksP2P::new(), &conn_opts, Some(pubkey));
+
+        let mut ping = StacksMessage::new(
+            0x12345678,
+            0x9abcdef0,
+            12345,
+            &BurnchainHeaderHash([0x11; 32]),
+            12339,
+            &BurnchainHeaderHash([0x22; 32]),
+            StacksMessageType::Ping(PingData { nonce: 0x01020304 }),
+        );
+        ping.sign(1, &privkey).unwrap();
+        let ping_bytes = ping.serialize_to_vec();
+
+        let mut handle = conn.make_relay_handle(0).unwrap();
+        ping.consensus_serialize(&mut handle).unwrap();
+        handle.flush().unwrap();
+
+        // interrupted writes don't fail the send; the message still goes out in full
+        let mut ping_buf = vec![0u8; ping_bytes.len()];
+        {
+            let mut ping_fd = InterruptedSocket {
+                inner: NetCursor::new(ping_buf.as_mut_slice()),
+                interrupts: 3,
+            };
+            let num_sent = conn.send_data(&mut ping_fd).unwrap();
+            assert_eq!(num_sent, ping_bytes.len());
+            assert_eq!(ping_fd.interrupts, 0);
+        }
+        assert_eq!(ping_buf, ping_bytes);
+
+        // interrupted reads don't fail the recv, or drop the connection
+        {
+            let mut ping_fd = InterruptedSocket {
+                inner: NetCursor::new(ping_buf.as_mut_slice()),
+                interrupts: 3,
+            };
+            let num_read = conn.recv_data(&mut ping_fd).unwrap();
+            assert_eq!(num_read, ping_bytes.len());
+            assert_eq!(ping_fd.interrupts, 0);
+        }
+        assert_eq!(conn.drain_inbox(), vec![ping]);
+    }
+
     #[test]
     fn connection_send_recv_timeout() {
         let privkey = Secp256k1PrivateKey::new();
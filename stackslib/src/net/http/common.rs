@@ -19,6 +19,8 @@ use std::net::SocketAddr;
 use std::str::FromStr;
 use std::{fmt, io};
 
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use libflate::gzip;
 use stacks_common::codec::{read_next, Error as CodecError, StacksMessageCodec, MAX_MESSAGE_LEN};
 use stacks_common::types::net::PeerHost;
 use stacks_common::util::chunked_encoding::*;
@@ -117,6 +119,30 @@ pub fn parse_json<T: serde::de::DeserializeOwned>(
     })
 }
 
+/// Helper function to undo a response's `Content-Encoding: gzip`, if it has one.  Decompresses
+/// at most `max_len` bytes.
+pub fn decode_gzip_body(
+    preamble: &HttpResponsePreamble,
+    body: &[u8],
+    max_len: u64,
+) -> Result<Option<Vec<u8>>, Error> {
+    let is_gzipped = preamble
+        .get_header("content-encoding".to_string())
+        .map_or(false, |encoding| {
+            encoding.trim().eq_ignore_ascii_case("gzip")
+        });
+    if !is_gzipped {
+        return Ok(None);
+    }
+    let mut decoder = gzip::Decoder::new(body).map_err(Error::ReadError)?;
+    let mut bound_fd = BoundReader::from_reader(&mut decoder, max_len);
+    let mut decoded = vec![];
+    bound_fd
+        .read_to_end(&mut decoded)
+        .map_err(Error::ReadError)?;
+    Ok(Some(decoded))
+}
+
 /// Helper function to read a raw bytestream
 pub fn parse_raw_bytes(
     preamble: &HttpResponsePreamble,
@@ -147,3 +173,31 @@ pub fn parse_bytes(
 ) -> Result<Vec<u8>, Error> {
     parse_raw_bytes(preamble, body, max_len, HttpContentType::Bytes)
 }
+
+/// The format of an RFC 7231 HTTP-date (IMF-fixdate), e.g. `Sun, 06 Nov 1994 08:49:37 GMT`
+const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// Render a unix timestamp (in seconds) as an HTTP-date, e.g. for a `Last-Modified` header
+pub fn http_date(secs: u64) -> String {
+    Utc.timestamp(i64::try_from(secs).unwrap_or(i64::MAX), 0)
+        .format(HTTP_DATE_FORMAT)
+        .to_string()
+}
+
+/// Parse an HTTP-date (e.g. from an `If-Modified-Since` header) into a unix timestamp (in
+/// seconds).  Returns None if it is malformed, which RFC 7232 says to treat as if the header were
+/// absent.
+pub fn parse_http_date(date: &str) -> Option<u64> {
+    let date = NaiveDateTime::parse_from_str(date.trim(), HTTP_DATE_FORMAT).ok()?;
+    u64::try_from(date.timestamp()).ok()
+}
+
+/// Does the value of an `If-None-Match` header match the given entity tag (quotes included)?
+/// Per RFC 7232, this is a weak comparison against each tag in the list, and `*` matches
+/// anything.
+pub fn if_none_match(header: &str, etag: &str) -> bool {
+    let etag = etag.strip_prefix("W/").unwrap_or(etag);
+    header.split(',').map(str::trim).any(|candidate| {
+        candidate == "*" || candidate.strip_prefix("W/").unwrap_or(candidate) == etag
+    })
+}
@@ -101,6 +101,7 @@ pub fn http_reason(code: u16) -> &'static str {
         415 => "Unsupported Media Type",
         416 => "Requested range not satisfiable",
         417 => "Expectation Failed",
+        429 => "Too Many Requests",
         500 => "Internal Server Error",
         501 => "Not Implemented",
         502 => "Bad Gateway",
@@ -111,6 +112,29 @@ pub fn http_reason(code: u16) -> &'static str {
     }
 }
 
+/// Decode an HTTP error status code into the machine-readable `error` field of a JSON error
+/// envelope
+pub fn http_error_name(code: u16) -> &'static str {
+    match code {
+        400 => "bad_request",
+        401 => "unauthorized",
+        402 => "payment_required",
+        403 => "forbidden",
+        404 => "not_found",
+        405 => "method_not_allowed",
+        408 => "request_timeout",
+        409 => "conflict",
+        413 => "payload_too_large",
+        429 => "too_many_requests",
+        500 => "internal_error",
+        501 => "not_implemented",
+        502 => "bad_gateway",
+        503 => "service_unavailable",
+        504 => "gateway_timeout",
+        _ => "error",
+    }
+}
+
 /// Make HTTP error responses distinct from HttpResponses
 pub trait HttpErrorResponse {
     fn code(&self) -> u16;
@@ -129,6 +153,8 @@ pub fn http_error_from_code_and_text(code: u16, message: String) -> Box<dyn Http
         402 => Box::new(HttpPaymentRequired::new(message)),
         403 => Box::new(HttpForbidden::new(message)),
         404 => Box::new(HttpNotFound::new(message)),
+        413 => Box::new(HttpPayloadTooLarge::new(message)),
+        429 => Box::new(HttpTooManyRequests::new(message)),
         500 => Box::new(HttpServerError::new(message)),
         503 => Box::new(HttpServiceUnavailable::new(message)),
         _ => Box::new(HttpError::new(code, message)),
@@ -319,6 +345,60 @@ impl HttpErrorResponse for HttpServerError {
     }
 }
 
+/// HTTP 413
+pub struct HttpPayloadTooLarge {
+    error_text: String,
+}
+
+impl HttpPayloadTooLarge {
+    pub fn new(error_text: String) -> Self {
+        Self { error_text }
+    }
+}
+
+impl HttpErrorResponse for HttpPayloadTooLarge {
+    fn code(&self) -> u16 {
+        413
+    }
+    fn payload(&self) -> HttpResponsePayload {
+        HttpResponsePayload::Text(self.error_text.clone())
+    }
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        try_parse_error_response(preamble.status_code, preamble.content_type, body)
+    }
+}
+
+/// HTTP 429
+pub struct HttpTooManyRequests {
+    error_text: String,
+}
+
+impl HttpTooManyRequests {
+    pub fn new(error_text: String) -> Self {
+        Self { error_text }
+    }
+}
+
+impl HttpErrorResponse for HttpTooManyRequests {
+    fn code(&self) -> u16 {
+        429
+    }
+    fn payload(&self) -> HttpResponsePayload {
+        HttpResponsePayload::Text(self.error_text.clone())
+    }
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        try_parse_error_response(preamble.status_code, preamble.content_type, body)
+    }
+}
+
 /// HTTP 503
 pub struct HttpServiceUnavailable {
     error_text: String,
@@ -33,13 +33,13 @@ use serde_json;
 use stacks_common::codec::Error as CodecError;
 
 pub use crate::net::http::common::{
-    parse_bytes, parse_bytestream, parse_json, HttpReservedHeader, HttpVersion,
-    HTTP_PREAMBLE_MAX_NUM_HEADERS,
+    http_date, if_none_match, parse_bytes, parse_bytestream, parse_http_date, parse_json,
+    HttpReservedHeader, HttpVersion, HTTP_PREAMBLE_MAX_NUM_HEADERS,
 };
 pub use crate::net::http::error::{
-    http_error_from_code_and_text, http_reason, HttpBadRequest, HttpError, HttpErrorResponse,
-    HttpForbidden, HttpNotFound, HttpPaymentRequired, HttpServerError, HttpServiceUnavailable,
-    HttpUnauthorized,
+    http_error_from_code_and_text, http_error_name, http_reason, HttpBadRequest, HttpError,
+    HttpErrorResponse, HttpForbidden, HttpNotFound, HttpPayloadTooLarge, HttpPaymentRequired,
+    HttpServerError, HttpServiceUnavailable, HttpTooManyRequests, HttpUnauthorized,
 };
 pub use crate::net::http::request::{
     HttpRequest, HttpRequestContents, HttpRequestPayload, HttpRequestPreamble,
@@ -47,7 +47,7 @@ pub use crate::net::http::request::{
 pub use crate::net::http::response::{
     HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
 };
-pub use crate::net::http::stream::HttpChunkGenerator;
+pub use crate::net::http::stream::{GzipChunkGenerator, HttpChunkGenerator};
 
 #[derive(Debug)]
 pub enum Error {
@@ -179,6 +179,24 @@ impl HttpRequestPreamble {
         return true;
     }
 
+    /// Does the client take gzip-compressed responses, per its `Accept-Encoding`?
+    pub fn accepts_gzip(&self) -> bool {
+        let Some(accept_encoding) = self.get_header("accept-encoding".to_string()) else {
+            return false;
+        };
+        accept_encoding.split(',').any(|coding| {
+            let mut params = coding.split(';').map(|param| param.trim());
+            let name = params.next().unwrap_or("");
+            // `gzip;q=0` means the client does *not* want it
+            name.eq_ignore_ascii_case("gzip")
+                && !params.any(|param| {
+                    param
+                        .strip_prefix("q=")
+                        .map_or(false, |q| q.parse::<f64>().map_or(false, |q| q == 0.0))
+                })
+        })
+    }
+
     /// Get an owned copy of a header if it exists
     pub fn get_header(&self, key: String) -> Option<String> {
         let hdr = key.to_lowercase();
@@ -33,7 +33,7 @@ use crate::net::http::common::{
     HttpReservedHeader, HTTP_PREAMBLE_MAX_ENCODED_SIZE, HTTP_PREAMBLE_MAX_NUM_HEADERS,
 };
 use crate::net::http::request::{HttpRequestContents, HttpRequestPreamble};
-use crate::net::http::stream::HttpChunkGenerator;
+use crate::net::http::stream::{GzipChunkGenerator, HttpChunkGenerator};
 use crate::net::http::{http_reason, write_headers, Error, HttpContentType, HttpVersion};
 
 /// HTTP response preamble.  This captures all HTTP header information, but in a way that
@@ -50,7 +50,8 @@ pub struct HttpResponsePreamble {
     /// true if `Connction: keep-alive` is present
     pub keep_alive: bool,
     /// Content-Length value, if given.  If it's not given, then the payload will be treated as
-    /// chunk-encoded (and it had better have a `Transfer-Encoding: chunked` header)
+    /// chunk-encoded (and it had better have a `Transfer-Encoding: chunked` header), unless the
+    /// client speaks HTTP/1.0, in which case it runs until the connection closes
     pub content_length: Option<u32>,
     /// Content-Type value.
     pub content_type: HttpContentType,
@@ -61,6 +62,9 @@ pub struct HttpResponsePreamble {
 pub struct HttpStreamState {
     encoder_state: Option<HttpChunkedTransferWriterState>,
     generator: Box<dyn HttpChunkGenerator>,
+    /// false if chunks are written as-is, for clients that don't understand chunked
+    /// transfer-encoding
+    chunked: bool,
 }
 
 /// HTTP response body generated by the request handler.  It implements a means of streaming data from disk
@@ -87,6 +91,7 @@ impl HttpResponseContents {
         HttpResponseContents::Stream(HttpStreamState {
             generator,
             encoder_state: Some(HttpChunkedTransferWriterState::new(chunk_size)),
+            chunked: true,
         })
     }
 
@@ -103,6 +108,40 @@ impl HttpResponseContents {
         Ok(Self::from_ram(serde_json::to_string(value)?.into_bytes()))
     }
 
+    /// Send a streamed body without chunked transfer-encoding, as it is generated.  Such a body
+    /// has no content-length, so it ends when the connection is closed.  Bodies in RAM are left
+    /// as they are.
+    pub fn into_unchunked(self) -> HttpResponseContents {
+        match self {
+            Self::RAM(..) => self,
+            Self::Stream(inner_stream) => Self::Stream(HttpStreamState {
+                chunked: false,
+                ..inner_stream
+            }),
+        }
+    }
+
+    /// Compress a streamed body with gzip as it is sent, so it never has to be buffered up in
+    /// full.  Bodies in RAM are left as they are.
+    pub fn try_into_gzip(self) -> Result<HttpResponseContents, Error> {
+        match self {
+            Self::RAM(..) => Ok(self),
+            Self::Stream(inner_stream) => {
+                let generator =
+                    GzipChunkGenerator::new(inner_stream.generator).map_err(Error::WriteError)?;
+                Ok(Self::from_stream(Box::new(generator)))
+            }
+        }
+    }
+
+    /// Is this a live stream, which may have more data to send later even if it has none now?
+    pub fn is_live(&self) -> bool {
+        match self {
+            Self::Stream(inner_stream) => inner_stream.generator.is_live(),
+            Self::RAM(..) => false,
+        }
+    }
+
     /// Deduce the proper content-length
     pub fn content_length(&self) -> Option<u32> {
         match self {
@@ -117,6 +156,15 @@ impl HttpResponseContents {
     #[cfg_attr(test, mutants::skip)]
     pub fn pipe_out(&mut self, fd: &mut PipeWrite) -> Result<u64, Error> {
         match self {
+            HttpResponseContents::Stream(ref mut inner_stream) if !inner_stream.chunked => {
+                // write the next chunk as-is
+                let chunk = inner_stream
+                    .generator
+                    .generate_next_chunk()
+                    .map_err(Error::AppError)?;
+                fd.write_all(&chunk).map_err(Error::WriteError)?;
+                Ok(chunk.len() as u64)
+            }
             HttpResponseContents::Stream(ref mut inner_stream) => {
                 // write the next chunk
                 let mut encoder_state = inner_stream
@@ -427,6 +475,10 @@ impl StacksMessageCodec for HttpResponsePreamble {
                 fd.write_all(format!("{}\r\n", len).as_bytes())
                     .map_err(CodecError::WriteError)?;
             }
+            None if self.client_http_version == HttpVersion::Http10 => {
+                // HTTP/1.0 has no chunked transfer-encoding; the body ends when the connection
+                // closes
+            }
             None => {
                 fd.write_all("Transfer-Encoding: chunked\r\n".as_bytes())
                     .map_err(CodecError::WriteError)?;
@@ -14,9 +14,10 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::io;
 use std::io::{Read, Write};
+use std::{io, mem};
 
+use libflate::gzip;
 use rand::{thread_rng, Rng};
 use stacks_common::types::chainstate::{BlockHeaderHash, StacksBlockId};
 use stacks_common::util::chunked_encoding::{
@@ -36,6 +37,13 @@ pub trait HttpChunkGenerator: Send {
     fn generate_next_chunk(&mut self) -> Result<Vec<u8>, String>;
     fn hint_chunk_size(&self) -> usize;
 
+    /// Can this generator produce more chunks later, even if it has none right now?  A live
+    /// generator (e.g. a feed of new blocks) reports true until its source goes away, so an empty
+    /// chunk does not end its stream.
+    fn is_live(&self) -> bool {
+        false
+    }
+
     /// Stream one chunk to the pipe writer.  This never blocks.
     /// Returns Ok(num-bytes > 0) if there are more chunks (i.e. the caller should call this again)
     /// Returns Ok(0) if there are no more chunks (i.e. the caller should not call this again)
@@ -54,19 +62,75 @@ pub trait HttpChunkGenerator: Send {
         let mut encoder = HttpChunkedTransferWriter::from_writer_state(fd, encoder_state);
 
         if chunk.is_empty() {
-            // no more chunks, but be sure to cork the stream
-            if !encoder.corked() {
+            // no more chunks, but be sure to cork the stream (unless more can come later)
+            if !encoder.corked() && !self.is_live() {
                 encoder.flush()?;
                 encoder.cork();
             }
         } else {
             encoder.write_all(&chunk)?;
+            if self.is_live() {
+                // the next chunk could be a long way off, so don't sit on this one
+                encoder.flush_buffered()?;
+            }
         }
 
         Ok(chunk.len() as u64)
     }
 }
 
+/// Compresses another generator's chunks with gzip as they are generated, so the body never has
+/// to be buffered up in full.  Each input chunk is flushed through the compressor, so compressed
+/// data goes out as soon as its input is generated.
+pub struct GzipChunkGenerator {
+    inner: Box<dyn HttpChunkGenerator>,
+    /// compresses into an in-memory buffer, which is drained on each chunk.  None once the
+    /// trailer has been generated.
+    encoder: Option<gzip::Encoder<Vec<u8>>>,
+}
+
+impl GzipChunkGenerator {
+    pub fn new(inner: Box<dyn HttpChunkGenerator>) -> Result<Self, io::Error> {
+        Ok(Self {
+            inner,
+            encoder: Some(gzip::Encoder::new(vec![])?),
+        })
+    }
+}
+
+impl HttpChunkGenerator for GzipChunkGenerator {
+    fn generate_next_chunk(&mut self) -> Result<Vec<u8>, String> {
+        loop {
+            let Some(encoder) = self.encoder.as_mut() else {
+                // already sent the trailer
+                return Ok(vec![]);
+            };
+            let chunk = self.inner.generate_next_chunk()?;
+            if chunk.is_empty() {
+                // no more input, so finish off the gzip stream
+                let encoder = self.encoder.take().expect("BUG: no gzip encoder");
+                return encoder
+                    .finish()
+                    .into_result()
+                    .map_err(|e| format!("Failed to finish gzip stream: {:?}", &e));
+            }
+            encoder
+                .write_all(&chunk)
+                .and_then(|_| encoder.flush())
+                .map_err(|e| format!("Failed to gzip chunk: {:?}", &e))?;
+            let compressed = mem::take(encoder.as_inner_mut());
+            // an empty chunk would end the stream, so keep going until there's output
+            if !compressed.is_empty() {
+                return Ok(compressed);
+            }
+        }
+    }
+
+    fn hint_chunk_size(&self) -> usize {
+        self.inner.hint_chunk_size()
+    }
+}
+
 /// Interface for streaming data
 pub trait Streamer {
     /// Return the offset into the stream at which this Streamer points.  This value is equivalent
@@ -621,6 +621,8 @@ fn test_http_response_version_keep_alive() {
         );
         match version {
             HttpVersion::Http10 => {
+                // http/1.0 clients don't understand chunked transfer-encoding
+                assert!(response.find("Transfer-Encoding:").is_none());
                 // be explicit about Connection: with http/1.0 clients
                 if *sent_keep_alive {
                     assert!(response.find("Connection: keep-alive\r\n").is_some());
@@ -629,6 +631,7 @@ fn test_http_response_version_keep_alive() {
                 }
             }
             HttpVersion::Http11 => {
+                assert!(response.find("Transfer-Encoding: chunked\r\n").is_some());
                 if *sent_keep_alive {
                     // we don't send connection: keep-alive if the client is 1.1 and it didn't
                     // send its own connection: <option>
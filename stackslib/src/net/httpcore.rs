@@ -15,9 +15,9 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 /// This module binds the http library to Stacks as a `ProtocolFamily` implementation
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::{Read, Write};
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::{fmt, io, mem};
 
 use clarity::vm::costs::ExecutionCost;
@@ -29,14 +29,14 @@ use stacks_common::codec::{read_next, Error as CodecError, StacksMessageCodec, M
 use stacks_common::types::chainstate::{
     ConsensusHash, StacksAddress, StacksBlockId, StacksPublicKey,
 };
-use stacks_common::types::net::PeerHost;
+use stacks_common::types::net::{PeerAddress, PeerHost};
 use stacks_common::types::Address;
 use stacks_common::util::chunked_encoding::*;
 use stacks_common::util::get_epoch_time_ms;
+use stacks_common::util::hash::Sha256Sum;
 use stacks_common::util::retry::{BoundReader, RetryReader};
 use url::Url;
 
-use super::rpc::ConversationHttp;
 use crate::burnchains::Txid;
 use crate::chainstate::burn::db::sortdb::SortitionDB;
 use crate::chainstate::burn::BlockSnapshot;
@@ -44,14 +44,15 @@ use crate::chainstate::nakamoto::NakamotoChainState;
 use crate::chainstate::stacks::db::{StacksChainState, StacksHeaderInfo};
 use crate::core::{MemPoolDB, StacksEpoch};
 use crate::net::connection::ConnectionOptions;
-use crate::net::http::common::HTTP_PREAMBLE_MAX_ENCODED_SIZE;
+use crate::net::http::common::{decode_gzip_body, HTTP_PREAMBLE_MAX_ENCODED_SIZE};
 use crate::net::http::{
-    http_reason, Error as HttpError, HttpBadRequest, HttpContentType, HttpErrorResponse,
-    HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble, HttpResponse,
-    HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError, HttpVersion,
+    http_error_name, http_reason, Error as HttpError, HttpBadRequest, HttpContentType,
+    HttpErrorResponse, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+    HttpServiceUnavailable, HttpTooManyRequests, HttpVersion,
 };
 use crate::net::p2p::PeerNetwork;
-use crate::net::server::HttpPeer;
+use crate::net::server::{prefix_matches, HttpPeer};
 use crate::net::{Error as NetError, MessageSequence, ProtocolFamily, StacksNodeState, UrlString};
 
 const CHUNK_BUF_LEN: usize = 32768;
@@ -68,6 +69,23 @@ pub const STACKS_REQUEST_ID: &'static str = "X-Request-Id";
 /// from non-Stacks nodes (like Gaia hubs, CDNs, vanilla HTTP servers, and so on).
 pub const HTTP_REQUEST_ID_RESERVED: u32 = 0;
 
+/// Correlation ID header, used to tie an HTTP request to its log lines, server events, and any
+/// peer network messages it causes the node to forward.  Clients may supply their own.
+pub const STACKS_CORRELATION_ID: &'static str = "X-Correlation-Id";
+
+/// Idempotency key header.  A client that posts a transaction with this header and retries the
+/// post with the same key gets back the response to its first attempt.  Keys are scoped to the
+/// client's IP address.
+pub const IDEMPOTENCY_KEY: &'static str = "Idempotency-Key";
+
+/// Request deadline header.  A client that sends this header with a number of milliseconds gets
+/// its connection closed if the server is still sending the reply after that long.
+pub const REQUEST_TIMEOUT_MS: &'static str = "X-Request-Timeout-Ms";
+
+/// Metrics identifiers of the endpoints which are served even while the node is in initial block
+/// download, so monitoring keeps working
+pub const IBD_EXEMPT_ENDPOINTS: &[&str] = &["/metrics", "/v2/health", "OPTIONS"];
+
 /// All representations of the `tip=` query parameter value
 #[derive(Debug, Clone, PartialEq)]
 pub enum TipRequest {
@@ -110,6 +128,10 @@ pub trait HttpPreambleExtensions {
     fn get_canonical_stacks_tip_height(&self) -> Option<u32>;
     /// Get the request ID
     fn get_request_id(&self) -> Option<u32>;
+    /// Set the correlation ID
+    fn set_correlation_id(&mut self, id: u64);
+    /// Get the correlation ID
+    fn get_correlation_id(&self) -> Option<u64>;
 }
 
 impl HttpPreambleExtensions for HttpRequestPreamble {
@@ -141,6 +163,17 @@ impl HttpPreambleExtensions for HttpRequestPreamble {
         self.get_header("X-Request-Id".to_string())
             .and_then(|req| req.parse::<u32>().ok())
     }
+
+    /// Set the correlation ID
+    fn set_correlation_id(&mut self, id: u64) {
+        self.add_header(STACKS_CORRELATION_ID.into(), format!("{}", id));
+    }
+
+    /// Get the correlation ID
+    fn get_correlation_id(&self) -> Option<u64> {
+        self.get_header(STACKS_CORRELATION_ID.to_string())
+            .and_then(|id| id.parse::<u64>().ok())
+    }
 }
 
 impl HttpPreambleExtensions for HttpResponsePreamble {
@@ -172,6 +205,17 @@ impl HttpPreambleExtensions for HttpResponsePreamble {
         self.get_header("X-Request-Id".to_string())
             .and_then(|req| req.parse::<u32>().ok())
     }
+
+    /// Set the correlation ID
+    fn set_correlation_id(&mut self, id: u64) {
+        self.add_header(STACKS_CORRELATION_ID.into(), format!("{}", id));
+    }
+
+    /// Get the correlation ID
+    fn get_correlation_id(&self) -> Option<u64> {
+        self.get_header(STACKS_CORRELATION_ID.to_string())
+            .and_then(|id| id.parse::<u64>().ok())
+    }
 }
 
 /// This module contains request helpers for decoding common data found in the request path regex captures.
@@ -610,12 +654,55 @@ impl StacksHttpResponse {
         }
     }
 
-    /// Make a new HTTP error response, in reaction to a request
+    /// Quality value (between 0 and 1) that an `Accept` header gives to the media type
+    /// `mime_type`, if it lists that type explicitly.  Wildcards are not considered.
+    fn accept_quality(accept: &str, mime_type: &str) -> Option<f32> {
+        accept.split(',').find_map(|media_range| {
+            let mut params = media_range.split(';').map(str::trim);
+            if !params.next()?.eq_ignore_ascii_case(mime_type) {
+                return None;
+            }
+            let quality = params
+                .find_map(|param| param.strip_prefix("q="))
+                .map(|q| q.parse::<f32>().unwrap_or(0.0))
+                .unwrap_or(1.0);
+            Some(quality)
+        })
+    }
+
+    /// Does the client want error responses as JSON?  This is the case if its `Accept` header
+    /// lists `application/json` as acceptable, and likes it at least as much as `text/plain`.
+    fn wants_json_errors(preamble: &HttpRequestPreamble) -> bool {
+        let Some(accept) = preamble.headers.get("accept") else {
+            return false;
+        };
+        let json_quality = Self::accept_quality(accept, "application/json").unwrap_or(0.0);
+        let text_quality = Self::accept_quality(accept, "text/plain").unwrap_or(0.0);
+        json_quality > 0.0 && json_quality >= text_quality
+    }
+
+    /// Make a new HTTP error response, in reaction to a request.
+    /// All error responses to requests are built here.  If the client asked for JSON, then a
+    /// plain-text error is wrapped into an envelope of the form
+    /// `{ "error": "<code>", "message": "<text>", "request_id": "<id>" }`, where the request ID
+    /// is the request's correlation ID, as set by the caller on `preamble`.  The request ID is
+    /// null if the caller hasn't set one; see `fill_request_id()`.  Errors that already carry a
+    /// JSON body (like transaction rejections) are sent as-is.
     pub fn new_error(
         preamble: &HttpRequestPreamble,
         error: &dyn HttpErrorResponse,
     ) -> StacksHttpResponse {
-        let payload = error.payload();
+        let mut payload = error.payload();
+        let correlation_id = preamble.get_correlation_id();
+        if Self::wants_json_errors(preamble) {
+            if let HttpResponsePayload::Text(message) = &payload {
+                payload = HttpResponsePayload::JSON(serde_json::json!({
+                    "error": http_error_name(error.code()),
+                    "message": message,
+                    "request_id": correlation_id.map(|id| format!("{}", id)),
+                }));
+            }
+        }
         let content_type = match &payload {
             HttpResponsePayload::Empty => HttpContentType::Bytes,
             HttpResponsePayload::Bytes(..) => HttpContentType::Bytes,
@@ -623,16 +710,38 @@ impl StacksHttpResponse {
             HttpResponsePayload::JSON(..) => HttpContentType::JSON,
         };
         let content_length = payload.try_content_length();
-        let preamble = HttpResponsePreamble::from_http_request_preamble(
+        let mut preamble = HttpResponsePreamble::from_http_request_preamble(
             preamble,
             error.code(),
             http_reason(error.code()),
             content_length,
             content_type,
         );
+        if let Some(id) = correlation_id {
+            preamble.set_correlation_id(id);
+        }
         StacksHttpResponse::new(preamble, payload)
     }
 
+    /// If this is a JSON error envelope from `new_error()` without a request ID, then set its
+    /// request ID (and the response's correlation ID header) to the one returned by `next_id`.
+    /// Nothing is done (and `next_id` is not called) for any other response.
+    pub fn fill_request_id<F: FnOnce() -> u64>(&mut self, next_id: F) {
+        let HttpResponsePayload::JSON(envelope) = &mut self.body else {
+            return;
+        };
+        let Some(request_id) = envelope.get_mut("request_id") else {
+            return;
+        };
+        if !request_id.is_null() {
+            return;
+        }
+        let id = next_id();
+        *request_id = serde_json::Value::String(format!("{}", id));
+        self.preamble.content_length = self.body.try_content_length();
+        self.preamble.set_correlation_id(id);
+    }
+
     /// Make a new HTTP error response for text, apropos of nothing
     pub fn new_empty_error(error: &dyn HttpErrorResponse) -> StacksHttpResponse {
         let code = error.code();
@@ -883,6 +992,38 @@ pub struct StacksHttp {
     pub read_only_call_limit: ExecutionCost,
     /// The authorization token to enable the block proposal RPC endpoint
     pub block_proposal_token: Option<String>,
+    /// The bearer token required to use privileged endpoints
+    pub auth_token: Option<String>,
+    /// Metrics identifiers of the endpoints which require `auth_token`
+    pub privileged_endpoints: HashSet<String>,
+    /// Whether or not to serve `/metrics`
+    pub enable_metrics_endpoint: bool,
+    /// Whether or not to answer CORS preflight requests
+    pub enable_cors_preflight: bool,
+    /// Subjects of client TLS certificates which may use privileged endpoints without
+    /// `auth_token`
+    pub privileged_client_subjects: HashSet<String>,
+    /// Subject of the client's verified TLS certificate, if it presented one
+    pub client_subject: Option<String>,
+    /// Whether or not to answer requests with a 503 while the node is in initial block download
+    pub reject_requests_during_ibd: bool,
+    /// If the node is in initial block download, its estimated progress (in percent)
+    pub ibd_progress: Option<u8>,
+    /// If set, how many more requests may be handled before the rest get a 429, per the
+    /// client's rate limit (see `max_requests_per_second_per_host`)
+    pub request_allowance: Option<u64>,
+    /// The largest request body we'll take, if limited (see `max_request_body_len`)
+    pub max_request_body_len: Option<u64>,
+    /// Text to serve for `GET /`, if any (see `root_response`)
+    pub root_response: Option<String>,
+    /// Preamble of the last request refused for declaring a body bigger than
+    /// `max_request_body_len`, so the refusal can be answered
+    oversized_request: Option<HttpRequestPreamble>,
+    /// Reverse proxies whose `X-Forwarded-For` headers we believe, if any (see
+    /// `trust_forwarded_for`)
+    pub trusted_proxies: Option<Vec<(PeerAddress, u32)>>,
+    /// Client that the last request was forwarded for, if it came through a trusted proxy
+    pub forwarded_for: Option<IpAddr>,
 }
 
 impl StacksHttp {
@@ -899,7 +1040,29 @@ impl StacksHttp {
             maximum_call_argument_size: conn_opts.maximum_call_argument_size,
             read_only_call_limit: conn_opts.read_only_call_limit.clone(),
             block_proposal_token: conn_opts.block_proposal_token.clone(),
+            auth_token: conn_opts.auth_token.clone(),
+            privileged_endpoints: conn_opts.privileged_endpoints.clone(),
+            enable_metrics_endpoint: conn_opts.enable_metrics_endpoint,
+            enable_cors_preflight: conn_opts.cors.is_some(),
+            privileged_client_subjects: conn_opts.privileged_client_subjects.clone(),
+            client_subject: None,
+            reject_requests_during_ibd: conn_opts.reject_requests_during_ibd,
+            ibd_progress: None,
+            request_allowance: None,
+            max_request_body_len: conn_opts.max_request_body_len,
+            root_response: conn_opts.root_response.clone(),
+            oversized_request: None,
+            trusted_proxies: if conn_opts.trust_forwarded_for {
+                Some(conn_opts.trusted_proxies.clone())
+            } else {
+                None
+            },
+            forwarded_for: None,
         };
+        if http.enable_metrics_endpoint {
+            // metrics are only for the operator
+            http.privileged_endpoints.insert("/metrics".to_string());
+        }
         http.register_rpc_methods();
         http
     }
@@ -976,6 +1139,34 @@ impl StacksHttp {
         Ok(request)
     }
 
+    /// Check that a request carries `Authorization: Bearer <auth_token>`, or that it arrived
+    /// over a TLS connection whose verified client certificate has a privileged subject.
+    /// Fails with a 401 if neither holds.
+    fn check_authorization(
+        auth_token: Option<&str>,
+        client_subject: Option<&str>,
+        privileged_client_subjects: &HashSet<String>,
+        preamble: &HttpRequestPreamble,
+    ) -> Result<(), NetError> {
+        if client_subject
+            .map(|subject| privileged_client_subjects.contains(subject))
+            .unwrap_or(false)
+        {
+            return Ok(());
+        }
+        let authorized = match (auth_token, preamble.headers.get("authorization")) {
+            (Some(token), Some(auth_header)) => auth_header
+                .strip_prefix("Bearer ")
+                .map(|bearer| secrets_equal(bearer.trim(), token))
+                .unwrap_or(false),
+            _ => false,
+        };
+        if !authorized {
+            return Err(NetError::Http(HttpError::Http(401, "Unauthorized".into())));
+        }
+        Ok(())
+    }
+
     /// Try to parse an inbound HTTP request, given its decoded HTTP preamble.
     /// The body will be in the `fd`.
     /// Returns the parsed HTTP request if successful.
@@ -998,6 +1189,18 @@ impl StacksHttp {
                 continue;
             };
 
+            if self
+                .privileged_endpoints
+                .contains(request.metrics_identifier())
+            {
+                Self::check_authorization(
+                    self.auth_token.as_deref(),
+                    self.client_subject.as_deref(),
+                    &self.privileged_client_subjects,
+                    preamble,
+                )?;
+            }
+
             let payload = match request.try_parse_request(
                 preamble,
                 &captures,
@@ -1019,7 +1222,10 @@ impl StacksHttp {
         test_debug!("Failed to parse '{}'", &preamble.path_and_query_str);
         Err(NetError::Http(HttpError::Http(
             404,
-            "No such file or directory".into(),
+            format!(
+                "No such API endpoint '{} {}'",
+                &preamble.verb, &decoded_path
+            ),
         )))
     }
 
@@ -1069,6 +1275,8 @@ impl StacksHttp {
         preamble: &HttpResponsePreamble,
         body: &[u8],
     ) -> Result<StacksHttpResponse, NetError> {
+        let decoded_body = decode_gzip_body(preamble, body, MAX_MESSAGE_LEN.into())?;
+        let body = decoded_body.as_deref().unwrap_or(body);
         if preamble.status_code >= 400 {
             return Self::try_parse_error_response(preamble, body);
         }
@@ -1091,6 +1299,17 @@ impl StacksHttp {
         request: StacksHttpRequest,
         node: &mut StacksNodeState,
     ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        if let Some(allowance) = self.request_allowance.as_mut() {
+            if *allowance == 0 {
+                return StacksHttpResponse::new_error(
+                    &request.preamble,
+                    &HttpTooManyRequests::new("Too many requests from this host".to_string()),
+                )
+                .try_into_contents();
+            }
+            *allowance -= 1;
+        }
+
         let (decoded_path, _) = decode_request_path(&request.preamble().path_and_query_str)?;
         let Some(response_handler_index) = request
             .response_handler_index
@@ -1112,6 +1331,23 @@ impl StacksHttp {
             .request_handlers
             .get_mut(response_handler_index)
             .expect("FATAL: request points to a nonexistent handler");
+
+        if let Some(ibd_progress) = self.ibd_progress {
+            if self.reject_requests_during_ibd
+                && !IBD_EXEMPT_ENDPOINTS.contains(&request_handler.metrics_identifier())
+            {
+                return StacksHttpResponse::new_error(
+                    &request.preamble,
+                    &HttpServiceUnavailable::new(format!(
+                        "Initial block download in progress ({}% done)",
+                        ibd_progress
+                    )),
+                )
+                .try_into_contents();
+            }
+        }
+
+        node.set_client_ip(self.forwarded_for.unwrap_or(self.peer_addr.ip()));
         let request_preamble = request.preamble.clone();
         let request_result =
             request_handler.try_handle_request(request.preamble, request.contents, node);
@@ -1172,11 +1408,46 @@ impl StacksHttp {
                     self.set_pending(http_response_preamble);
                 }
             }
-            _ => {}
+            StacksHttpPreamble::Request(ref http_request_preamble) => {
+                // each request names its own client, since a proxy can send many clients'
+                // requests over one connection
+                if let Some(trusted_proxies) = self.trusted_proxies.as_ref() {
+                    self.forwarded_for = http_request_preamble
+                        .get_header("X-Forwarded-For".to_string())
+                        .and_then(|forwarded_for| {
+                            forwarded_client_ip(
+                                &self.peer_addr.ip(),
+                                &forwarded_for,
+                                trusted_proxies,
+                            )
+                        });
+                }
+
+                // refuse an oversized body before buffering any of it
+                let content_length = u64::from(http_request_preamble.get_content_length());
+                if let Some(max_len) = self
+                    .max_request_body_len
+                    .filter(|max_len| content_length > *max_len)
+                {
+                    self.oversized_request = Some(http_request_preamble.clone());
+                    return Err(NetError::Http(HttpError::Http(
+                        413,
+                        format!(
+                            "Request body of {} bytes exceeds the maximum of {} bytes",
+                            content_length, max_len
+                        ),
+                    )));
+                }
+            }
         }
         Ok(())
     }
 
+    /// Take the preamble of the request that was just refused for being too big, if any
+    pub fn take_oversized_request(&mut self) -> Option<HttpRequestPreamble> {
+        self.oversized_request.take()
+    }
+
     /// Clear any pending response state -- i.e. due to a failed request.
     fn reset(&mut self) -> () {
         self.request_handler_index = None;
@@ -1619,6 +1890,7 @@ impl PeerNetwork {
                 ) {
                     Ok(event_id) => Ok(event_id),
                     Err(NetError::AlreadyConnected(event_id, _)) => {
+                        http.check_outbound_request_limit()?;
                         if let (Some(ref mut convo), Some(ref mut socket)) =
                             http.get_conversation_and_socket(event_id)
                         {
@@ -1647,6 +1919,25 @@ pub fn decode_request_path(path: &str) -> Result<(String, String), NetError> {
         NetError::DeserializeError("Http request path could not be parsed".to_string())
     })?;
 
+    // every `%` must start a two-hex-digit escape.  `percent_decode_str()` would otherwise pass
+    // a malformed escape through verbatim, and the request would just fail to match a route.
+    let path_bytes = url.path().as_bytes();
+    for (i, byte) in path_bytes.iter().enumerate() {
+        if *byte != b'%' {
+            continue;
+        }
+        let is_escape = path_bytes
+            .get(i + 1..i + 3)
+            .map(|digits| digits.iter().all(u8::is_ascii_hexdigit))
+            .unwrap_or(false);
+        if !is_escape {
+            return Err(NetError::Http(HttpError::DecodeError(format!(
+                "Malformed percent-encoding at offset {} of the request path",
+                i
+            ))));
+        }
+    }
+
     let decoded_path = percent_decode_str(url.path()).decode_utf8().map_err(|_e| {
         NetError::DeserializeError("Http request path could not be parsed as UTF-8".to_string())
     })?;
@@ -1657,3 +1948,58 @@ pub fn decode_request_path(path: &str) -> Result<(String, String), NetError> {
         query_str.unwrap_or("").to_string(),
     ))
 }
+
+/// Compare two secrets (e.g. bearer tokens) in constant time, so that how long the comparison
+/// takes says nothing about how much of a guess was right.  Both are hashed first, so their
+/// lengths don't matter either.
+pub fn secrets_equal(a: &str, b: &str) -> bool {
+    let a = Sha256Sum::from_data(a.as_bytes());
+    let b = Sha256Sum::from_data(b.as_bytes());
+    let diff = a
+        .as_bytes()
+        .iter()
+        .zip(b.as_bytes().iter())
+        .fold(0u8, |diff, (x, y)| diff | (x ^ y));
+    std::hint::black_box(diff) == 0
+}
+
+/// Find the client that a request was forwarded for, given the address of the peer that sent it
+/// and its `X-Forwarded-For` header.  Each proxy appends the address it heard from, so the header
+/// is only believed as far back as it was written by trusted proxies: the client is the nearest
+/// address in it that isn't one of `trusted_proxies`.  Addresses further left were written by the
+/// client itself, and could be anything.
+/// Returns None if the peer isn't a trusted proxy, or if the header has no usable address.
+pub fn forwarded_client_ip(
+    peer_ip: &IpAddr,
+    forwarded_for: &str,
+    trusted_proxies: &[(PeerAddress, u32)],
+) -> Option<IpAddr> {
+    let is_trusted = |ip: &IpAddr| {
+        let addr = PeerAddress::from_ip(ip);
+        trusted_proxies
+            .iter()
+            .any(|(prefix, mask)| prefix_matches(prefix, *mask, &addr))
+    };
+    if !is_trusted(peer_ip) {
+        return None;
+    }
+
+    let mut client_ip = None;
+    for hop in forwarded_for.rsplit(',') {
+        let hop = hop.trim();
+        // some proxies include the client's port
+        let Some(ip) = hop
+            .parse::<IpAddr>()
+            .ok()
+            .or_else(|| hop.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+        else {
+            // can't tell who sent this hop, so stop at the last one we can
+            break;
+        };
+        client_ip = Some(ip);
+        if !is_trusted(&ip) {
+            break;
+        }
+    }
+    client_ip
+}
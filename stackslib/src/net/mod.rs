@@ -137,8 +137,11 @@ pub mod relay;
 pub mod rpc;
 pub mod server;
 pub mod stackerdb;
+/// Implements TLS termination for the HTTP server
+pub mod tls;
 
 pub use crate::net::neighbors::{NeighborComms, PeerNetworkComms};
+use crate::net::server::IdempotencyCache;
 use crate::net::stackerdb::{StackerDBConfig, StackerDBSync, StackerDBSyncResult, StackerDBs};
 
 #[cfg(test)]
@@ -220,8 +223,18 @@ pub enum Error {
     PeerNotConnected,
     /// Too many peers
     TooManyPeers,
+    /// Too many outbound HTTP requests outstanding
+    TooManyOutboundRequests,
     /// Peer already connected
     AlreadyConnected(usize, NeighborKey),
+    /// Connection to peer already in progress (with the given event ID)
+    AlreadyConnecting(usize),
+    /// Tried to connect to ourselves
+    ConnectToSelf,
+    /// HTTP server is draining for shutdown, and takes no new connections
+    Draining,
+    /// Failed to set up or carry out a TLS session
+    TlsError(String),
     /// Message already in progress
     InProgress,
     /// Peer is denied
@@ -367,7 +380,14 @@ impl fmt::Display for Error {
             Error::NotConnected => write!(f, "Not connected to peer network"),
             Error::PeerNotConnected => write!(f, "Remote peer is not connected to us"),
             Error::TooManyPeers => write!(f, "Too many peer connections open"),
+            Error::TooManyOutboundRequests => {
+                write!(f, "Too many outbound HTTP requests outstanding")
+            }
             Error::AlreadyConnected(ref _id, ref _nk) => write!(f, "Peer already connected"),
+            Error::AlreadyConnecting(ref _id) => write!(f, "Peer connection already in progress"),
+            Error::ConnectToSelf => write!(f, "Tried to connect to ourselves"),
+            Error::Draining => write!(f, "HTTP server is draining for shutdown"),
+            Error::TlsError(ref s) => fmt::Display::fmt(s, f),
             Error::InProgress => write!(f, "Message already in progress"),
             Error::Denied => write!(f, "Peer is denied"),
             Error::NoDataUrl => write!(f, "No data URL available"),
@@ -476,7 +496,12 @@ impl error::Error for Error {
             Error::NotConnected => None,
             Error::PeerNotConnected => None,
             Error::TooManyPeers => None,
+            Error::TooManyOutboundRequests => None,
             Error::AlreadyConnected(ref _id, ref _nk) => None,
+            Error::AlreadyConnecting(ref _id) => None,
+            Error::ConnectToSelf => None,
+            Error::Draining => None,
+            Error::TlsError(ref _s) => None,
             Error::InProgress => None,
             Error::Denied => None,
             Error::NoDataUrl => None,
@@ -647,6 +672,8 @@ pub struct StacksNodeState<'a> {
     inner_mempool: Option<&'a mut MemPoolDB>,
     inner_rpc_args: Option<&'a RPCHandlerArgs<'a>>,
     relay_message: Option<StacksMessageType>,
+    idempotency_cache: Option<IdempotencyCache>,
+    client_ip: Option<IpAddr>,
 }
 
 impl<'a> StacksNodeState<'a> {
@@ -664,6 +691,8 @@ impl<'a> StacksNodeState<'a> {
             inner_mempool: Some(inner_mempool),
             inner_rpc_args: Some(inner_rpc_args),
             relay_message: None,
+            idempotency_cache: None,
+            client_ip: None,
         }
     }
 
@@ -724,6 +753,35 @@ impl<'a> StacksNodeState<'a> {
         self.relay_message.take()
     }
 
+    /// Lend the HTTP server's idempotency-key cache to the request handlers
+    pub fn set_idempotency_cache(&mut self, cache: IdempotencyCache) {
+        self.idempotency_cache = Some(cache);
+    }
+
+    /// Take back the idempotency-key cache, if one was lent
+    pub fn take_idempotency_cache(&mut self) -> Option<IdempotencyCache> {
+        self.idempotency_cache.take()
+    }
+
+    /// Set the IP address of the client whose request is being handled
+    pub fn set_client_ip(&mut self, client_ip: IpAddr) {
+        self.client_ip = Some(client_ip);
+    }
+
+    /// IP address of the client whose request is being handled, if known
+    pub fn client_ip(&self) -> Option<IpAddr> {
+        self.client_ip
+    }
+
+    /// Run func() with the idempotency-key cache.
+    /// Returns None if no cache was lent (e.g. the request did not come through an `HttpPeer`).
+    pub fn with_idempotency_cache<F, R>(&mut self, func: F) -> Option<R>
+    where
+        F: FnOnce(&mut IdempotencyCache) -> R,
+    {
+        self.idempotency_cache.as_mut().map(func)
+    }
+
     /// Load up the canonical Stacks chain tip.  Note that this is subject to both burn chain block
     /// Stacks block availability -- different nodes with different partial replicas of the Stacks chain state
     /// will return different values here.
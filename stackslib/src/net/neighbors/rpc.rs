@@ -194,6 +194,7 @@ impl NeighborRPC {
                     match http.connect_http(network_state, network, data_url, data_addr, None) {
                         Ok(event_id) => Ok(event_id),
                         Err(NetError::AlreadyConnected(event_id, _)) => Ok(event_id),
+                        Err(NetError::AlreadyConnecting(event_id)) => Ok(event_id),
                         Err(e) => {
                             return Err(e);
                         }
@@ -220,6 +221,11 @@ impl NeighborRPC {
         request_opt: &mut Option<StacksHttpRequest>,
     ) -> Result<Option<StacksHttpResponse>, NetError> {
         PeerNetwork::with_http(network, |network, http| {
+            // don't start a new request if we're at our cap on outstanding requests
+            if request_opt.is_some() && http.peers.contains_key(&event_id) {
+                http.check_outbound_request_limit()?;
+            }
+
             // make sure we're connected
             let (Some(ref mut convo), Some(ref mut socket)) =
                 http.get_conversation_and_socket(event_id)
@@ -33,7 +33,7 @@ use stacks_common::consts::{FIRST_BURNCHAIN_CONSENSUS_HASH, FIRST_STACKS_BLOCK_H
 use stacks_common::types::chainstate::{PoxId, SortitionId};
 use stacks_common::types::net::{PeerAddress, PeerHost};
 use stacks_common::types::StacksEpochId;
-use stacks_common::util::hash::to_hex;
+use stacks_common::util::hash::{to_hex, Sha256Sum};
 use stacks_common::util::secp256k1::Secp256k1PublicKey;
 use stacks_common::util::{get_epoch_time_ms, get_epoch_time_secs, log};
 use wsts::curve::point::Point;
@@ -52,6 +52,7 @@ use crate::chainstate::stacks::db::StacksChainState;
 use crate::chainstate::stacks::{StacksBlockHeader, MAX_BLOCK_LEN, MAX_TRANSACTION_LEN};
 use crate::core::StacksEpoch;
 use crate::monitoring::{update_inbound_neighbors, update_outbound_neighbors};
+use crate::net::api::getblocks_subscribe::BlockSubscribers;
 use crate::net::asn::ASEntry4;
 use crate::net::atlas::{AtlasDB, AttachmentInstance, AttachmentsDownloader};
 use crate::net::chat::{ConversationP2P, NeighborStats};
@@ -365,6 +366,13 @@ pub struct PeerNetwork {
 
     // http endpoint, used for driving HTTP conversations (some of which we initiate)
     pub http: Option<HttpPeer>,
+    // last snapshot of the http endpoint's counters, taken before it handles requests
+    pub http_metrics: HttpPeerMetrics,
+    // HTTP clients that are streaming new-block announcements
+    pub block_subscribers: BlockSubscribers,
+    // fingerprint of the chain view last served by /v2/info, and its Last-Modified time (in unix
+    // seconds)
+    pub info_last_modified: Option<(Sha256Sum, u64)>,
 
     // our own neighbor address that we bind on
     bind_nk: NeighborKey,
@@ -536,6 +544,9 @@ impl PeerNetwork {
             prune_inbound_counts: HashMap::new(),
 
             http: Some(http),
+            http_metrics: HttpPeerMetrics::default(),
+            block_subscribers: BlockSubscribers::default(),
+            info_last_modified: None,
             bind_nk: NeighborKey {
                 network_id: 0,
                 peer_version: 0,
@@ -6053,6 +6064,21 @@ impl PeerNetwork {
         false
     }
 
+    /// Estimate how far along initial block download is (in percent), as the share of the
+    /// burnchain headers we have processed into sortitions
+    fn ibd_progress(&self, burnchain_height: u64) -> u8 {
+        let first_block_height = self.burnchain.first_block_height;
+        let processed = self
+            .burnchain_tip
+            .block_height
+            .saturating_sub(first_block_height);
+        let total = burnchain_height.saturating_sub(first_block_height);
+        if total == 0 {
+            return 0;
+        }
+        (processed.saturating_mul(100) / total).min(100) as u8
+    }
+
     /// Top-level main-loop circuit to take.
     /// -- polls the peer network and http network server sockets to get new sockets and detect ready sockets
     /// -- carries out network conversations
@@ -6135,23 +6161,26 @@ impl PeerNetwork {
         })
         .expect("FATAL: with_attachments_downloader should be infallable (not connected)");
 
+        let burnchain_height = indexer
+            .get_burnchain_headers_height()
+            // N.B. the indexer reports 1 + num_headers
+            .map(|burnchain_height| burnchain_height.saturating_sub(1))
+            .unwrap_or(self.burnchain_tip.block_height);
+        let ibd_progress = ibd.then(|| self.ibd_progress(burnchain_height));
+
         PeerNetwork::with_network_state(self, |ref mut network, ref mut network_state| {
             let http_stacks_msgs = PeerNetwork::with_http(network, |ref mut net, ref mut http| {
+                net.http_metrics = http.metrics();
+                http.set_ibd_progress(ibd_progress);
                 let mut node_state =
                     StacksNodeState::new(net, sortdb, chainstate, mempool, handler_args);
-                http.run(network_state, &mut node_state, http_poll_state)
+                http.run(network_state, &mut node_state, http_poll_state, None)
             });
-            network_result.consume_http_uploads(http_stacks_msgs);
+            network_result.consume_http_uploads(ForwardedMessage::into_msgs(http_stacks_msgs));
             Ok(())
         })
         .expect("FATAL: with_network_state should be infallable (not connected)");
 
-        let burnchain_height = indexer
-            .get_burnchain_headers_height()
-            // N.B. the indexer reports 1 + num_headers
-            .map(|burnchain_height| burnchain_height.saturating_sub(1))
-            .unwrap_or(self.burnchain_tip.block_height);
-
         self.dispatch_network(
             &mut network_result,
             burnchain_height,
@@ -262,6 +262,25 @@ impl NetworkState {
         Ok(())
     }
 
+    /// Deregister a socket event, but leave the socket open, e.g. so it can be handed off to
+    /// another process
+    pub fn release(&mut self, event_id: usize, sock: &mio_net::TcpStream) -> Result<(), net_error> {
+        if self.event_map.remove(&event_id).is_none() {
+            return Err(net_error::RegisterError);
+        }
+        if let Err(e) = self.poll.deregister(sock) {
+            warn!("Failed to deregister socket {}: {:?}", event_id, &e);
+        };
+        debug!(
+            "Socket released: {}, {:?} (Events total: {}, max: {})",
+            event_id,
+            sock,
+            self.event_map.len(),
+            self.event_capacity
+        );
+        Ok(())
+    }
+
     fn make_next_event_id(&self, cur_count: usize, in_use: &HashSet<usize>) -> Option<usize> {
         let mut ret = cur_count;
 
@@ -367,6 +386,48 @@ impl NetworkState {
         Ok(stream)
     }
 
+    /// Turn on TCP keepalive for a connected socket, so the OS notices if the remote end silently
+    /// goes away.  Probing starts once the socket has been idle for `idle_secs`, and the
+    /// connection is reset after `probes` unanswered probes sent `interval_secs` apart.  The
+    /// interval and probe count can only be set on Linux; elsewhere, the OS defaults are used.
+    pub fn set_tcp_keepalive(
+        stream: &mio_net::TcpStream,
+        idle_secs: u64,
+        interval_secs: u64,
+        probes: u32,
+    ) -> Result<(), net_error> {
+        stream
+            .set_keepalive(Some(Duration::from_secs(idle_secs)))
+            .map_err(|e| {
+                warn!("Failed to set TCP_KEEPALIVE and/or SO_KEEPALIVE: {:?}", &e);
+                net_error::ConnectionError
+            })?;
+
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        {
+            use std::os::unix::io::AsRawFd;
+
+            use nix::sys::socket::{setsockopt, sockopt};
+
+            let interval_secs = u32::try_from(interval_secs).unwrap_or(u32::MAX);
+            setsockopt(stream.as_raw_fd(), sockopt::TcpKeepInterval, &interval_secs).map_err(
+                |e| {
+                    warn!("Failed to set TCP_KEEPINTVL: {:?}", &e);
+                    net_error::ConnectionError
+                },
+            )?;
+            setsockopt(stream.as_raw_fd(), sockopt::TcpKeepCount, &probes).map_err(|e| {
+                warn!("Failed to set TCP_KEEPCNT: {:?}", &e);
+                net_error::ConnectionError
+            })?;
+        }
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        {
+            let _ = (interval_secs, probes);
+        }
+        Ok(())
+    }
+
     /// Poll all server sockets.
     /// Returns a map between network server handles (returned by bind()) and their new polling state
     pub fn poll(&mut self, timeout: u64) -> Result<HashMap<usize, NetworkPollState>, net_error> {
@@ -17,7 +17,8 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::io::prelude::*;
 use std::io::{Read, Seek, SeekFrom, Write};
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Instant;
 use std::{fmt, io};
 
@@ -38,6 +39,7 @@ use libstackerdb::{StackerDBChunkAckData, StackerDBChunkData};
 use rand::prelude::*;
 use rand::thread_rng;
 use rusqlite::{DatabaseName, NO_PARAMS};
+use serde::{Deserialize, Serialize};
 use stacks_common::codec::StacksMessageCodec;
 use stacks_common::types::chainstate::{
     BlockHeaderHash, BurnchainHeaderHash, StacksAddress, StacksBlockId,
@@ -45,9 +47,9 @@ use stacks_common::types::chainstate::{
 use stacks_common::types::net::{PeerAddress, PeerHost};
 use stacks_common::types::StacksPublicKeyBuffer;
 use stacks_common::util::chunked_encoding::*;
-use stacks_common::util::get_epoch_time_secs;
 use stacks_common::util::hash::{hex_bytes, to_hex, Hash160, Sha256Sum};
 use stacks_common::util::secp256k1::MessageSignature;
+use stacks_common::util::{get_epoch_time_ms, get_epoch_time_secs};
 use stacks_common::{types, util};
 
 use crate::burnchains::affirmation::AffirmationMap;
@@ -66,9 +68,13 @@ use crate::cost_estimates::{CostEstimator, FeeEstimator};
 use crate::net::atlas::{AtlasDB, Attachment, MAX_ATTACHMENT_INV_PAGES_PER_REQUEST};
 use crate::net::connection::{ConnectionHttp, ConnectionOptions, ReplyHandleHttp};
 use crate::net::db::PeerDB;
-use crate::net::http::{HttpRequestContents, HttpResponseContents};
+use crate::net::http::{
+    Error as HttpErr, HttpPayloadTooLarge, HttpRequestContents, HttpRequestPreamble,
+    HttpResponseContents, HttpVersion,
+};
 use crate::net::httpcore::{
-    StacksHttp, StacksHttpMessage, StacksHttpRequest, StacksHttpResponse, HTTP_REQUEST_ID_RESERVED,
+    HttpPreambleExtensions, StacksHttp, StacksHttpMessage, StacksHttpRequest, StacksHttpResponse,
+    HTTP_REQUEST_ID_RESERVED, REQUEST_TIMEOUT_MS,
 };
 use crate::net::p2p::{PeerMap, PeerNetwork};
 use crate::net::relay::Relayer;
@@ -81,6 +87,47 @@ use crate::{monitoring, version_string};
 
 pub const STREAM_CHUNK_SIZE: u64 = 4096;
 
+/// Maximum number of bytes of a malformed request to log, if `log_bad_requests` is set
+pub const MAX_BAD_REQUEST_LOG_BYTES: usize = 4096;
+
+/// Next correlation ID to hand out to an inbound HTTP request that did not bring its own.
+/// Shared by all conversations, so IDs are unique across connections.
+static NEXT_CORRELATION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// HTTP protocol details of a conversation, as last used by the client
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProtocolInfo {
+    /// HTTP version of the client's last request, if it has sent one
+    pub http_version: Option<HttpVersion>,
+    /// whether the connection will be kept open after the current reply
+    pub keep_alive: bool,
+    /// verb of the client's last request
+    pub last_method: Option<String>,
+    /// path of the client's last request, without the query string
+    pub last_path: Option<String>,
+}
+
+/// What a conversation needs to carry on in another `HttpPeer`, e.g. one in a new process that
+/// has been handed the conversation's socket.  Only idle conversations can be exported, so no
+/// buffered data needs to carry over.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConversationHttpState {
+    pub peer_addr: SocketAddr,
+    pub outbound_url: Option<UrlString>,
+    /// remote host's identifier, as it would go into the `Host:` header
+    pub peer_host: String,
+    pub keep_alive: bool,
+    pub total_request_count: u64,
+    pub total_reply_count: u64,
+    pub total_error_reply_count: u64,
+    pub total_bytes_received: u64,
+    pub total_bytes_sent: u64,
+    pub last_request_timestamp: u64,
+    pub last_response_timestamp: u64,
+    pub connection_time: u64,
+    pub canonical_stacks_tip_height: Option<u32>,
+}
+
 pub struct ConversationHttp {
     /// send/receive buffering state-machine for interfacing with a non-blocking socket
     connection: ConnectionHttp,
@@ -100,6 +147,12 @@ pub struct ConversationHttp {
     total_request_count: u64,
     /// number of messages sent
     total_reply_count: u64,
+    /// number of replies queued with a 400, 429, or 5xx status
+    total_error_reply_count: u64,
+    /// number of bytes received over this conversation's lifetime
+    total_bytes_received: u64,
+    /// number of bytes sent over this conversation's lifetime
+    total_bytes_sent: u64,
     /// absolute timestamp of the last time we recieved at least 1 byte
     last_request_timestamp: u64,
     /// absolute timestamp of the last time we sent at least 1 byte
@@ -108,16 +161,54 @@ pub struct ConversationHttp {
     connection_time: u64,
     /// stacks canonical chain tip that this peer reported
     canonical_stacks_tip_height: Option<u32>,
-    /// Ongoing replies
-    reply_streams: VecDeque<(ReplyHandleHttp, HttpResponseContents, bool)>,
+    /// Ongoing replies, in the order in which their requests arrived.  Each reply is written to
+    /// its own relay handle, whose pipe is queued in the connection's outbox when the handle is
+    /// made, and the outbox drains pipes strictly in queue order.  Only the front reply's body
+    /// is generated at a time.  So, pipelined responses are sent in request order, even when a
+    /// later response is ready while an earlier one is still streaming.
+    /// Each reply also records whether to keep the connection alive once it is sent, and the
+    /// deadline (in epoch milliseconds) by which the client needs it sent, if the client gave one.
+    reply_streams: VecDeque<(ReplyHandleHttp, HttpResponseContents, bool, Option<u128>)>,
     /// outstanding request
     pending_request: Option<ReplyHandleHttp>,
+    /// time (in seconds) the remote peer has to answer our outstanding request.  0 means forever.
+    outbound_request_timeout: u64,
+    /// absolute time by which the remote peer needs to have answered our outstanding request
+    request_deadline: Option<u64>,
+    /// verb and path of our outstanding request, so identical requests can share its response
+    pending_request_key: Option<(String, String)>,
+    /// how many callers besides the one that sent our outstanding request are waiting on its
+    /// response.  Each one gets a copy of it from `try_get_response()`.
+    extra_response_waiters: usize,
     /// outstanding response
     pending_response: Option<StacksHttpResponse>,
     /// whether or not there's an error response pending
     pending_error_response: bool,
+    /// whether or not we refused a request for its body size, and so stopped reading from the
+    /// client
+    refused_oversized_request: bool,
     /// how much data to buffer (i.e. the socket's send buffer size)
     socket_send_buffer_size: u32,
+    /// raw bytes received since the last complete request, up to `MAX_BAD_REQUEST_LOG_BYTES`.
+    /// Only kept if `log_bad_requests` is set, so they can be logged if the request is malformed.
+    recv_log: Option<Vec<u8>>,
+    /// protocol details of the last request handled
+    protocol_info: ProtocolInfo,
+}
+
+/// Reader that records the first `MAX_BAD_REQUEST_LOG_BYTES` bytes read through it
+struct RecordingReader<'a, R: Read> {
+    inner: &'a mut R,
+    log: &'a mut Vec<u8>,
+}
+
+impl<'a, R: Read> Read for RecordingReader<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let nread = self.inner.read(buf)?;
+        let nlog = nread.min(MAX_BAD_REQUEST_LOG_BYTES.saturating_sub(self.log.len()));
+        self.log.extend_from_slice(&buf[..nlog]);
+        Ok(nread)
+    }
 }
 
 impl fmt::Display for ConversationHttp {
@@ -164,15 +255,34 @@ impl ConversationHttp {
             peer_host,
             canonical_stacks_tip_height: None,
             pending_request: None,
+            outbound_request_timeout: conn_opts.outbound_request_timeout,
+            request_deadline: None,
+            pending_request_key: None,
+            extra_response_waiters: 0,
             pending_response: None,
             pending_error_response: false,
+            refused_oversized_request: false,
             keep_alive: true,
             total_request_count: 0,
             total_reply_count: 0,
+            total_error_reply_count: 0,
+            total_bytes_received: 0,
+            total_bytes_sent: 0,
             last_request_timestamp: 0,
             last_response_timestamp: 0,
             socket_send_buffer_size,
             connection_time: get_epoch_time_secs(),
+            recv_log: if conn_opts.log_bad_requests {
+                Some(vec![])
+            } else {
+                None
+            },
+            protocol_info: ProtocolInfo {
+                http_version: None,
+                keep_alive: true,
+                last_method: None,
+                last_path: None,
+            },
         }
     }
 
@@ -191,6 +301,16 @@ impl ConversationHttp {
         &self.peer_addr
     }
 
+    /// What's the IP address of the client we're serving?  This is the peer's, unless the peer
+    /// is a trusted reverse proxy that forwarded the last request for someone else (see
+    /// `trust_forwarded_for`).
+    pub fn get_client_ip(&self) -> IpAddr {
+        self.connection
+            .protocol
+            .forwarded_for
+            .unwrap_or(self.peer_addr.ip())
+    }
+
     /// Is a request in-progress?
     pub fn is_request_inflight(&self) -> bool {
         self.pending_request.is_some()
@@ -236,13 +356,48 @@ impl ConversationHttp {
             return Err(net_error::InProgress);
         }
 
+        let request_key = (req.verb().to_string(), req.request_path().to_string());
         let handle = self.start_request(req)?;
 
         self.pending_request = Some(handle);
+        self.pending_request_key = Some(request_key);
+        self.extra_response_waiters = 0;
         self.pending_response = None;
+        self.request_deadline = if self.outbound_request_timeout > 0 {
+            Some(get_epoch_time_secs().saturating_add(self.outbound_request_timeout))
+        } else {
+            None
+        };
         Ok(())
     }
 
+    /// Is our outstanding request a GET for the same path as `req`?  If so, `req` can share its
+    /// response instead of being sent, via `add_response_waiters()`.
+    pub fn is_same_request_inflight(&self, req: &StacksHttpRequest) -> bool {
+        req.verb() == "GET"
+            && self.is_request_inflight()
+            && self
+                .pending_request_key
+                .as_ref()
+                .map_or(false, |(verb, path)| {
+                    verb == req.verb() && path == req.request_path()
+                })
+    }
+
+    /// Have `count` more callers wait on our outstanding request's response.  Each one gets its
+    /// own copy of it from `try_get_response()`.
+    pub fn add_response_waiters(&mut self, count: usize) {
+        self.extra_response_waiters = self.extra_response_waiters.saturating_add(count);
+    }
+
+    /// Has our outstanding request gone unanswered for longer than `outbound_request_timeout`?
+    pub fn is_request_timed_out(&self, now: u64) -> bool {
+        self.is_request_inflight()
+            && self
+                .request_deadline
+                .map_or(false, |request_deadline| request_deadline < now)
+    }
+
     /// Send a HTTP error response.
     /// Discontinues and disables sending a non-error response.
     pub fn reply_error(&mut self, res: StacksHttpResponse) -> Result<(), net_error> {
@@ -259,7 +414,13 @@ impl ConversationHttp {
             return Ok(());
         }
 
-        let (preamble, body_contents) = res.try_into_contents()?;
+        let (mut preamble, body_contents) = res.try_into_contents()?;
+        if Self::is_error_status(preamble.status_code) {
+            self.total_error_reply_count += 1;
+        }
+        if let Some(cors) = self.connection.options.cors.as_ref() {
+            cors.add_headers(&mut preamble);
+        }
 
         // make the relay handle. There may not have been a valid request in the first place, so
         // we'll use a relay handle (not a reply handle) to push out the error.
@@ -267,23 +428,71 @@ impl ConversationHttp {
 
         // queue up the HTTP headers, and then stream back the body.
         preamble.consensus_serialize(&mut reply)?;
-        self.reply_streams.push_back((reply, body_contents, false));
+        self.reply_streams
+            .push_back((reply, body_contents, false, None));
         self.pending_error_response = true;
         Ok(())
     }
 
     /// Handle an external HTTP request.
+    /// The given correlation ID is echoed back to the client in the response headers.
     /// Returns a StacksMessageType option -- it's Some(...) if we need to forward a message to the
     /// peer network (like a transaction or a block or microblock)
     pub fn handle_request(
         &mut self,
-        req: StacksHttpRequest,
+        mut req: StacksHttpRequest,
+        correlation_id: u64,
         node: &mut StacksNodeState,
     ) -> Result<Option<StacksMessageType>, net_error> {
+        // so error responses built from the request report the same ID
+        req.preamble_mut().set_correlation_id(correlation_id);
+
         // NOTE: This may set node.relay_message
-        let keep_alive = req.preamble().keep_alive;
-        let (mut response_preamble, response_body) =
+        let mut keep_alive = req.preamble().keep_alive;
+        let deadline_ms = req
+            .preamble()
+            .get_header(REQUEST_TIMEOUT_MS.to_string())
+            .and_then(|timeout_ms| timeout_ms.trim().parse::<u64>().ok())
+            .map(|timeout_ms| get_epoch_time_ms().saturating_add(u128::from(timeout_ms)));
+        let client_http_version = req.preamble().version;
+        let accepts_gzip = req.preamble().accepts_gzip();
+        self.protocol_info = ProtocolInfo {
+            http_version: Some(client_http_version),
+            keep_alive,
+            last_method: Some(req.preamble().verb.clone()),
+            last_path: req
+                .preamble()
+                .path_and_query_str
+                .split('?')
+                .next()
+                .map(|path| path.to_string()),
+        };
+        let (mut response_preamble, mut response_body) =
             self.connection.protocol.try_handle_request(req, node)?;
+        if Self::is_error_status(response_preamble.status_code) {
+            self.total_error_reply_count += 1;
+        }
+        // Streamed bodies (i.e. blocks and microblocks) are the big ones, so compress those if
+        // the client can take it.  Live feeds are left alone, so each event goes out as it happens.
+        if accepts_gzip
+            && response_preamble.status_code == 200
+            && response_body.content_length().is_none()
+            && !response_body.is_live()
+        {
+            response_body = response_body.try_into_gzip()?;
+            response_preamble.add_header("Content-Encoding".to_string(), "gzip".to_string());
+        }
+        // HTTP/1.0 clients don't understand chunked transfer-encoding, so stream bodies to them
+        // as-is, and close the connection to mark the end.
+        if client_http_version == HttpVersion::Http10 && response_body.content_length().is_none() {
+            response_body = response_body.into_unchunked();
+            response_preamble.keep_alive = false;
+            keep_alive = false;
+        }
+        response_preamble.set_correlation_id(correlation_id);
+        if let Some(cors) = self.connection.options.cors.as_ref() {
+            cors.add_headers(&mut response_preamble);
+        }
 
         let mut reply = self.connection.make_relay_handle(self.conn_id)?;
         let relay_msg_opt = node.take_relay_message();
@@ -294,7 +503,7 @@ impl ConversationHttp {
         // buffer up response headers into the reply handle
         response_preamble.consensus_serialize(&mut reply)?;
         self.reply_streams
-            .push_back((reply, response_body, keep_alive));
+            .push_back((reply, response_body, keep_alive, deadline_ms));
         Ok(relay_msg_opt)
     }
 
@@ -313,7 +522,7 @@ impl ConversationHttp {
         );
         let _self_str = format!("{}", &self);
 
-        if let Some((ref mut reply, ref mut http_response, ref keep_alive)) =
+        if let Some((ref mut reply, ref mut http_response, ref keep_alive, _)) =
             self.reply_streams.front_mut()
         {
             do_keep_alive = *keep_alive;
@@ -323,6 +532,10 @@ impl ConversationHttp {
                 if let Some(pipe_fd) = reply.inner_pipe_out() {
                     let num_written = http_response.pipe_out(pipe_fd)?;
                     if num_written == 0 {
+                        if http_response.is_live() {
+                            // nothing to send right now, but there will be later
+                            break;
+                        }
                         // no more chunks
                         drained_stream = true;
                     }
@@ -449,6 +662,8 @@ impl ConversationHttp {
         self.pending_response = response;
 
         if in_progress && self.pending_request.is_none() {
+            self.request_deadline = None;
+            self.pending_request_key = None;
             test_debug!(
                 "{:?},id={}: HTTP request finished",
                 &self.peer_host,
@@ -467,8 +682,12 @@ impl ConversationHttp {
         Ok(())
     }
 
-    /// Try to get our response
+    /// Try to get our response.  If other callers are waiting on it too, they each get a copy.
     pub fn try_get_response(&mut self) -> Option<StacksHttpResponse> {
+        if self.extra_response_waiters > 0 && self.pending_response.is_some() {
+            self.extra_response_waiters -= 1;
+            return self.pending_response.clone();
+        }
         self.pending_response.take()
     }
 
@@ -495,11 +714,88 @@ impl ConversationHttp {
             && self.is_idle()
     }
 
+    /// Is this conversation streaming a live feed back to the client?  Such a reply is never
+    /// drained, so the conversation stays open until the client or the feed goes away.
+    pub fn has_live_reply(&self) -> bool {
+        self.reply_streams
+            .iter()
+            .any(|(_, response_body, ..)| response_body.is_live())
+    }
+
+    /// Is a reply still being sent after the deadline its client gave for it?
+    pub fn is_past_deadline(&self, now_ms: u128) -> bool {
+        self.reply_streams
+            .iter()
+            .any(|(.., deadline_ms)| deadline_ms.map_or(false, |deadline_ms| deadline_ms <= now_ms))
+    }
+
     /// Should the connection be kept alive even if drained?
     pub fn is_keep_alive(&self) -> bool {
         self.keep_alive
     }
 
+    /// What HTTP version, keep-alive setting, and request did the client last use?
+    pub fn protocol_info(&self) -> ProtocolInfo {
+        let mut info = self.protocol_info.clone();
+        info.keep_alive = info.keep_alive && self.keep_alive;
+        info
+    }
+
+    /// Snapshot this conversation's state, so it can be carried on elsewhere.
+    /// Returns None if it is in the middle of a request or reply, since buffered data and
+    /// in-flight replies can't be carried over.
+    pub fn export_state(&self) -> Option<ConversationHttpState> {
+        if !self.is_idle() || self.is_request_inflight() || self.pending_error_response {
+            return None;
+        }
+        Some(ConversationHttpState {
+            peer_addr: self.peer_addr.clone(),
+            outbound_url: self.outbound_url.clone(),
+            peer_host: format!("{}", &self.peer_host),
+            keep_alive: self.keep_alive,
+            total_request_count: self.total_request_count,
+            total_reply_count: self.total_reply_count,
+            total_error_reply_count: self.total_error_reply_count,
+            total_bytes_received: self.total_bytes_received,
+            total_bytes_sent: self.total_bytes_sent,
+            last_request_timestamp: self.last_request_timestamp,
+            last_response_timestamp: self.last_response_timestamp,
+            connection_time: self.connection_time,
+            canonical_stacks_tip_height: self.canonical_stacks_tip_height,
+        })
+    }
+
+    /// Carry on a conversation from a snapshot taken with `export_state()`
+    pub fn from_state(
+        state: ConversationHttpState,
+        conn_opts: &ConnectionOptions,
+        conn_id: usize,
+        socket_send_buffer_size: u32,
+    ) -> Result<ConversationHttp, net_error> {
+        let peer_host = state.peer_host.parse::<PeerHost>().map_err(|_| {
+            net_error::DeserializeError(format!("Invalid peer host '{}'", &state.peer_host))
+        })?;
+        let mut convo = ConversationHttp::new(
+            state.peer_addr,
+            state.outbound_url,
+            peer_host,
+            conn_opts,
+            conn_id,
+            socket_send_buffer_size,
+        );
+        convo.keep_alive = state.keep_alive;
+        convo.total_request_count = state.total_request_count;
+        convo.total_reply_count = state.total_reply_count;
+        convo.total_error_reply_count = state.total_error_reply_count;
+        convo.total_bytes_received = state.total_bytes_received;
+        convo.total_bytes_sent = state.total_bytes_sent;
+        convo.last_request_timestamp = state.last_request_timestamp;
+        convo.last_response_timestamp = state.last_response_timestamp;
+        convo.connection_time = state.connection_time;
+        convo.canonical_stacks_tip_height = state.canonical_stacks_tip_height;
+        Ok(convo)
+    }
+
     /// When was the last time we got an inbound request?
     pub fn get_last_request_time(&self) -> u64 {
         self.last_request_timestamp
@@ -516,12 +812,26 @@ impl ConversationHttp {
         self.connection_time
     }
 
+    /// Get the correlation ID for an inbound request.  If the client supplied one, then it is
+    /// reused so the ID persists across hops; otherwise, a fresh one is assigned.
+    fn next_correlation_id(preamble: &HttpRequestPreamble) -> u64 {
+        preamble
+            .get_correlation_id()
+            .unwrap_or_else(Self::fresh_correlation_id)
+    }
+
+    /// Assign a fresh correlation ID
+    fn fresh_correlation_id() -> u64 {
+        NEXT_CORRELATION_ID.fetch_add(1, Ordering::SeqCst)
+    }
+
     /// Make progress on in-flight requests and replies.
-    /// Returns the list of messages we'll need to forward to the peer network
+    /// Returns the list of messages we'll need to forward to the peer network, each paired with
+    /// the correlation ID of the request that produced it.
     pub fn chat(
         &mut self,
         node: &mut StacksNodeState,
-    ) -> Result<Vec<StacksMessageType>, net_error> {
+    ) -> Result<Vec<(u64, StacksMessageType)>, net_error> {
         // if we have an in-flight error, then don't take any more requests.
         if self.pending_error_response {
             return Ok(vec![]);
@@ -540,16 +850,20 @@ impl ConversationHttp {
             match msg {
                 StacksHttpMessage::Request(req) => {
                     // new request that we can handle
+                    if let Some(log) = self.recv_log.as_mut() {
+                        log.clear();
+                    }
                     self.total_request_count += 1;
                     self.last_request_timestamp = get_epoch_time_secs();
                     let latency = req.duration_ms();
                     let start_time = Instant::now();
                     let verb = req.verb().to_string();
                     let request_path = req.request_path().to_string();
+                    let correlation_id = Self::next_correlation_id(req.preamble());
                     let msg_opt = monitoring::instrument_http_request_handler(
                         self,
                         req,
-                        |conv_http, req| conv_http.handle_request(req, node),
+                        |conv_http, req| conv_http.handle_request(req, correlation_id, node),
                     )?;
 
                     info!("Handled StacksHTTPRequest";
@@ -558,14 +872,17 @@ impl ConversationHttp {
                            "processing_time_ms" => start_time.elapsed().as_millis(),
                            "latency_ms" => latency,
                            "conn_id" => self.conn_id,
-                           "peer_addr" => &self.peer_addr);
+                           "peer_addr" => &self.peer_addr,
+                           "correlation_id" => correlation_id);
 
                     if let Some(msg) = msg_opt {
-                        ret.push(msg);
+                        ret.push((correlation_id, msg));
                     }
                 }
-                StacksHttpMessage::Error(path, resp) => {
-                    // new request, but resulted in an error when parsing it
+                StacksHttpMessage::Error(path, mut resp) => {
+                    // new request, but resulted in an error when parsing it (so it has no
+                    // correlation ID yet)
+                    resp.fill_request_id(Self::fresh_correlation_id);
                     self.total_request_count += 1;
                     self.last_request_timestamp = get_epoch_time_secs();
                     let start_time = Instant::now();
@@ -601,19 +918,139 @@ impl ConversationHttp {
         self.connection.drain_timeouts();
     }
 
+    /// Hex-encoded raw bytes received since the last complete request (truncated to
+    /// `MAX_BAD_REQUEST_LOG_BYTES`), if `log_bad_requests` is set.
+    pub fn recv_log_hex(&self) -> Option<String> {
+        self.recv_log.as_ref().map(|bytes| to_hex(bytes))
+    }
+
+    /// Total number of requests received over this conversation's lifetime
+    pub fn requests_received(&self) -> u64 {
+        self.total_request_count
+    }
+
+    /// Total number of requests replied to over this conversation's lifetime
+    pub fn requests_served(&self) -> u64 {
+        self.total_reply_count
+    }
+
+    /// Total number of replies with a 400, 429, or 5xx status over this conversation's lifetime
+    pub fn error_responses_served(&self) -> u64 {
+        self.total_error_reply_count
+    }
+
+    /// Does a reply with this status count as an error?  These point at a misbehaving client or
+    /// an ailing server, unlike e.g. a 404 for a block we don't have.
+    fn is_error_status(status_code: u16) -> bool {
+        status_code == 400 || status_code == 429 || status_code >= 500
+    }
+
+    /// Total number of bytes received over this conversation's lifetime
+    pub fn bytes_received(&self) -> u64 {
+        self.total_bytes_received
+    }
+
+    /// Total number of bytes sent over this conversation's lifetime
+    pub fn bytes_sent(&self) -> u64 {
+        self.total_bytes_sent
+    }
+
+    /// Subject of the client's verified TLS certificate, if it presented one
+    pub fn get_client_subject(&self) -> Option<&str> {
+        self.connection.protocol.client_subject.as_deref()
+    }
+
+    /// Record the subject of the client's verified TLS certificate
+    pub fn set_client_subject(&mut self, subject: Option<String>) {
+        self.connection.protocol.client_subject = subject;
+    }
+
+    /// Record whether or not the node is in initial block download, and if so, how far along it
+    /// is (in percent)
+    pub fn set_ibd_progress(&mut self, ibd_progress: Option<u8>) {
+        self.connection.protocol.ibd_progress = ibd_progress;
+    }
+
+    /// Limit how many more requests this conversation handles before answering the rest with a
+    /// 429.  None means no limit.
+    pub fn set_request_allowance(&mut self, allowance: Option<u64>) {
+        self.connection.protocol.request_allowance = allowance;
+    }
+
+    /// How many received messages are waiting to be handled by `chat()`?
+    pub fn num_pending_inbound(&self) -> usize {
+        self.connection.inbox_len()
+    }
+
+    /// How many bytes is this conversation holding in memory right now?
+    /// Counts unparsed inbound data, unsent outbound data, and the bad-request log.
+    pub fn buffered_bytes(&self) -> usize {
+        self.connection.buffered_bytes() + self.recv_log.as_ref().map(|log| log.len()).unwrap_or(0)
+    }
+
+    /// Shrink this conversation's socket buffers down to a small size, e.g. because it has been
+    /// idle.  No buffered data is lost, and the buffers grow back as needed.
+    pub fn shrink_buffers(&mut self) {
+        self.connection.shrink_buffers();
+    }
+
+    /// How many bytes are allocated for this conversation's socket buffers?
+    pub fn buffer_capacity(&self) -> usize {
+        self.connection.buffer_capacity()
+    }
+
     /// Load data into our HTTP connection
     pub fn recv<R: Read>(&mut self, r: &mut R) -> Result<usize, net_error> {
+        if self.refused_oversized_request {
+            // the rest of what the client sends is the body we refused
+            return Ok(0);
+        }
         let mut total_recv = 0;
         loop {
-            let nrecv = match self.connection.recv_data(r) {
+            let recv_res = match self.recv_log.as_mut() {
+                Some(log) => self
+                    .connection
+                    .recv_data(&mut RecordingReader { inner: r, log }),
+                None => self.connection.recv_data(r),
+            };
+            let nrecv = match recv_res {
                 Ok(nr) => nr,
+                Err(net_error::Http(HttpErr::Http(413, msg))) => {
+                    // the request declared a body bigger than `max_request_body_len`.  Refuse it
+                    // without reading any further, and hang up once the refusal is sent.
+                    let Some(mut preamble) = self.connection.protocol.take_oversized_request()
+                    else {
+                        return Err(net_error::Http(HttpErr::Http(413, msg)));
+                    };
+                    let correlation_id = Self::next_correlation_id(&preamble);
+                    preamble.set_correlation_id(correlation_id);
+                    debug!("{:?}: refusing oversized request: {}", self, &msg);
+                    self.total_request_count += 1;
+                    self.refused_oversized_request = true;
+                    self.keep_alive = false;
+                    self.reply_error(StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpPayloadTooLarge::new(msg),
+                    ))?;
+                    break;
+                }
                 Err(e) => {
                     debug!("{:?}: failed to recv: {:?}", self, &e);
+                    if let (net_error::InvalidMessage | net_error::DeserializeError(_), Some(hex)) =
+                        (&e, self.recv_log_hex())
+                    {
+                        debug!("Malformed HTTP request";
+                               "conn_id" => self.conn_id,
+                               "peer_addr" => &self.peer_addr,
+                               "bytes" => %hex,
+                               "truncated" => hex.len() / 2 >= MAX_BAD_REQUEST_LOG_BYTES);
+                    }
                     return Err(e);
                 }
             };
 
             total_recv += nrecv;
+            self.total_bytes_received += nrecv as u64;
             if nrecv > 0 {
                 self.last_request_timestamp = get_epoch_time_secs();
             } else {
@@ -645,6 +1082,7 @@ impl ConversationHttp {
             test_debug!("{:?}: Sent {} bytes (total {})", self, sz, total_sz);
 
             total_sz += sz;
+            self.total_bytes_sent += sz as u64;
             if sz > 0 {
                 self.last_response_timestamp = get_epoch_time_secs();
             } else {
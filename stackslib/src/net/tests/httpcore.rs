@@ -37,16 +37,16 @@ use crate::chainstate::stacks::{
 use crate::net::api::getneighbors::{RPCNeighbor, RPCNeighborsInfo};
 use crate::net::connection::ConnectionOptions;
 use crate::net::http::{
-    http_error_from_code_and_text, http_reason, HttpContentType, HttpErrorResponse,
-    HttpRequestContents, HttpRequestPreamble, HttpReservedHeader, HttpResponsePreamble,
-    HttpVersion, HTTP_PREAMBLE_MAX_NUM_HEADERS,
+    http_error_from_code_and_text, http_reason, Error as HttpError, HttpContentType,
+    HttpErrorResponse, HttpRequestContents, HttpRequestPreamble, HttpReservedHeader,
+    HttpResponsePayload, HttpResponsePreamble, HttpVersion, HTTP_PREAMBLE_MAX_NUM_HEADERS,
 };
 use crate::net::httpcore::{
-    HttpPreambleExtensions, HttpRequestContentsExtensions, StacksHttp, StacksHttpMessage,
-    StacksHttpPreamble, StacksHttpRequest, StacksHttpResponse,
+    forwarded_client_ip, secrets_equal, HttpPreambleExtensions, HttpRequestContentsExtensions,
+    StacksHttp, StacksHttpMessage, StacksHttpPreamble, StacksHttpRequest, StacksHttpResponse,
 };
 use crate::net::rpc::ConversationHttp;
-use crate::net::{ProtocolFamily, TipRequest};
+use crate::net::{Error as NetError, ProtocolFamily, TipRequest};
 
 #[test]
 fn test_parse_stacks_http_preamble_request_err() {
@@ -1118,3 +1118,271 @@ fn test_metrics_identifiers() {
         assert_eq!(response_handler_index.is_some(), should_have_handler);
     }
 }
+
+#[test]
+fn test_http_privileged_endpoint_auth() {
+    let mut conn_opts = ConnectionOptions::default();
+    conn_opts.auth_token = Some("secret".to_string());
+    conn_opts
+        .privileged_endpoints
+        .insert("/v2/info".to_string());
+    let mut http = StacksHttp::new("127.0.0.1:12345".parse().unwrap(), &conn_opts);
+
+    let make_preamble = |path: &str, auth: Option<&str>| {
+        let mut preamble = HttpRequestPreamble::new(
+            HttpVersion::Http11,
+            "GET".to_string(),
+            path.to_string(),
+            "localhost".to_string(),
+            12345,
+            true,
+        );
+        if let Some(auth) = auth {
+            preamble.add_header("Authorization".to_string(), auth.to_string());
+        }
+        preamble
+    };
+    let expect_unauthorized = |res: Result<StacksHttpRequest, NetError>| match res {
+        Err(NetError::Http(HttpError::Http(401, _))) => {}
+        res => panic!("Expected a 401, got {:?}", &res),
+    };
+
+    // no token, wrong token, and wrong scheme are all turned away
+    expect_unauthorized(http.try_parse_request(&make_preamble("/v2/info", None), &[]));
+    expect_unauthorized(
+        http.try_parse_request(&make_preamble("/v2/info", Some("Bearer wrong")), &[]),
+    );
+    expect_unauthorized(http.try_parse_request(&make_preamble("/v2/info", Some("secret")), &[]));
+
+    // the right token is let through
+    http.try_parse_request(&make_preamble("/v2/info", Some("Bearer secret")), &[])
+        .unwrap();
+
+    // public endpoints need no token
+    http.try_parse_request(&make_preamble("/v2/pox", None), &[])
+        .unwrap();
+
+    // privileged endpoints are unreachable if there's no token to check against
+    conn_opts.auth_token = None;
+    let mut http = StacksHttp::new("127.0.0.1:12345".parse().unwrap(), &conn_opts);
+    expect_unauthorized(
+        http.try_parse_request(&make_preamble("/v2/info", Some("Bearer secret")), &[]),
+    );
+}
+
+#[test]
+fn test_secrets_equal() {
+    assert!(secrets_equal("secret-token", "secret-token"));
+    assert!(secrets_equal("", ""));
+    assert!(!secrets_equal("secret-token", "secret-tokem"));
+    assert!(!secrets_equal("secret-token", "secret-token2"));
+    assert!(!secrets_equal("secret-token", ""));
+}
+
+#[test]
+fn test_http_json_error_envelope() {
+    let bad_requests = vec![
+        // 400: unexpected body
+        (
+            "GET /v2/pox HTTP/1.1\r\nUser-Agent: stacks/2.0\r\nHost: bad:123\r\nAccept: application/json\r\nContent-Length: 1\r\n\r\nb",
+            400,
+            "bad_request",
+        ),
+        // 404: no such endpoint
+        (
+            "GET /v2/nope HTTP/1.1\r\nUser-Agent: stacks/2.0\r\nHost: bad:123\r\nAccept: application/json\r\nX-Correlation-Id: 1234\r\n\r\n",
+            404,
+            "not_found",
+        ),
+    ];
+    for (bad_request, status_code, error_name) in bad_requests {
+        let mut http = StacksHttp::new(
+            "127.0.0.1:20443".parse().unwrap(),
+            &ConnectionOptions::default(),
+        );
+        let (preamble, offset) = http.read_preamble(bad_request.as_bytes()).unwrap();
+        let (msg, _) = http
+            .read_payload(&preamble, &bad_request.as_bytes()[offset..])
+            .unwrap();
+        let StacksHttpMessage::Error(_, mut resp) = msg else {
+            panic!("Expected an error response, got {:?}", &msg);
+        };
+
+        assert_eq!(resp.preamble().status_code, status_code);
+        assert_eq!(resp.preamble().content_type, HttpContentType::JSON);
+
+        let HttpResponsePayload::JSON(envelope) = resp.body() else {
+            panic!("Expected a JSON body, got {:?}", resp.body());
+        };
+        assert_eq!(envelope["error"], error_name);
+        assert!(envelope["message"].as_str().unwrap().len() > 0);
+
+        if status_code == 404 {
+            // the client's correlation ID is echoed back
+            assert_eq!(envelope["request_id"], "1234");
+            assert_eq!(resp.preamble().get_correlation_id(), Some(1234));
+        } else {
+            // no ID until the caller assigns one
+            assert!(envelope["request_id"].is_null());
+            assert!(resp.preamble().get_correlation_id().is_none());
+        }
+
+        // the caller's ID is only used if there wasn't one already
+        resp.fill_request_id(|| 5678);
+        let correlation_id = resp.preamble().get_correlation_id().unwrap();
+        let HttpResponsePayload::JSON(envelope) = resp.body() else {
+            panic!("Expected a JSON body, got {:?}", resp.body());
+        };
+        assert_eq!(envelope["request_id"], format!("{}", correlation_id));
+        assert_eq!(correlation_id, if status_code == 404 { 1234 } else { 5678 });
+    }
+
+    let accept_headers = vec![
+        ("", HttpContentType::Text),
+        ("Accept: */*\r\n", HttpContentType::Text),
+        ("Accept: text/plain\r\n", HttpContentType::Text),
+        (
+            "Accept: text/plain, application/json;q=0.5\r\n",
+            HttpContentType::Text,
+        ),
+        ("Accept: application/json;q=0\r\n", HttpContentType::Text),
+        (
+            "Accept: text/html, application/json\r\n",
+            HttpContentType::JSON,
+        ),
+        (
+            "Accept: text/plain, application/json\r\n",
+            HttpContentType::JSON,
+        ),
+        (
+            "Accept: text/plain;q=0.2, application/json;q=0.9\r\n",
+            HttpContentType::JSON,
+        ),
+    ];
+    for (accept, content_type) in accept_headers {
+        let request = format!(
+            "GET /v2/nope HTTP/1.1\r\nUser-Agent: stacks/2.0\r\nHost: bad:123\r\n{}\r\n",
+            accept
+        );
+        let mut http = StacksHttp::new(
+            "127.0.0.1:20443".parse().unwrap(),
+            &ConnectionOptions::default(),
+        );
+        let (preamble, offset) = http.read_preamble(request.as_bytes()).unwrap();
+        let (msg, _) = http
+            .read_payload(&preamble, &request.as_bytes()[offset..])
+            .unwrap();
+        let StacksHttpMessage::Error(_, mut resp) = msg else {
+            panic!("Expected an error response, got {:?}", &msg);
+        };
+        assert_eq!(resp.preamble().status_code, 404);
+        assert_eq!(resp.preamble().content_type, content_type, "{}", accept);
+
+        // plain text errors have no envelope to fill in
+        if content_type == HttpContentType::Text {
+            resp.fill_request_id(|| 5678);
+            assert!(resp.preamble().get_correlation_id().is_none());
+        }
+    }
+}
+
+#[test]
+fn test_http_unknown_path_not_found() {
+    let request =
+        "GET /v2/nope?tip=latest HTTP/1.1\r\nUser-Agent: stacks/2.0\r\nHost: bad:123\r\n\r\n";
+    let mut http = StacksHttp::new(
+        "127.0.0.1:20443".parse().unwrap(),
+        &ConnectionOptions::default(),
+    );
+    let (preamble, offset) = http.read_preamble(request.as_bytes()).unwrap();
+    let (msg, _) = http
+        .read_payload(&preamble, &request.as_bytes()[offset..])
+        .unwrap();
+    let StacksHttpMessage::Error(_, resp) = msg else {
+        panic!("Expected an error response, got {:?}", &msg);
+    };
+    assert_eq!(resp.preamble().status_code, 404);
+
+    // the attempted path is echoed back
+    let HttpResponsePayload::Text(text) = resp.body() else {
+        panic!("Expected a text body, got {:?}", resp.body());
+    };
+    assert_eq!(text, "No such API endpoint 'GET /v2/nope'");
+}
+
+#[test]
+fn test_forwarded_client_ip() {
+    // 10.0.0.0/8 and 192.168.1.1
+    let trusted_proxies = vec![
+        (PeerAddress::from_ipv4(10, 0, 0, 0), 104),
+        (PeerAddress::from_ipv4(192, 168, 1, 1), 128),
+    ];
+    let proxy = "10.1.2.3".parse().unwrap();
+
+    // an untrusted peer can't claim to be forwarding for anyone
+    assert_eq!(
+        forwarded_client_ip(&"1.2.3.4".parse().unwrap(), "5.6.7.8", &trusted_proxies),
+        None
+    );
+
+    // a trusted proxy's client is believed
+    assert_eq!(
+        forwarded_client_ip(&proxy, "5.6.7.8", &trusted_proxies),
+        Some("5.6.7.8".parse().unwrap())
+    );
+
+    // the client can't hide behind addresses of its own, but trusted proxies are skipped
+    assert_eq!(
+        forwarded_client_ip(
+            &proxy,
+            "9.9.9.9, 5.6.7.8, 192.168.1.1, 10.0.0.1",
+            &trusted_proxies
+        ),
+        Some("5.6.7.8".parse().unwrap())
+    );
+
+    // ports and IPv6 addresses are understood
+    assert_eq!(
+        forwarded_client_ip(&proxy, "5.6.7.8:1234", &trusted_proxies),
+        Some("5.6.7.8".parse().unwrap())
+    );
+    assert_eq!(
+        forwarded_client_ip(&proxy, "2001:db8::1", &trusted_proxies),
+        Some("2001:db8::1".parse().unwrap())
+    );
+
+    // stop at a hop we can't parse
+    assert_eq!(
+        forwarded_client_ip(&proxy, "5.6.7.8, unknown, 10.0.0.1", &trusted_proxies),
+        Some("10.0.0.1".parse().unwrap())
+    );
+    assert_eq!(
+        forwarded_client_ip(&proxy, "unknown", &trusted_proxies),
+        None
+    );
+}
+
+#[test]
+fn test_http_forwarded_for() {
+    let request =
+        "GET /v2/info HTTP/1.1\r\nHost: localhost:20443\r\nX-Forwarded-For: 5.6.7.8\r\n\r\n";
+    let mut conn_opts = ConnectionOptions::default();
+    conn_opts.trusted_proxies = vec![(PeerAddress::from_ipv4(127, 0, 0, 1), 128)];
+
+    // not believed unless enabled
+    let mut http = StacksHttp::new("127.0.0.1:20443".parse().unwrap(), &conn_opts);
+    http.read_preamble(request.as_bytes()).unwrap();
+    assert_eq!(http.forwarded_for, None);
+
+    conn_opts.trust_forwarded_for = true;
+
+    // a spoofed header from an untrusted peer is ignored
+    let mut http = StacksHttp::new("1.2.3.4:20443".parse().unwrap(), &conn_opts);
+    http.read_preamble(request.as_bytes()).unwrap();
+    assert_eq!(http.forwarded_for, None);
+
+    // the header from a trusted proxy names the client
+    let mut http = StacksHttp::new("127.0.0.1:20443".parse().unwrap(), &conn_opts);
+    http.read_preamble(request.as_bytes()).unwrap();
+    assert_eq!(http.forwarded_for, Some("5.6.7.8".parse().unwrap()));
+}
@@ -0,0 +1,437 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::sync::Arc;
+use std::{fmt, io};
+
+use mio::net as mio_net;
+use rustls::server::{AllowAnyAnonymousOrAuthenticatedClient, AllowAnyAuthenticatedClient};
+use rustls::{Certificate, PrivateKey, RootCertStore, ServerConfig, ServerConnection};
+use rustls_pemfile::Item;
+use stacks_common::util::hash::to_hex;
+
+use crate::net::Error as net_error;
+
+/// Where to find the certificate chain and private key with which the HTTP server speaks TLS
+#[derive(Debug, Clone, PartialEq)]
+pub struct TlsConfig {
+    /// path to a PEM file with the server's certificate chain, leaf certificate first
+    pub cert_path: String,
+    /// path to a PEM file with the server's private key (PKCS#8, PKCS#1, or SEC1)
+    pub key_path: String,
+    /// path to a PEM file with the CA certificates that client certificates are verified
+    /// against.  If unset, clients are not asked for certificates.
+    pub client_ca_path: Option<String>,
+    /// if true (and `client_ca_path` is set), clients without a valid certificate are rejected
+    /// during the handshake.  Otherwise, they may connect, but without a verified identity.
+    pub require_client_cert: bool,
+}
+
+/// Read every certificate in a PEM file
+fn read_certs(path: &str) -> Result<Vec<Certificate>, net_error> {
+    let file = File::open(path)
+        .map_err(|e| net_error::TlsError(format!("Failed to open {}: {:?}", path, &e)))?;
+    let certs: Vec<_> = rustls_pemfile::certs(&mut BufReader::new(file))
+        .map_err(|e| {
+            net_error::TlsError(format!(
+                "Failed to read certificates from {}: {:?}",
+                path, &e
+            ))
+        })?
+        .into_iter()
+        .map(Certificate)
+        .collect();
+    if certs.is_empty() {
+        return Err(net_error::TlsError(format!(
+            "No certificates found in {}",
+            path
+        )));
+    }
+    Ok(certs)
+}
+
+impl TlsConfig {
+    /// Load the certificate chain and private key from disk
+    pub fn load(&self) -> Result<TlsAcceptor, net_error> {
+        let certs = read_certs(&self.cert_path)?;
+
+        let key_file = File::open(&self.key_path).map_err(|e| {
+            net_error::TlsError(format!("Failed to open {}: {:?}", &self.key_path, &e))
+        })?;
+        let key = rustls_pemfile::read_all(&mut BufReader::new(key_file))
+            .map_err(|e| {
+                net_error::TlsError(format!(
+                    "Failed to read private key from {}: {:?}",
+                    &self.key_path, &e
+                ))
+            })?
+            .into_iter()
+            .find_map(|item| match item {
+                Item::PKCS8Key(key) | Item::RSAKey(key) | Item::ECKey(key) => Some(PrivateKey(key)),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                net_error::TlsError(format!("No private key found in {}", &self.key_path))
+            })?;
+
+        let builder = ServerConfig::builder().with_safe_defaults();
+        let builder = match self.client_ca_path.as_ref() {
+            Some(client_ca_path) => {
+                let mut roots = RootCertStore::empty();
+                for ca_cert in read_certs(client_ca_path)?.iter() {
+                    roots.add(ca_cert).map_err(|e| {
+                        net_error::TlsError(format!(
+                            "Invalid CA certificate in {}: {:?}",
+                            client_ca_path, &e
+                        ))
+                    })?;
+                }
+                if self.require_client_cert {
+                    builder
+                        .with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots).boxed())
+                } else {
+                    builder.with_client_cert_verifier(
+                        AllowAnyAnonymousOrAuthenticatedClient::new(roots).boxed(),
+                    )
+                }
+            }
+            None => builder.with_no_client_auth(),
+        };
+        let config = builder
+            .with_single_cert(certs, key)
+            .map_err(|e| net_error::TlsError(format!("Invalid certificate or key: {:?}", &e)))?;
+
+        Ok(TlsAcceptor {
+            config: Arc::new(config),
+        })
+    }
+}
+
+/// Starts TLS sessions on accepted sockets
+#[derive(Clone)]
+pub struct TlsAcceptor {
+    config: Arc<ServerConfig>,
+}
+
+impl fmt::Debug for TlsAcceptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TlsAcceptor")
+    }
+}
+
+impl TlsAcceptor {
+    /// Begin a server-side TLS session.  The handshake happens as data is read from and written
+    /// to the socket via `TlsSession::stream()`.
+    pub fn accept(&self) -> Result<TlsSession, net_error> {
+        let conn = ServerConnection::new(self.config.clone())
+            .map_err(|e| net_error::TlsError(format!("Failed to start TLS session: {:?}", &e)))?;
+        Ok(TlsSession { conn })
+    }
+}
+
+/// Server-side TLS state for a single socket
+pub struct TlsSession {
+    conn: ServerConnection,
+}
+
+impl fmt::Debug for TlsSession {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TlsSession(handshaking={})", self.conn.is_handshaking())
+    }
+}
+
+impl TlsSession {
+    /// Pair this session with its socket, so plaintext can be read and written over it
+    pub fn stream<'a>(&'a mut self, sock: &'a mut mio_net::TcpStream) -> TlsStream<'a> {
+        TlsStream {
+            conn: &mut self.conn,
+            sock,
+        }
+    }
+
+    /// Make as much progress on the handshake as the socket allows, without consuming any
+    /// plaintext.  This way, the client's identity is known before its first request is read.
+    /// Fails if the handshake fails (e.g. the client's certificate is not valid).
+    pub fn complete_handshake(&mut self, sock: &mut mio_net::TcpStream) -> io::Result<()> {
+        while self.conn.is_handshaking() {
+            self.stream(sock).write_tls()?;
+            match self.conn.read_tls(sock) {
+                // let the next read see the EOF
+                Ok(0) => return Ok(()),
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                Err(e) => return Err(e),
+            }
+            if let Err(e) = self.conn.process_new_packets() {
+                // try to tell the peer why we're hanging up
+                let _ = self.stream(sock).write_tls();
+                return Err(io::Error::new(io::ErrorKind::InvalidData, e));
+            }
+        }
+        self.stream(sock).write_tls()
+    }
+
+    /// The subject of the client's certificate, if it presented one.  rustls only completes
+    /// the handshake if the certificate was verified against the configured client CA.
+    pub fn client_subject(&self) -> Option<String> {
+        if self.conn.is_handshaking() {
+            return None;
+        }
+        let cert = self.conn.peer_certificates()?.first()?;
+        certificate_subject(&cert.0)
+    }
+}
+
+/// Split a DER-encoded value into its tag, its contents, and whatever follows it
+fn der_split(der: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = der.split_first()?;
+    let (&len_byte, rest) = rest.split_first()?;
+    let (len, rest) = if len_byte < 0x80 {
+        (usize::from(len_byte), rest)
+    } else {
+        let num_len_bytes = usize::from(len_byte & 0x7f);
+        if num_len_bytes == 0 || num_len_bytes > 4 || rest.len() < num_len_bytes {
+            return None;
+        }
+        let (len_bytes, rest) = rest.split_at(num_len_bytes);
+        let len = len_bytes
+            .iter()
+            .fold(0usize, |len, byte| (len << 8) | usize::from(*byte));
+        (len, rest)
+    };
+    if rest.len() < len {
+        return None;
+    }
+    let (contents, rest) = rest.split_at(len);
+    Some((tag, contents, rest))
+}
+
+/// The short name that RFC 4514 gives an X.509 attribute type, if it has one
+fn attribute_type_short_name(oid: &[u8]) -> Option<&'static str> {
+    match oid {
+        [0x55, 0x04, 0x03] => Some("CN"),
+        [0x55, 0x04, 0x06] => Some("C"),
+        [0x55, 0x04, 0x07] => Some("L"),
+        [0x55, 0x04, 0x08] => Some("ST"),
+        [0x55, 0x04, 0x09] => Some("STREET"),
+        [0x55, 0x04, 0x0a] => Some("O"),
+        [0x55, 0x04, 0x0b] => Some("OU"),
+        [0x09, 0x92, 0x26, 0x89, 0x93, 0xf2, 0x2c, 0x64, 0x01, 0x19] => Some("DC"),
+        [0x09, 0x92, 0x26, 0x89, 0x93, 0xf2, 0x2c, 0x64, 0x01, 0x01] => Some("UID"),
+        _ => None,
+    }
+}
+
+/// Render a DER-encoded OID in dotted-decimal form
+fn oid_dotted_decimal(oid: &[u8]) -> String {
+    let mut arcs = vec![];
+    let mut arc = 0u64;
+    for byte in oid.iter() {
+        arc = (arc << 7) | u64::from(byte & 0x7f);
+        if byte & 0x80 == 0 {
+            if arcs.is_empty() {
+                let first = (arc / 40).min(2);
+                arcs.push(first);
+                arcs.push(arc - first * 40);
+            } else {
+                arcs.push(arc);
+            }
+            arc = 0;
+        }
+    }
+    arcs.iter()
+        .map(|arc| arc.to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Decode a DER-encoded attribute value, if it is a string type that holds UTF-8 (or ASCII)
+fn attribute_value_string(tag: u8, contents: &[u8]) -> Option<String> {
+    const UTF8_STRING: u8 = 0x0c;
+    const PRINTABLE_STRING: u8 = 0x13;
+    const IA5_STRING: u8 = 0x16;
+    match tag {
+        UTF8_STRING | PRINTABLE_STRING | IA5_STRING => String::from_utf8(contents.to_vec()).ok(),
+        _ => None,
+    }
+}
+
+/// Escape an attribute value per RFC 4514, so that it can't be mistaken for the separators
+/// between attributes (or for a hex-encoded value)
+fn escape_attribute_value(value: &str) -> String {
+    let last = value.chars().count().saturating_sub(1);
+    let mut escaped = String::with_capacity(value.len());
+    for (i, c) in value.chars().enumerate() {
+        match c {
+            '"' | '+' | ',' | ';' | '<' | '>' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\0' => escaped.push_str("\\00"),
+            '#' if i == 0 => escaped.push_str("\\#"),
+            ' ' if i == 0 || i == last => escaped.push_str("\\ "),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Get the subject of a DER-encoded X.509 certificate as an RFC 4514 distinguished name
+/// string, which is what `openssl x509 -noout -subject -nameopt RFC2253` prints (e.g.
+/// `CN=operator,O=Example`).  Per RFC 4514, the RDNs are listed in the reverse of their order
+/// in the certificate, attribute values are escaped, and values which aren't strings (or
+/// whose types have no short name) are hex-encoded.  Returns None if the certificate can't be
+/// decoded.
+pub fn certificate_subject(der: &[u8]) -> Option<String> {
+    const SEQUENCE: u8 = 0x30;
+    const SET: u8 = 0x31;
+    const OID: u8 = 0x06;
+    const EXPLICIT_VERSION: u8 = 0xa0;
+
+    let (SEQUENCE, cert, _) = der_split(der)? else {
+        return None;
+    };
+    let (SEQUENCE, tbs, _) = der_split(cert)? else {
+        return None;
+    };
+    // skip the version (if present), serial number, signature algorithm, issuer, and validity
+    let (tag, _, mut rest) = der_split(tbs)?;
+    if tag == EXPLICIT_VERSION {
+        (_, _, rest) = der_split(rest)?;
+    }
+    for _ in 0..3 {
+        (_, _, rest) = der_split(rest)?;
+    }
+    let (SEQUENCE, mut rdns, _) = der_split(rest)? else {
+        return None;
+    };
+
+    let mut rendered_rdns = vec![];
+    while !rdns.is_empty() {
+        let (SET, mut attrs, next_rdn) = der_split(rdns)? else {
+            return None;
+        };
+        let mut rendered_attrs = vec![];
+        while !attrs.is_empty() {
+            let (SEQUENCE, attr, next_attr) = der_split(attrs)? else {
+                return None;
+            };
+            let (OID, oid, value) = der_split(attr)? else {
+                return None;
+            };
+            let (tag, contents, []) = der_split(value)? else {
+                return None;
+            };
+            let short_name = attribute_type_short_name(oid);
+            let rendered_value = short_name
+                .and_then(|_| attribute_value_string(tag, contents))
+                .map(|value| escape_attribute_value(&value))
+                .unwrap_or_else(|| format!("#{}", to_hex(value)));
+            rendered_attrs.push(format!(
+                "{}={}",
+                short_name
+                    .map(String::from)
+                    .unwrap_or_else(|| oid_dotted_decimal(oid)),
+                rendered_value
+            ));
+            attrs = next_attr;
+        }
+        rendered_rdns.push(rendered_attrs.join("+"));
+        rdns = next_rdn;
+    }
+    rendered_rdns.reverse();
+    Some(rendered_rdns.join(","))
+}
+
+/// Plaintext view of a non-blocking TLS socket.  Like the socket itself, reads and writes fail
+/// with `ErrorKind::WouldBlock` when no progress can be made, so this can be used in place of
+/// the socket by `ConversationHttp::recv()` and `ConversationHttp::send()`.
+pub struct TlsStream<'a> {
+    conn: &'a mut ServerConnection,
+    sock: &'a mut mio_net::TcpStream,
+}
+
+impl fmt::Debug for TlsStream<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TlsStream({:?})", &self.sock)
+    }
+}
+
+impl TlsStream<'_> {
+    /// Write out as much pending ciphertext (including handshake messages) as the socket will
+    /// take right now.
+    fn write_tls(&mut self) -> io::Result<()> {
+        while self.conn.wants_write() {
+            match self.conn.write_tls(self.sock) {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Read for TlsStream<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            // hand back plaintext we already decrypted.  Ok(0) means the peer sent close_notify.
+            match self.conn.reader().read(buf) {
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                res => return res,
+            }
+
+            // need more ciphertext.  Passes through WouldBlock if there isn't any.
+            if self.conn.read_tls(self.sock)? == 0 {
+                return Ok(0);
+            }
+            if let Err(e) = self.conn.process_new_packets() {
+                // try to tell the peer why we're hanging up
+                let _ = self.write_tls();
+                return Err(io::Error::new(io::ErrorKind::InvalidData, e));
+            }
+
+            // the handshake may need a reply
+            self.write_tls()?;
+        }
+    }
+}
+
+impl Write for TlsStream<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // don't buffer up more plaintext while the socket is backed up
+        self.write_tls()?;
+        if self.conn.wants_write() {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+
+        let nwritten = self.conn.writer().write(buf)?;
+        if nwritten == 0 && !buf.is_empty() {
+            // rustls's buffer is full (e.g. we're still handshaking)
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        self.write_tls()?;
+        Ok(nwritten)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_tls()?;
+        self.sock.flush()
+    }
+}
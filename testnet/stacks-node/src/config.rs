@@ -1,5 +1,5 @@
 use std::collections::{HashMap, HashSet};
-use std::net::{Ipv4Addr, SocketAddr, ToSocketAddrs};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, ToSocketAddrs};
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
@@ -33,7 +33,8 @@ use stacks::cost_estimates::fee_scalar::ScalarFeeRateEstimator;
 use stacks::cost_estimates::metrics::{CostMetric, ProportionalDotProduct, UnitMetric};
 use stacks::cost_estimates::{CostEstimator, FeeEstimator, PessimisticEstimator, UnitEstimator};
 use stacks::net::atlas::AtlasConfig;
-use stacks::net::connection::ConnectionOptions;
+use stacks::net::connection::{ConnectionOptions, CorsConfig};
+use stacks::net::tls::TlsConfig;
 use stacks::net::{Neighbor, NeighborKey};
 use stacks::types::chainstate::BurnchainHeaderHash;
 use stacks::util_lib::boot::boot_code_id;
@@ -2390,6 +2391,63 @@ pub struct ConnectionOptionsFile {
     pub private_neighbors: Option<bool>,
     pub block_proposal_token: Option<String>,
     pub antientropy_retry: Option<u64>,
+    pub max_connecting_lifetime: Option<u64>,
+    pub max_sockets_per_poll: Option<u64>,
+    pub max_outstanding_outbound_requests: Option<u64>,
+    pub log_bad_requests: Option<bool>,
+    pub tls_cert_path: Option<String>,
+    pub tls_key_path: Option<String>,
+    pub tls_client_ca_path: Option<String>,
+    pub tls_require_client_cert: Option<bool>,
+    pub auth_token: Option<String>,
+    pub privileged_endpoints: Option<String>,
+    pub enable_metrics_endpoint: Option<bool>,
+    pub max_work_per_run: Option<u64>,
+    pub close_linger_ms: Option<u64>,
+    pub cors_allow_origin: Option<String>,
+    pub cors_allow_methods: Option<String>,
+    pub cors_allow_headers: Option<String>,
+    pub max_accepts_per_sec: Option<u64>,
+    pub privileged_client_subjects: Option<Vec<String>>,
+    pub http_banned_hosts: Option<String>,
+    pub idempotency_key_ttl_secs: Option<u64>,
+    pub max_idempotency_keys: Option<usize>,
+    pub max_subscriptions: Option<u64>,
+    pub subscription_heartbeat_secs: Option<u64>,
+    pub subscription_write_timeout_secs: Option<u64>,
+    pub idle_buffer_shrink_secs: Option<u64>,
+    pub tcp_keepalive_idle_secs: Option<u64>,
+    pub tcp_keepalive_interval_secs: Option<u64>,
+    pub tcp_keepalive_probes: Option<u32>,
+    pub outbound_request_timeout: Option<u64>,
+    pub reject_requests_during_ibd: Option<bool>,
+    pub max_tracked_hosts: Option<u64>,
+    pub flapping_window_secs: Option<u64>,
+    pub flapping_min_failures: Option<u32>,
+    pub max_requests_per_second_per_host: Option<u64>,
+    pub coalesce_outbound_requests: Option<bool>,
+    pub max_request_body_len: Option<u64>,
+    pub root_response: Option<String>,
+    pub trust_forwarded_for: Option<bool>,
+    pub trusted_proxies: Option<String>,
+    pub startup_grace_secs: Option<u64>,
+    pub startup_grace_multiplier: Option<u64>,
+}
+
+/// Parse an IP address or CIDR prefix (e.g. `10.0.0.0/8`) into a `PeerAddress` and the number of
+/// its leading bits to match.  IPv4 prefixes are mapped into the 16-byte `PeerAddress`, so their
+/// masks are offset by 96 bits.
+fn parse_ip_prefix(prefix: &str) -> Option<(PeerAddress, u32)> {
+    let (addr, len) = match prefix.split_once('/') {
+        Some((addr, len)) => (addr.parse::<IpAddr>().ok()?, Some(len.parse::<u32>().ok()?)),
+        None => (prefix.parse::<IpAddr>().ok()?, None),
+    };
+    let (max_len, offset) = if addr.is_ipv4() { (32, 96) } else { (128, 0) };
+    let len = len.unwrap_or(max_len);
+    if len > max_len {
+        return None;
+    }
+    Some((PeerAddress::from_ip(&addr), offset + len))
 }
 
 impl ConnectionOptionsFile {
@@ -2403,6 +2461,67 @@ impl ConnectionOptionsFile {
                     .map_err(|e| format!("Invalid connection_option.public_ip_address: {}", e))
             })
             .transpose()?;
+        let cors = self.cors_allow_origin.map(|allow_origin| {
+            let default_cors = CorsConfig::default();
+            CorsConfig {
+                allow_origin,
+                allow_methods: self
+                    .cors_allow_methods
+                    .unwrap_or(default_cors.allow_methods),
+                allow_headers: self
+                    .cors_allow_headers
+                    .unwrap_or(default_cors.allow_headers),
+            }
+        });
+        let http_banned_hosts = self
+            .http_banned_hosts
+            .map(|hosts| {
+                hosts
+                    .split(',')
+                    .map(|host| host.trim())
+                    .filter(|host| !host.is_empty())
+                    .map(|host| {
+                        host.parse().map_err(|_e| {
+                            format!(
+                                "connection_options.http_banned_hosts: invalid IP address '{host}'"
+                            )
+                        })
+                    })
+                    .collect::<Result<_, _>>()
+            })
+            .transpose()?;
+        let trusted_proxies = self
+            .trusted_proxies
+            .map(|proxies| {
+                proxies
+                    .split(',')
+                    .map(|proxy| proxy.trim())
+                    .filter(|proxy| !proxy.is_empty())
+                    .map(|proxy| {
+                        parse_ip_prefix(proxy).ok_or_else(|| {
+                            format!(
+                                "connection_options.trusted_proxies: invalid IP address or CIDR prefix '{proxy}'"
+                            )
+                        })
+                    })
+                    .collect::<Result<_, _>>()
+            })
+            .transpose()?;
+        let tls = match (self.tls_cert_path, self.tls_key_path) {
+            (Some(cert_path), Some(key_path)) => Some(TlsConfig {
+                cert_path,
+                key_path,
+                client_ca_path: self.tls_client_ca_path,
+                require_client_cert: self.tls_require_client_cert.unwrap_or(false),
+            }),
+            (None, None) => None,
+            _ => {
+                return Err(
+                    "connection_options.tls_cert_path and connection_options.tls_key_path must be given together"
+                        .to_string(),
+                );
+            }
+        };
         let mut read_only_call_limit = HELIUM_DEFAULT_CONNECTION_OPTIONS
             .read_only_call_limit
             .clone();
@@ -2516,6 +2635,98 @@ impl ConnectionOptionsFile {
             private_neighbors: self.private_neighbors.unwrap_or(true),
             block_proposal_token: self.block_proposal_token,
             antientropy_retry: self.antientropy_retry.unwrap_or(default.antientropy_retry),
+            max_connecting_lifetime: self
+                .max_connecting_lifetime
+                .unwrap_or(default.max_connecting_lifetime),
+            max_sockets_per_poll: self
+                .max_sockets_per_poll
+                .unwrap_or(default.max_sockets_per_poll),
+            max_outstanding_outbound_requests: self
+                .max_outstanding_outbound_requests
+                .unwrap_or(default.max_outstanding_outbound_requests),
+            log_bad_requests: self.log_bad_requests.unwrap_or(default.log_bad_requests),
+            tls,
+            auth_token: self.auth_token,
+            privileged_endpoints: self
+                .privileged_endpoints
+                .map(|endpoints| {
+                    endpoints
+                        .split(',')
+                        .map(|endpoint| endpoint.trim().to_string())
+                        .filter(|endpoint| !endpoint.is_empty())
+                        .collect()
+                })
+                .unwrap_or(default.privileged_endpoints),
+            enable_metrics_endpoint: self
+                .enable_metrics_endpoint
+                .unwrap_or(default.enable_metrics_endpoint),
+            max_work_per_run: self.max_work_per_run.unwrap_or(default.max_work_per_run),
+            close_linger_ms: self.close_linger_ms.unwrap_or(default.close_linger_ms),
+            cors,
+            max_accepts_per_sec: self
+                .max_accepts_per_sec
+                .unwrap_or(default.max_accepts_per_sec),
+            privileged_client_subjects: self
+                .privileged_client_subjects
+                .map(|subjects| subjects.into_iter().collect())
+                .unwrap_or(default.privileged_client_subjects),
+            http_banned_hosts: http_banned_hosts.unwrap_or(default.http_banned_hosts),
+            idempotency_key_ttl_secs: self
+                .idempotency_key_ttl_secs
+                .unwrap_or(default.idempotency_key_ttl_secs),
+            max_idempotency_keys: self
+                .max_idempotency_keys
+                .unwrap_or(default.max_idempotency_keys),
+            max_subscriptions: self.max_subscriptions.unwrap_or(default.max_subscriptions),
+            subscription_heartbeat_secs: self
+                .subscription_heartbeat_secs
+                .unwrap_or(default.subscription_heartbeat_secs),
+            subscription_write_timeout_secs: self
+                .subscription_write_timeout_secs
+                .unwrap_or(default.subscription_write_timeout_secs),
+            idle_buffer_shrink_secs: self
+                .idle_buffer_shrink_secs
+                .unwrap_or(default.idle_buffer_shrink_secs),
+            tcp_keepalive_idle_secs: self
+                .tcp_keepalive_idle_secs
+                .unwrap_or(default.tcp_keepalive_idle_secs),
+            tcp_keepalive_interval_secs: self
+                .tcp_keepalive_interval_secs
+                .unwrap_or(default.tcp_keepalive_interval_secs),
+            tcp_keepalive_probes: self
+                .tcp_keepalive_probes
+                .unwrap_or(default.tcp_keepalive_probes),
+            outbound_request_timeout: self
+                .outbound_request_timeout
+                .unwrap_or(default.outbound_request_timeout),
+            reject_requests_during_ibd: self
+                .reject_requests_during_ibd
+                .unwrap_or(default.reject_requests_during_ibd),
+            max_tracked_hosts: self.max_tracked_hosts.unwrap_or(default.max_tracked_hosts),
+            flapping_window_secs: self
+                .flapping_window_secs
+                .unwrap_or(default.flapping_window_secs),
+            flapping_min_failures: self
+                .flapping_min_failures
+                .unwrap_or(default.flapping_min_failures),
+            max_requests_per_second_per_host: self
+                .max_requests_per_second_per_host
+                .unwrap_or(default.max_requests_per_second_per_host),
+            coalesce_outbound_requests: self
+                .coalesce_outbound_requests
+                .unwrap_or(default.coalesce_outbound_requests),
+            max_request_body_len: self.max_request_body_len.or(default.max_request_body_len),
+            root_response: self.root_response,
+            trust_forwarded_for: self
+                .trust_forwarded_for
+                .unwrap_or(default.trust_forwarded_for),
+            trusted_proxies: trusted_proxies.unwrap_or(default.trusted_proxies),
+            startup_grace_secs: self
+                .startup_grace_secs
+                .unwrap_or(default.startup_grace_secs),
+            startup_grace_multiplier: self
+                .startup_grace_multiplier
+                .unwrap_or(default.startup_grace_multiplier),
             ..default
         })
     }
@@ -486,6 +486,7 @@ impl SignCoordinator {
                     SignerMessage::DkgResults { .. }
                     | SignerMessage::BlockResponse(_)
                     | SignerMessage::EncryptedSignerState(_)
+                    | SignerMessage::StateCheckpoint(_)
                     | SignerMessage::Transactions(_) => None,
                     SignerMessage::Packet(packet) => {
                         debug!("Received signers packet: {packet:?}");
@@ -1148,6 +1148,7 @@ fn stackerdb_delayed_dkg() {
                 false,
                 reward_cycle,
                 *i,
+                None,
             )
         })
         .collect();
@@ -1474,6 +1475,7 @@ fn stackerdb_filter_bad_transactions() {
         false,
         next_reward_cycle,
         signer_index,
+        None,
     );
 
     debug!(